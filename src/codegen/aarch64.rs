@@ -1,7 +1,12 @@
 use std::io;
 
 use crate::codegen;
-use crate::ir::{Arg, Expr, Fn, Lit};
+use crate::ir::{Arg, Expr, Fn, InnerType, Lit, Op, Part, Type};
+use crate::lexer::token::Span;
+
+mod runtime;
+
+use runtime::Routine;
 
 pub struct Codegen<'prog, W> {
     // Inputs
@@ -14,6 +19,26 @@ pub struct Codegen<'prog, W> {
     string_literals: Vec<(&'prog str, &'prog str)>,
     curr_var_id: Option<&'prog str>,
     fmt_str_cpt: usize,
+    label_cpt: usize,
+    // Set when an array access or a dereference is checked, the failure routine is then
+    // emitted
+    bounds_checked: bool,
+    null_checked: bool,
+    // Set when a heap allocation is checked
+    alloc_checked: bool,
+    // Set when a Credit is read from the standard input
+    input_checked: bool,
+    // Set when a substring is extracted
+    slice_checked: bool,
+    // Set when an arithmetic operation is checked for overflow or for a zero divisor
+    overflow_checked: bool,
+    zero_checked: bool,
+    // Set when the program holds an assertion or a panic
+    panicked: bool,
+    // Source file of the function being generated, it is reported by the arithmetic checks
+    curr_file: &'prog str,
+    // Runtime routines called by the program, they are emitted after the functions
+    runtime: Vec<&'static Routine>,
 }
 
 impl<'prog, W: io::Write> Codegen<'prog, W> {
@@ -27,17 +52,25 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
             string_literals: Vec::new(),
             curr_var_id: None,
             fmt_str_cpt: 0,
+            label_cpt: 0,
+            bounds_checked: false,
+            null_checked: false,
+            alloc_checked: false,
+            input_checked: false,
+            slice_checked: false,
+            overflow_checked: false,
+            zero_checked: false,
+            panicked: false,
+            curr_file: "",
+            runtime: Vec::new(),
         }
     }
-}
 
-impl<'prog, W: io::Write> codegen::Codegen<'prog> for Codegen<'prog, W> {
-    fn generate_program<'a>(
+    /// Write the assembly of the program, `generate_program` assembles and links it
+    pub fn generate_assembly<'a>(
         &mut self,
         program: &'prog crate::ir::Program,
-        _slt: &'a crate::parser::slt::NavigableSlt<'a, 'prog>,
-        childs: &mut crate::parser::slt::ChildIterator<'a, 'prog>,
-        cmd: &mut crate::command::Cmd<'prog>,
+        slt: &'a crate::parser::slt::NavigableSlt<'a, 'prog>,
     ) -> codegen::error::Result<()> {
         gen_write!(self.writer, ".global _main\n.p2align 4\n_main:\n")?;
         gen_write!(
@@ -47,8 +80,10 @@ impl<'prog, W: io::Write> codegen::Codegen<'prog> for Codegen<'prog, W> {
         gen_write!(self.writer, "    stp x29, lr, [sp, -0x10]!\n")?;
         gen_write!(self.writer, "    mov x29, sp\n")?;
         gen_write!(self.writer, "\n")?;
+        // SAFETY: this is safe because of the semantic pass
+        let main = program.func.iter().find(|f| f.id == "galaxy").unwrap();
         gen_write!(self.writer, "    // jump to the main function\n")?;
-        gen_write!(self.writer, "    bl _galaxy\n")?;
+        gen_write!(self.writer, "    bl _{}\n", main.symbol)?;
         gen_write!(self.writer, "\n")?;
         gen_write!(
             self.writer,
@@ -59,11 +94,74 @@ impl<'prog, W: io::Write> codegen::Codegen<'prog> for Codegen<'prog, W> {
         gen_write!(self.writer, "    ret\n")?;
         gen_write!(self.writer, "\n")?;
 
-        for (func, slt) in program.func.iter().zip(childs) {
-            let mut childs = slt.childs();
-            self.generate_fn_decl(func, &slt, &mut childs)?;
+        for func in program.func.iter() {
+            // SAFETY: this is safe because the resolver gave a region to every function
+            let slt = slt.child(func.region.unwrap()).unwrap();
+            self.generate_fn_decl(func, &slt)?;
+        }
+
+        if self.bounds_checked {
+            self.generate_fail_routine(
+                "bounds",
+                "%s: line %ld: index %ld out of bounds for length %ld\\n",
+                &["x11", "x12", "x8", "x13"],
+            )?;
+        }
+
+        if self.null_checked {
+            self.generate_fail_routine(
+                "null",
+                "%s: line %ld: null pointer dereference\\n",
+                &["x11", "x12"],
+            )?;
+        }
+
+        if self.alloc_checked {
+            self.generate_fail_routine("alloc", "line %ld: out of memory\\n", &["x12"])?;
         }
 
+        if self.input_checked {
+            self.generate_fail_routine(
+                "input",
+                "line %ld: expected a Credit on the standard input\\n",
+                &["x12"],
+            )?;
+        }
+
+        if self.slice_checked {
+            self.generate_fail_routine("slice", "line %ld: substring out of bounds\\n", &["x12"])?;
+        }
+
+        if self.overflow_checked {
+            self.generate_fail_routine(
+                "overflow",
+                "%s: line %ld: arithmetic overflow\\n",
+                &["x11", "x12"],
+            )?;
+        }
+
+        if self.panicked {
+            self.generate_fail_routine("panic", "%s: line %ld: %s\\n", &["x11", "x12", "x13"])?;
+        }
+
+        if self.zero_checked {
+            self.generate_fail_routine(
+                "zero",
+                "%s: line %ld: division by zero\\n",
+                &["x11", "x12"],
+            )?;
+        }
+
+        for routine in std::mem::take(&mut self.runtime) {
+            gen_write!(self.writer, ".p2align 4\n")?;
+            gen_write!(self.writer, "{}:\n{}\n", routine.name, routine.code)?;
+            // The routines refer to their literals by label, they are not merged with the
+            // literals of the program
+            self.string_literals.extend(routine.data.iter().copied());
+        }
+
+        self.generate_globals(program, slt)?;
+
         gen_write!(self.writer, ".data\n")?;
 
         for (name, s) in self.string_literals.iter() {
@@ -75,30 +173,65 @@ impl<'prog, W: io::Write> codegen::Codegen<'prog> for Codegen<'prog, W> {
             self.writer.flush();
         }
 
-        info!("generated {}", self.c.output_path);
-        cmd_append!(cmd, "as", "-o", self.c.object_path, self.c.output_path);
-        if let Err(e) = cmd.run_and_reset() {
-            return Err(new_error!(from e));
-        }
-        cmd_append!(
-            cmd,
-            "cc",
-            "-arch",
-            "arm64",
-            "-o",
-            self.c.program_path,
-            self.c.object_path
-        );
+        Ok(())
+    }
+}
+
+/// Assemble the generated assembly of the program and link it into the program
+pub fn assemble<'prog>(
+    c: &'prog crate::compiler::Compiler<'prog>,
+    cmd: &mut crate::command::Cmd<'prog>,
+) -> codegen::error::Result<()> {
+    info!("generated {}", c.output_path);
+    cmd_append!(cmd, "as", "-o", c.object_path, c.output_path);
+    if let Err(e) = cmd.run_and_reset() {
+        return Err(new_error!(from e));
+    }
+    cmd_append!(
+        cmd,
+        "cc",
+        "-arch",
+        "arm64",
+        "-o",
+        c.program_path,
+        c.object_path
+    );
+    for lib in c.flags.libs.iter() {
+        cmd_append!(cmd, c.arena.strdup(&format!("-l{lib}")));
+    }
+    if let Err(e) = cmd.run_and_reset() {
+        return Err(new_error!(from e));
+    }
+
+    Ok(())
+}
+
+impl<'prog, W: io::Write> codegen::Codegen<'prog> for Codegen<'prog, W> {
+    fn generate_program<'a>(
+        &mut self,
+        program: &'prog crate::ir::Program,
+        slt: &'a crate::parser::slt::NavigableSlt<'a, 'prog>,
+        cmd: &mut crate::command::Cmd<'prog>,
+    ) -> codegen::error::Result<()> {
+        self.generate_assembly(program, slt)?;
+        assemble(self.c, cmd)
+    }
+
+    fn run_program(&mut self, cmd: &mut crate::command::Cmd<'prog>) -> codegen::error::Result<()> {
+        // A program without directory would be searched in the PATH
+        let program_path = if self.c.program_path.contains('/') {
+            self.c.program_path
+        } else {
+            self.arena.strdup(&format!("./{}", self.c.program_path))
+        };
+
+        cmd_append!(cmd, program_path);
         if let Err(e) = cmd.run_and_reset() {
             return Err(new_error!(from e));
         }
 
         Ok(())
     }
-
-    fn run_program(&mut self, _cmd: &mut crate::command::Cmd) -> codegen::error::Result<()> {
-        todo!()
-    }
 }
 
 impl<'prog, W: io::Write> Codegen<'prog, W> {
@@ -106,11 +239,12 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
         &mut self,
         func: &'prog Fn,
         slt: &'a crate::parser::slt::NavigableSlt<'a, 'prog>,
-        childs: &mut crate::parser::slt::ChildIterator<'a, 'prog>,
     ) -> codegen::error::Result<()> {
+        self.curr_file = func.file;
+
         // TODO: handle the stack for function call
-        gen_write!(self.writer, ".global _{}\n.p2align 4\n", func.id)?;
-        gen_write!(self.writer, "_{}:\n", func.id)?;
+        gen_write!(self.writer, ".global _{}\n.p2align 4\n", func.symbol)?;
+        gen_write!(self.writer, "_{}:\n", func.symbol)?;
         gen_write!(
             self.writer,
             "    // load link register and previous stack pointer onto the stack\n"
@@ -119,9 +253,11 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
         gen_write!(self.writer, "    mov x29, sp\n")?;
         gen_write!(self.writer, "\n")?;
 
-        let allocated_stack_size = crate::math::align_bytes(slt.variables.len() * 8, 16);
+        // Variables of disjoint blocks share the same stack slots
+        let stack_slots = slt.max_offset as usize;
+        let allocated_stack_size = crate::math::align_bytes(stack_slots * 8, 16);
 
-        if !slt.variables.is_empty() {
+        if stack_slots > 0 {
             // Allocate variables on the stack and store them
             let reg_args = func.variadic.unwrap_or(if func.args.len() > 7 {
                 7
@@ -173,14 +309,14 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
         }
 
         for expr in func.body.iter() {
-            self.generate_expr(expr, slt, childs)?;
+            self.generate_expr(expr, slt)?;
         }
 
-        if !slt.variables.is_empty() {
+        if stack_slots > 0 {
             gen_write!(
                 self.writer,
                 "    // pop the stack (deallocating {} variables)\n",
-                slt.variables.len()
+                stack_slots
             )?;
             gen_write!(self.writer, "    add sp, sp, {allocated_stack_size:#02x}\n")?;
             gen_write!(self.writer, "\n")?;
@@ -199,23 +335,745 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
         &mut self,
         stmt: &'prog Expr,
         slt: &'a crate::parser::slt::NavigableSlt<'a, 'prog>,
-        _childs: &mut crate::parser::slt::ChildIterator<'a, 'prog>,
     ) -> codegen::error::Result<()> {
         use Expr::*;
 
         match stmt {
-            Let { id, value } => self.generate_let(id, value, slt),
-            FnCall { id, args } => self.generate_fn_call(id, args, slt),
+            Let { id, value, sym, .. } => self.generate_let(id, value, sym, slt),
+            FnCall { id, args, func, .. } => {
+                // SAFETY: this is safe because of the resolver
+                let func = func.and_then(|f| slt.resolve_func(f)).unwrap();
+                self.generate_fn_call(id, func, args, slt)
+            }
+            Assign {
+                id,
+                span,
+                index,
+                field,
+                deref,
+                ops,
+                sym,
+            } => match (index, field) {
+                (Some(index), _) => self.generate_index_assign(id, *span, index, ops, sym, slt),
+                (_, Some(field)) => self.generate_field_assign(id, *span, field, ops, sym, slt),
+                _ if *deref => self.generate_deref_assign(id, *span, ops, sym, slt),
+                _ => self.generate_assign(id, *span, ops, sym, slt),
+            },
+            Print { value, .. } => {
+                // SAFETY: this is safe because of the semantic pass
+                let routine = match crate::semantic::get_arg_ty(value, slt).unwrap() {
+                    Type::Val(InnerType::Int) => &runtime::PRINT_INT,
+                    Type::Val(InnerType::Bool) => &runtime::PRINT_BOOL,
+                    Type::Val(InnerType::Char) => &runtime::PRINT_CHAR,
+                    Type::Val(InnerType::Str) | Type::Text => &runtime::PRINT_STR,
+                    _ => unreachable!(),
+                };
+
+                self.generate_arg(value, slt)?;
+                gen_write!(self.writer, "    // print x8\n")?;
+                gen_write!(self.writer, "    mov x0, x8\n")?;
+                self.call_runtime(routine)?;
+                self.write_newline()
+            }
+            Assert { span, cond, msg } => {
+                self.label_cpt += 1;
+                let label = self.label_cpt;
+
+                gen_write!(self.writer, "    // assert condition\n")?;
+                self.generate_arg(cond, slt)?;
+                gen_write!(self.writer, "    cbnz x8, Lassert_ok_{label}\n")?;
+                let msg = match msg {
+                    Some(msg) => self.arena.strdup(&format!("assertion failed: {msg}")),
+                    None => "assertion failed",
+                };
+                self.generate_panic(*span, msg)?;
+                gen_write!(self.writer, "Lassert_ok_{label}:\n")?;
+                self.write_newline()
+            }
+            Panic { span, msg } => {
+                gen_write!(self.writer, "    // panic\n")?;
+                self.generate_panic(*span, msg.unwrap_or("explicit panic"))?;
+                self.write_newline()
+            }
+            Free { id, sym, .. } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+                self.label_cpt += 1;
+                let label = self.label_cpt;
+
+                gen_write!(self.writer, "    // let go of {id}\n")?;
+                self.load_var(var, "x0")?;
+                gen_write!(self.writer, "    cbz x0, Lfree_end_{label}\n")?;
+                // The block starts at the capacity
+                gen_write!(self.writer, "    sub x0, x0, #16\n")?;
+                gen_write!(self.writer, "    bl _free\n")?;
+                gen_write!(self.writer, "Lfree_end_{label}:\n")?;
+                self.store_var(var, "xzr")?;
+                self.write_newline()
+            }
+            If {
+                cond,
+                body,
+                else_body,
+                ..
+            } => {
+                self.label_cpt += 1;
+                let label = self.label_cpt;
+
+                gen_write!(self.writer, "    // if condition\n")?;
+                self.generate_arg(cond, slt)?;
+                gen_write!(self.writer, "    cbz x8, Lelse_{label}\n")?;
+                self.write_newline()?;
+
+                for expr in body {
+                    self.generate_expr(expr, slt)?;
+                }
+
+                gen_write!(self.writer, "    b Lif_end_{label}\n")?;
+                gen_write!(self.writer, "Lelse_{label}:\n")?;
+
+                if let Some(else_body) = else_body {
+                    for expr in else_body {
+                        self.generate_expr(expr, slt)?;
+                    }
+                }
+
+                gen_write!(self.writer, "Lif_end_{label}:\n")?;
+                self.write_newline()
+            }
+            While { cond, body, .. } => {
+                self.label_cpt += 1;
+                let label = self.label_cpt;
+
+                gen_write!(self.writer, "Lwhile_{label}:\n")?;
+                gen_write!(self.writer, "    // while condition\n")?;
+                self.generate_arg(cond, slt)?;
+                gen_write!(self.writer, "    cbz x8, Lwhile_end_{label}\n")?;
+                self.write_newline()?;
+
+                for expr in body {
+                    self.generate_expr(expr, slt)?;
+                }
+
+                gen_write!(self.writer, "    b Lwhile_{label}\n")?;
+                gen_write!(self.writer, "Lwhile_end_{label}:\n")?;
+                self.write_newline()
+            }
+        }
+    }
+
+    fn generate_assign<'a>(
+        &mut self,
+        id: &'prog str,
+        span: Span,
+        ops: &'prog [(Op, Arg)],
+        sym: &Option<crate::parser::slt::SymbolId<'prog>>,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        // SAFETY: this is safe because of the resolver
+        let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+        gen_write!(self.writer, "    // load var {id} into x10\n")?;
+        self.load_var(var, "x10")?;
+        self.write_newline()?;
+
+        self.generate_ops(id, span, var.ty, ops, slt)?;
+
+        gen_write!(self.writer, "    // store x10 into var {id}\n")?;
+        self.store_var(var, "x10")?;
+        self.write_newline()
+    }
+
+    /// Assign an element of an array, the address of the element is kept in x13 while the
+    /// operations are applied
+    fn generate_index_assign<'a>(
+        &mut self,
+        id: &'prog str,
+        span: Span,
+        index: &'prog Arg,
+        ops: &'prog [(Op, Arg)],
+        sym: &Option<crate::parser::slt::SymbolId<'prog>>,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        // SAFETY: this is safe because of the resolver
+        let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+        self.generate_arg(index, slt)?;
+        self.checked_array_base(id, var, span)?;
+        self.bounds_check(span)?;
+
+        gen_write!(self.writer, "    // load element of {id} into x10\n")?;
+        gen_write!(self.writer, "    add x13, x9, x8, lsl #3\n")?;
+        gen_write!(self.writer, "    ldr x10, [x13]\n")?;
+        self.write_newline()?;
+
+        // SAFETY: this is safe because of the semantic pass
+        let elem = Type::Val(var.ty.elem().unwrap());
+        self.generate_ops(id, span, elem, ops, slt)?;
+
+        gen_write!(self.writer, "    // store x10 into element of {id}\n")?;
+        gen_write!(self.writer, "    str x10, [x13]\n")?;
+        self.write_newline()
+    }
+
+    /// Assign a field of a record, the address of the record is kept in x13 while the
+    /// operations are applied
+    fn generate_field_assign<'a>(
+        &mut self,
+        id: &'prog str,
+        span: Span,
+        field: &'prog str,
+        ops: &'prog [(Op, Arg)],
+        sym: &Option<crate::parser::slt::SymbolId<'prog>>,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        // SAFETY: this is safe because of the resolver
+        let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+        // SAFETY: this is safe because of the semantic pass
+        let record = var.ty.record().and_then(|r| slt.find_record(r)).unwrap();
+        let field = record.get_field(field).unwrap();
+
+        self.record_base(id, var, "x13")?;
+        gen_write!(
+            self.writer,
+            "    // load field {} of {id} into x10\n",
+            field.id
+        )?;
+        self.access_field("ldr", field, 10, "x13")?;
+        self.write_newline()?;
+
+        self.generate_ops(id, span, Type::Val(field.ty), ops, slt)?;
+
+        gen_write!(
+            self.writer,
+            "    // store x10 into field {} of {id}\n",
+            field.id
+        )?;
+        self.access_field("str", field, 10, "x13")?;
+        self.write_newline()
+    }
+
+    /// Assign the value pointed by a pointer, the pointer is kept in x13 while the operations
+    /// are applied
+    fn generate_deref_assign<'a>(
+        &mut self,
+        id: &'prog str,
+        span: Span,
+        ops: &'prog [(Op, Arg)],
+        sym: &Option<crate::parser::slt::SymbolId<'prog>>,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        // SAFETY: this is safe because of the resolver
+        let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+        // SAFETY: this is safe because of the semantic pass
+        let Type::Ptr(inner) = var.ty else {
+            unreachable!()
+        };
+
+        gen_write!(self.writer, "    // load pointer {id} into x13\n")?;
+        self.load_var(var, "x13")?;
+        self.null_check(span, "x13")?;
+
+        gen_write!(self.writer, "    // load value pointed by {id} into x10\n")?;
+        self.access_sized("ldr", inner, 10, "x13", 0)?;
+        self.write_newline()?;
+
+        self.generate_ops(id, span, Type::Val(inner), ops, slt)?;
+
+        gen_write!(self.writer, "    // store x10 into value pointed by {id}\n")?;
+        self.access_sized("str", inner, 10, "x13", 0)?;
+        self.write_newline()
+    }
+
+    /// Apply the operations of an assignment to the x10 register holding a value of type `ty`
+    fn generate_ops<'a>(
+        &mut self,
+        id: &'prog str,
+        span: Span,
+        ty: Type<'prog>,
+        ops: &'prog [(Op, Arg)],
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        for (op, arg) in ops {
+            // Logical operations skip the value when x10 already decides the result
+            let short = match op {
+                Op::And => Some("cbz"),
+                Op::Or => Some("cbnz"),
+                _ => None,
+            };
+            let label = self.label_cpt + 1;
+            if let Some(branch) = short {
+                self.label_cpt = label;
+                gen_write!(self.writer, "    {branch} x10, Lshort_{label}\n")?;
+            }
+
+            let calls = self.calls_runtime(arg, slt);
+            if calls {
+                gen_write!(self.writer, "    // save x10 and x13 during the calls\n")?;
+                gen_write!(self.writer, "    stp x10, x13, [sp, -0x10]!\n")?;
+            }
+
+            self.generate_arg(arg, slt)?;
+
+            if calls {
+                gen_write!(self.writer, "    ldp x10, x13, [sp], 0x10\n")?;
+            }
+
+            gen_write!(self.writer, "    // {op} x8 to var {id}\n")?;
+            match op {
+                Op::Assign => gen_write!(self.writer, "    mov x10, x8\n")?,
+                Op::Add | Op::Sub if self.c.flags.checked_arith => {
+                    let instr = if matches!(op, Op::Add) {
+                        "adds"
+                    } else {
+                        "subs"
+                    };
+                    gen_write!(self.writer, "    {instr} x10, x10, x8\n")?;
+                    self.overflow_check(span, "b.vc")?;
+                }
+                Op::Add => gen_write!(self.writer, "    add x10, x10, x8\n")?,
+                Op::Sub => gen_write!(self.writer, "    sub x10, x10, x8\n")?,
+                Op::Mul if self.c.flags.checked_arith => {
+                    // The high bits of the product are the sign extension of the low bits
+                    // unless it overflows
+                    gen_write!(self.writer, "    smulh x11, x10, x8\n")?;
+                    gen_write!(self.writer, "    mul x10, x10, x8\n")?;
+                    gen_write!(self.writer, "    cmp x11, x10, asr #63\n")?;
+                    self.overflow_check(span, "b.eq")?;
+                }
+                Op::Mul => gen_write!(self.writer, "    mul x10, x10, x8\n")?,
+                Op::Div => {
+                    if self.c.flags.checked_arith {
+                        self.zero_check(span)?;
+                        // Only the smallest Credit divided by -1 overflows, subtracting 1
+                        // from it sets the overflow flag
+                        gen_write!(self.writer, "    cmn x8, #1\n")?;
+                        gen_write!(self.writer, "    ccmp x10, #1, #0, eq\n")?;
+                        self.overflow_check(span, "b.vc")?;
+                    }
+                    gen_write!(self.writer, "    sdiv x10, x10, x8\n")?;
+                }
+                Op::Mod => {
+                    if self.c.flags.checked_arith {
+                        self.zero_check(span)?;
+                    }
+                    gen_write!(self.writer, "    sdiv x11, x10, x8\n")?;
+                    gen_write!(self.writer, "    msub x10, x11, x8, x10\n")?;
+                }
+                Op::Eq => {
+                    gen_write!(self.writer, "    cmp x10, x8\n")?;
+                    gen_write!(self.writer, "    cset x10, eq\n")?;
+                }
+                Op::Push => {
+                    self.null_check(span, "x10")?;
+                    gen_write!(self.writer, "    mov x0, x10\n")?;
+                    gen_write!(self.writer, "    mov x1, x8\n")?;
+                    match ty {
+                        Type::Heap(_) => self.call_runtime(&runtime::HEAP_PUSH)?,
+                        Type::Text => self.call_runtime(&runtime::TEXT_PUSH)?,
+                        // The semantic pass only allows pushing onto heap values
+                        _ => unreachable!(),
+                    }
+                    self.alloc_check(span)?;
+                    gen_write!(self.writer, "    mov x10, x0\n")?;
+                }
+                Op::And | Op::Or => {
+                    gen_write!(self.writer, "    mov x10, x8\n")?;
+                    gen_write!(self.writer, "Lshort_{label}:\n")?;
+                }
+            }
+            self.write_newline()?;
+        }
+
+        Ok(())
+    }
+
+    /// Load a variable into `reg`, the globals are named after their symbol
+    fn load_var(
+        &mut self,
+        var: &crate::parser::slt::Variable,
+        reg: &str,
+    ) -> codegen::error::Result<()> {
+        if var.is_global() {
+            let id = var.id;
+            gen_write!(self.writer, "    adrp x9, __global_{id}@PAGE\n")?;
+            gen_write!(self.writer, "    ldr {reg}, [x9, __global_{id}@PAGEOFF]\n")
+        } else {
+            // Variables of nested blocks live in the stack frame of their function
+            self.access_slot("ldr", var.offset, reg)
+        }
+    }
+
+    fn store_var(
+        &mut self,
+        var: &crate::parser::slt::Variable,
+        reg: &str,
+    ) -> codegen::error::Result<()> {
+        if var.is_global() {
+            let id = var.id;
+            gen_write!(self.writer, "    adrp x9, __global_{id}@PAGE\n")?;
+            gen_write!(self.writer, "    str {reg}, [x9, __global_{id}@PAGEOFF]\n")
+        } else {
+            self.access_slot("str", var.offset, reg)
+        }
+    }
+
+    /// Load or store a stack slot, slots out of reach of an unscaled offset are addressed
+    /// through x9
+    fn access_slot(&mut self, instr: &str, offset: i32, reg: &str) -> codegen::error::Result<()> {
+        let offset = offset * 8;
+        if offset <= 0x100 {
+            gen_write!(self.writer, "    {instr} {reg}, [x29, -{offset:#02x}]\n")
+        } else {
+            gen_write!(self.writer, "    sub x9, x29, #{offset:#02x}\n")?;
+            gen_write!(self.writer, "    {instr} {reg}, [x9]\n")
+        }
+    }
+
+    /// Load the address of the first element of an array, arrays live in the stack frame
+    /// and slices hold the address of the array they reference
+    fn array_base(
+        &mut self,
+        id: &str,
+        var: &crate::parser::slt::Variable,
+        reg: &str,
+    ) -> codegen::error::Result<()> {
+        gen_write!(self.writer, "    // load address of {id} into {reg}\n")?;
+        match var.ty {
+            // The length is stored in the lowest slot, right before the first element
+            Type::Array(..) => {
+                gen_write!(
+                    self.writer,
+                    "    sub {reg}, x29, #{:#02x}\n",
+                    (var.offset - 1) * 8
+                )
+            }
+            _ => self.load_var(var, reg),
+        }
+    }
+
+    /// Load the address of the first element of an array into x9, the address held by slices
+    /// and growable arrays is checked not to be null
+    fn checked_array_base(
+        &mut self,
+        id: &str,
+        var: &crate::parser::slt::Variable,
+        span: Span,
+    ) -> codegen::error::Result<()> {
+        self.array_base(id, var, "x9")?;
+        match var.ty {
+            Type::Array(..) => Ok(()),
+            _ => self.null_check(span, "x9"),
+        }
+    }
+
+    /// Load the address of a record, records live in the stack frame and record references
+    /// hold the address of the record they reference
+    fn record_base(
+        &mut self,
+        id: &str,
+        var: &crate::parser::slt::Variable,
+        reg: &str,
+    ) -> codegen::error::Result<()> {
+        gen_write!(self.writer, "    // load address of {id} into {reg}\n")?;
+        match var.ty {
+            // The fields are stored upward from the lowest slot
+            Type::Record(_) => {
+                gen_write!(
+                    self.writer,
+                    "    sub {reg}, x29, #{:#02x}\n",
+                    var.offset * 8
+                )
+            }
+            _ => self.load_var(var, reg),
+        }
+    }
+
+    /// Load or store the register number `reg` from or to a field of the record in `base`,
+    /// the access is sized after the field type
+    fn access_field(
+        &mut self,
+        instr: &str,
+        field: &crate::parser::slt::Field,
+        reg: u8,
+        base: &str,
+    ) -> codegen::error::Result<()> {
+        self.access_sized(instr, field.ty, reg, base, field.offset)
+    }
+
+    /// Load or store the register number `reg` from or to the memory at `base + offset`,
+    /// the access is sized after the type of the value
+    fn access_sized(
+        &mut self,
+        instr: &str,
+        ty: InnerType,
+        reg: u8,
+        base: &str,
+        offset: usize,
+    ) -> codegen::error::Result<()> {
+        match ty {
+            InnerType::Int | InnerType::Str => {
+                gen_write!(
+                    self.writer,
+                    "    {instr} x{reg}, [{base}, #{offset:#02x}]\n"
+                )
+            }
+            InnerType::Char => {
+                gen_write!(
+                    self.writer,
+                    "    {instr} w{reg}, [{base}, #{offset:#02x}]\n"
+                )
+            }
+            InnerType::Bool => {
+                gen_write!(
+                    self.writer,
+                    "    {instr}b w{reg}, [{base}, #{offset:#02x}]\n"
+                )
+            }
+        }
+    }
+
+    /// Check that the index in x8 is in the bounds of the array in x9. The length is moved to
+    /// x13, the source file loaded in x11 and the line in x12 on failure. Release builds skip
+    /// the check
+    fn bounds_check(&mut self, span: Span) -> codegen::error::Result<()> {
+        if self.c.flags.release {
+            return Ok(());
+        }
+
+        self.label_cpt += 1;
+        let label = self.label_cpt;
+        self.bounds_checked = true;
+
+        gen_write!(self.writer, "    // check array bounds\n")?;
+        gen_write!(self.writer, "    ldur x11, [x9, #-8]\n")?;
+        // Negative indexes are caught by the unsigned comparison
+        gen_write!(self.writer, "    cmp x8, x11\n")?;
+        gen_write!(self.writer, "    b.lo Lbounds_ok_{label}\n")?;
+        gen_write!(self.writer, "    mov x13, x11\n")?;
+        self.load_location(span)?;
+        gen_write!(self.writer, "    b __hsl_bounds_fail\n")?;
+        gen_write!(self.writer, "Lbounds_ok_{label}:\n")
+    }
+
+    /// Check that the pointer in `reg` is not null, the source file is loaded in x11 and the
+    /// line in x12 on failure. Release builds skip the check
+    fn null_check(&mut self, span: Span, reg: &str) -> codegen::error::Result<()> {
+        if self.c.flags.release {
+            return Ok(());
+        }
+
+        self.label_cpt += 1;
+        let label = self.label_cpt;
+        self.null_checked = true;
+
+        gen_write!(self.writer, "    // check null pointer\n")?;
+        gen_write!(self.writer, "    cbnz {reg}, Lnull_ok_{label}\n")?;
+        self.load_location(span)?;
+        gen_write!(self.writer, "    b __hsl_null_fail\n")?;
+        gen_write!(self.writer, "Lnull_ok_{label}:\n")
+    }
+
+    /// Check the flags of an arithmetic operation, `branch` is taken when it did not overflow.
+    /// The source file is loaded in x11 and the line in x12 on failure
+    fn overflow_check(&mut self, span: Span, branch: &str) -> codegen::error::Result<()> {
+        self.overflow_checked = true;
+
+        gen_write!(self.writer, "    // check arithmetic overflow\n")?;
+        self.arith_check(span, branch, "overflow")
+    }
+
+    /// Check that the divisor in x8 is not zero, the source file is loaded in x11 and the line
+    /// in x12 on failure
+    fn zero_check(&mut self, span: Span) -> codegen::error::Result<()> {
+        self.zero_checked = true;
+
+        gen_write!(self.writer, "    // check division by zero\n")?;
+        self.arith_check(span, "cbnz x8,", "zero")
+    }
+
+    fn arith_check(&mut self, span: Span, branch: &str, name: &str) -> codegen::error::Result<()> {
+        self.label_cpt += 1;
+        let label = self.label_cpt;
+
+        gen_write!(self.writer, "    {branch} Larith_ok_{label}\n")?;
+        self.load_location(span)?;
+        gen_write!(self.writer, "    b __hsl_{name}_fail\n")?;
+        gen_write!(self.writer, "Larith_ok_{label}:\n")
+    }
+
+    /// Stop the program with `msg`, it is loaded in x13
+    fn generate_panic(&mut self, span: Span, msg: &'prog str) -> codegen::error::Result<()> {
+        self.panicked = true;
+
+        let msg = self.part_literal(msg);
+        self.load_location(span)?;
+        gen_write!(self.writer, "    adrp x13, {msg}@PAGE\n")?;
+        gen_write!(self.writer, "    add x13, x13, {msg}@PAGEOFF\n")?;
+        gen_write!(self.writer, "    b __hsl_panic_fail\n")
+    }
+
+    /// Load the source file of the current function in x11 and the line of `span` in x12
+    fn load_location(&mut self, span: Span) -> codegen::error::Result<()> {
+        self.fmt_str_cpt += 1;
+        let id = self
+            .arena
+            .strdup(format!("__hsl_file_{}", self.fmt_str_cpt).as_str());
+        let file = self.string_literal(id, self.curr_file);
+
+        gen_write!(self.writer, "    adrp x11, {file}@PAGE\n")?;
+        gen_write!(self.writer, "    add x11, x11, {file}@PAGEOFF\n")?;
+        self.load_line(span)
+    }
+
+    /// Load the line of `span` in x12, the lines of the spans start at 0 and the reported ones
+    /// at 1 like the errors of the compiler
+    fn load_line(&mut self, span: Span) -> codegen::error::Result<()> {
+        self.load_immediate("x12", span.line as i64 + 1)
+    }
+
+    /// Check that the heap allocation returned in x0 succeeded, the line is loaded in x12 on
+    /// failure
+    fn alloc_check(&mut self, span: Span) -> codegen::error::Result<()> {
+        self.label_cpt += 1;
+        let label = self.label_cpt;
+        self.alloc_checked = true;
+
+        gen_write!(self.writer, "    // check heap allocation\n")?;
+        gen_write!(self.writer, "    cbnz x0, Lalloc_ok_{label}\n")?;
+        self.load_line(span)?;
+        gen_write!(self.writer, "    b __hsl_alloc_fail\n")?;
+        gen_write!(self.writer, "Lalloc_ok_{label}:\n")
+    }
+
+    /// Call a runtime routine, it is emitted with the program
+    fn call_runtime(&mut self, routine: &'static Routine) -> codegen::error::Result<()> {
+        self.use_runtime(routine);
+        gen_write!(self.writer, "    bl {}\n", routine.name)
+    }
+
+    /// Emit a runtime routine and the routines it calls with the program
+    fn use_runtime(&mut self, routine: &'static Routine) {
+        if !self.runtime.iter().any(|r| r.name == routine.name) {
+            self.runtime.push(routine);
+            for dep in routine.deps {
+                self.use_runtime(dep);
+            }
+        }
+    }
+
+    /// Routine `__hsl_{name}_fail` reached when a runtime check fails, it prints `msg`
+    /// formatted with the `regs` registers on stderr and exits
+    fn generate_fail_routine(
+        &mut self,
+        name: &str,
+        msg: &'prog str,
+        regs: &[&str],
+    ) -> codegen::error::Result<()> {
+        let label = self.arena.strdup(&format!("__hsl_{name}_msg"));
+        let msg = self.string_literal(label, msg);
+
+        gen_write!(self.writer, ".p2align 4\n")?;
+        gen_write!(self.writer, "__hsl_{name}_fail:\n")?;
+        gen_write!(
+            self.writer,
+            "    // variadic arguments of dprintf are passed on the stack\n"
+        )?;
+        gen_write!(
+            self.writer,
+            "    sub sp, sp, #{:#02x}\n",
+            crate::math::align_bytes(regs.len() * 8, 16)
+        )?;
+        for (i, reg) in regs.iter().enumerate() {
+            gen_write!(self.writer, "    str {reg}, [sp, #{:#02x}]\n", i * 8)?;
+        }
+        gen_write!(self.writer, "    mov x0, #2\n")?;
+        gen_write!(self.writer, "    adrp x1, {msg}@PAGE\n")?;
+        gen_write!(self.writer, "    add x1, x1, {msg}@PAGEOFF\n")?;
+        gen_write!(self.writer, "    bl _dprintf\n")?;
+        gen_write!(self.writer, "    mov x0, #101\n")?;
+        gen_write!(self.writer, "    bl _exit\n")?;
+        self.write_newline()
+    }
+
+    /// Constant globals are placed in a read only section and mutable ones in `.data`, both
+    /// hold 8 bytes values (strings globals hold a pointer to the string literal)
+    fn generate_globals<'a>(
+        &mut self,
+        program: &'prog crate::ir::Program,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        use crate::parser::slt::Value;
+
+        for mutable in [false, true] {
+            let mut globals = program
+                .globals
+                .iter()
+                .filter(|g| g.mutable == mutable)
+                .peekable();
+
+            if globals.peek().is_none() {
+                continue;
+            }
+
+            if mutable {
+                gen_write!(self.writer, ".data\n")?;
+            } else {
+                gen_write!(self.writer, ".section __DATA,__const\n")?;
+            }
+            gen_write!(self.writer, ".p2align 3\n")?;
+
+            for global in globals {
+                // SAFETY: this is safe because of the resolver
+                let var = slt.root().get_variable(global.symbol).unwrap();
+
+                gen_write!(self.writer, "__global_{}:\n", global.symbol)?;
+                match var.value {
+                    Value::Int(i) => gen_write!(self.writer, "    .quad {i}\n")?,
+                    Value::Bool(b) => gen_write!(self.writer, "    .quad {}\n", b as u8)?,
+                    Value::Char(c) => gen_write!(self.writer, "    .quad {}\n", c as u32)?,
+                    Value::Str(s) => {
+                        let name = self
+                            .arena
+                            .strdup(&format!("__global_{}_str", global.symbol));
+                        let name = self.string_literal(name, s);
+                        gen_write!(self.writer, "    .quad {name}\n")?;
+                    }
+                    Value::None => gen_write!(self.writer, "    .quad 0\n")?,
+                }
+            }
+
+            self.write_newline()?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the label of a string literal, pushing it to the literals if needed
+    fn string_literal(&mut self, id: &'prog str, s: &'prog str) -> &'prog str {
+        // Check if a similar string literal has already been pushed so we avoid duping items
+        if let Some(name) = self
+            .string_literals
+            .iter()
+            .find(|(_, value)| *value == s)
+            .map(|(name, _)| *name)
+        {
+            name
+        } else {
+            self.string_literals.push((id, s));
+            id
         }
     }
 
     fn generate_fn_call<'a>(
         &mut self,
         id: &'prog str,
+        func: &crate::parser::slt::Fn<'prog>,
         args: &'prog [Arg],
         slt: &'a crate::parser::slt::NavigableSlt<'a, 'prog>,
     ) -> codegen::error::Result<()> {
-        let variadic = self.c.program.get_fn_variadic(id);
+        let variadic = func.variadic;
 
         // This is for macosX variadic are passes onto the stack and other arguments are passed
         // using registers x0 to x7
@@ -224,6 +1082,14 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
 
         let allocated_space = crate::math::align_bytes(stack_args * 8, 16);
 
+        // Arguments calling functions would clobber the registers of the previous ones, the
+        // register arguments are then saved on the stack until the call
+        let saved = if args.iter().any(|arg| self.calls_runtime(arg, slt)) {
+            reg_args
+        } else {
+            0
+        };
+
         gen_write!(self.writer, "    // calling {id} function\n")?;
 
         if reg_args > 0 {
@@ -237,9 +1103,14 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
             for (i, arg) in args.iter().enumerate().take(reg_args) {
                 self.generate_arg(arg, slt)?;
 
-                // Load the argument onto the associated register
-                gen_write!(self.writer, "    // load fn arguments onto x{i}\n")?;
-                gen_write!(self.writer, "    mov x{i}, x8\n")?;
+                if saved > 0 {
+                    gen_write!(self.writer, "    // save fn argument {i} onto the stack\n")?;
+                    gen_write!(self.writer, "    str x8, [sp, -0x10]!\n")?;
+                } else {
+                    // Load the argument onto the associated register
+                    gen_write!(self.writer, "    // load fn arguments onto x{i}\n")?;
+                    gen_write!(self.writer, "    mov x{i}, x8\n")?;
+                }
                 gen_write!(self.writer, "\n")?;
             }
         }
@@ -265,16 +1136,35 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
             }
         }
 
+        if saved > 0 {
+            gen_write!(
+                self.writer,
+                "    // load the saved fn arguments onto the associated registers\n"
+            )?;
+            for i in 0..saved {
+                gen_write!(
+                    self.writer,
+                    "    ldr x{i}, [sp, {:#02x}]\n",
+                    allocated_space + (saved - 1 - i) * 16
+                )?;
+            }
+            gen_write!(self.writer, "\n")?;
+        }
+
         gen_write!(self.writer, "    // jump to the function\n")?;
-        gen_write!(self.writer, "    bl _{id}\n")?;
+        gen_write!(self.writer, "    bl _{}\n", func.id)?;
         gen_write!(self.writer, "\n")?;
 
-        if stack_args > 0 {
+        if stack_args > 0 || saved > 0 {
             gen_write!(
                 self.writer,
                 "    // pop from the stack the {id} function arguments\n"
             )?;
-            gen_write!(self.writer, "    add sp, sp, {allocated_space:#02x}\n")?;
+            gen_write!(
+                self.writer,
+                "    add sp, sp, {:#02x}\n",
+                allocated_space + saved * 16
+            )?;
             gen_write!(self.writer, "\n")?;
         }
 
@@ -285,21 +1175,66 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
         &mut self,
         id: &'prog str,
         value: &'prog Arg,
+        sym: &Option<crate::parser::slt::SymbolId<'prog>>,
         slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
     ) -> codegen::error::Result<()> {
-        self.curr_var_id = Some(id);
+        // SAFETY: this is safe because of the resolver (the variable has been pushed to the slt)
+        let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+        if let Arg::Array { items, .. } = value {
+            gen_write!(
+                self.writer,
+                "    // pushing length of array {id} to the stack\n"
+            )?;
+            self.load_immediate("x8", items.len() as i64)?;
+            self.access_slot("str", var.offset, "x8")?;
+            self.write_newline()?;
+
+            for (i, item) in items.iter().enumerate() {
+                self.generate_lit(item)?;
+                gen_write!(
+                    self.writer,
+                    "    // pushing x8 (element {i} of {id} to the stack)\n"
+                )?;
+                self.access_slot("str", var.offset - 1 - i as i32, "x8")?;
+                self.write_newline()?;
+            }
+
+            return Ok(());
+        }
+
+        if let Arg::Record { fields, .. } = value {
+            // SAFETY: this is safe because of the semantic pass
+            let record = var.ty.record().and_then(|r| slt.find_record(r)).unwrap();
+
+            for (name, value) in fields {
+                let field = record.get_field(name).unwrap();
+
+                self.generate_arg(value, slt)?;
+                self.record_base(id, var, "x9")?;
+                gen_write!(
+                    self.writer,
+                    "    // pushing x8 (field {name} of {id} to the stack)\n"
+                )?;
+                self.access_field("str", field, 8, "x9")?;
+                self.write_newline()?;
+            }
+
+            return Ok(());
+        }
+
+        // Literals are named after their variable, other values may hold several literals
+        if let Arg::Lit(_) = value {
+            self.curr_var_id = Some(id);
+        }
         // Value is loaded inside the x8 register we need to store it on the stack
         self.generate_arg(value, slt)?;
-        // SAFETY: this is safe because of the parser (the variable has been pushed to the slt)
-        let var = slt.get_variable(id).unwrap();
 
         gen_write!(
             self.writer,
-            "    // pushing x8 (variable {} to the stack)\n",
-            // SAFETY: this is safe because we just populated self.curr_var_id
-            self.curr_var_id.unwrap()
+            "    // pushing x8 (variable {id} to the stack)\n"
         )?;
-        gen_write!(self.writer, "    str x8, [x29, -{:#02x}]\n", var.offset * 8)?;
+        self.store_var(var, "x8")?;
 
         self.curr_var_id = None;
         self.write_newline()
@@ -314,32 +1249,432 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
 
         match expr {
             Lit(lit) => self.generate_lit(lit),
-            Id(id) => {
-                // SAFETY: this is safe because of the semantic controls
-                let var = slt.find_variable(id).unwrap();
-                let diff = slt.scope - var.scope;
+            Id { id, sym, .. } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+                // Arrays and records are passed by reference
+                match var.ty {
+                    Type::Array(..) => {
+                        self.array_base(id, var, "x8")?;
+                        return gen_write!(self.writer, "\n");
+                    }
+                    Type::Record(_) => {
+                        self.record_base(id, var, "x8")?;
+                        return gen_write!(self.writer, "\n");
+                    }
+                    _ => (),
+                }
+
+                gen_write!(self.writer, "    // load var {} into x8\n", id)?;
+                self.load_var(var, "x8")?;
+                gen_write!(self.writer, "\n")
+            }
+            Index {
+                id,
+                span,
+                index,
+                sym,
+            } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+                self.generate_arg(index, slt)?;
+                self.checked_array_base(id, var, *span)?;
+                self.bounds_check(*span)?;
+
+                gen_write!(self.writer, "    // load element of {id} into x8\n")?;
+                gen_write!(self.writer, "    ldr x8, [x9, x8, lsl #3]\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Len { id, span, sym } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+                // Strings don't store their length
+                if var.ty == Type::Val(InnerType::Str) {
+                    gen_write!(self.writer, "    // load length of {id} into x8\n")?;
+                    self.load_var(var, "x0")?;
+                    gen_write!(self.writer, "    bl _strlen\n")?;
+                    gen_write!(self.writer, "    mov x8, x0\n")?;
+                    return gen_write!(self.writer, "\n");
+                }
+
+                self.checked_array_base(id, var, *span)?;
+                gen_write!(self.writer, "    // load length of {id} into x8\n")?;
+                gen_write!(self.writer, "    ldur x8, [x9, #-8]\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Field { id, field, sym, .. } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+                // SAFETY: this is safe because of the semantic pass
+                let record = var.ty.record().and_then(|r| slt.find_record(r)).unwrap();
+                let field = record.get_field(field).unwrap();
+
+                self.record_base(id, var, "x9")?;
+                gen_write!(
+                    self.writer,
+                    "    // load field {} of {id} into x8\n",
+                    field.id
+                )?;
+                self.access_field("ldr", field, 8, "x9")?;
+                gen_write!(self.writer, "\n")
+            }
+            AddrOf { target, .. } => self.generate_addr_of(target, slt),
+            Deref { id, span, sym } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+                // SAFETY: this is safe because of the semantic pass
+                let Type::Ptr(inner) = var.ty else {
+                    unreachable!()
+                };
+
+                gen_write!(self.writer, "    // load pointer {id} into x9\n")?;
+                self.load_var(var, "x9")?;
+                self.null_check(*span, "x9")?;
+                gen_write!(self.writer, "    // load value pointed by {id} into x8\n")?;
+                self.access_sized("ldr", inner, 8, "x9", 0)?;
+                gen_write!(self.writer, "\n")
+            }
+            Null { .. } => {
+                gen_write!(self.writer, "    // load null pointer into x8\n")?;
+                gen_write!(self.writer, "    mov x8, #0\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Alloc { span, ty, len } => {
+                self.generate_arg(len, slt)?;
+                gen_write!(
+                    self.writer,
+                    "    // allocate {} elements into x8\n",
+                    Type::Heap(*ty)
+                )?;
+                gen_write!(self.writer, "    mov x0, x8\n")?;
+                self.call_runtime(&runtime::HEAP_ALLOC)?;
+                self.alloc_check(*span)?;
+                gen_write!(self.writer, "    mov x8, x0\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Read {
+                span,
+                ty: InnerType::Str,
+            } => {
+                gen_write!(self.writer, "    // read a line into x8\n")?;
+                self.call_runtime(&runtime::READ_LINE)?;
+                self.alloc_check(*span)?;
+                gen_write!(self.writer, "    mov x8, x0\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Read { span, .. } => {
+                self.label_cpt += 1;
+                let label = self.label_cpt;
+                self.input_checked = true;
 
-                gen_write!(self.writer, "    mov x9, x29\n")?;
+                gen_write!(self.writer, "    // read a Credit into x8\n")?;
+                self.call_runtime(&runtime::READ_INT)?;
+                gen_write!(self.writer, "    cbnz x1, Linput_ok_{label}\n")?;
+                self.load_line(*span)?;
+                gen_write!(self.writer, "    b __hsl_input_fail\n")?;
+                gen_write!(self.writer, "Linput_ok_{label}:\n")?;
+                gen_write!(self.writer, "    mov x8, x0\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Cmp { op, lhs, rhs, .. } => {
+                let cond = match op {
+                    crate::ir::Cmp::Eq => "eq",
+                    crate::ir::Cmp::Lt => "lt",
+                    crate::ir::Cmp::Gt => "gt",
+                };
 
-                if diff > 0 {
+                if crate::semantic::get_arg_ty(lhs, slt).is_some_and(|ty| ty.is_str()) {
+                    self.generate_operands(lhs, rhs, slt)?;
                     gen_write!(
                         self.writer,
-                        "    // climb up the stack calls to get to {} scope\n",
-                        id
+                        "    // compare the strings x0 and x1 into x8\n"
                     )?;
+                    gen_write!(self.writer, "    bl _strcmp\n")?;
+                    gen_write!(self.writer, "    cmp w0, #0\n")?;
+                } else {
+                    // Keep the argument registers untouched, see `calls_runtime`
+                    self.generate_arg(lhs, slt)?;
+                    gen_write!(self.writer, "    str x8, [sp, -0x10]!\n")?;
+                    self.generate_arg(rhs, slt)?;
+                    gen_write!(self.writer, "    ldr x9, [sp], 0x10\n")?;
+                    gen_write!(self.writer, "    // compare x9 and x8 into x8\n")?;
+                    gen_write!(self.writer, "    cmp x9, x8\n")?;
                 }
+                gen_write!(self.writer, "    cset x8, {cond}\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Logic { op, lhs, rhs, .. } => {
+                self.label_cpt += 1;
+                let label = self.label_cpt;
+                let branch = match op {
+                    crate::ir::Logic::And => "cbz",
+                    crate::ir::Logic::Or => "cbnz",
+                };
 
-                for _ in 0..diff {
-                    gen_write!(self.writer, "    ldr x9, [x29]\n")?;
-                }
+                self.generate_arg(lhs, slt)?;
+                gen_write!(
+                    self.writer,
+                    "    // {op}, skip the right operand when x8 decides the result\n"
+                )?;
+                gen_write!(self.writer, "    {branch} x8, Lshort_{label}\n")?;
+                self.generate_arg(rhs, slt)?;
+                gen_write!(self.writer, "Lshort_{label}:\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Not { arg, .. } => {
+                self.generate_arg(arg, slt)?;
+                gen_write!(self.writer, "    // not x8\n")?;
+                gen_write!(self.writer, "    eor x8, x8, #1\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Concat { span, lhs, rhs } => {
+                self.generate_operands(lhs, rhs, slt)?;
+                gen_write!(self.writer, "    // concatenate x0 and x1 into x8\n")?;
+                self.call_runtime(&runtime::TEXT_CONCAT)?;
+                self.alloc_check(*span)?;
+                gen_write!(self.writer, "    mov x8, x0\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Substr {
+                span,
+                text,
+                start,
+                len,
+            } => {
+                self.generate_arg(text, slt)?;
+                gen_write!(self.writer, "    str x8, [sp, -0x10]!\n")?;
+                self.generate_arg(start, slt)?;
+                gen_write!(self.writer, "    str x8, [sp, -0x10]!\n")?;
+                self.generate_arg(len, slt)?;
 
-                self.write_newline()?;
+                self.label_cpt += 1;
+                let label = self.label_cpt;
+                self.slice_checked = true;
 
-                gen_write!(self.writer, "    // load var {} into x8\n", id)?;
-                gen_write!(self.writer, "    ldr x8, [x9, -{:#02x}]\n", var.offset * 8)?;
+                gen_write!(self.writer, "    // extract a substring into x8\n")?;
+                gen_write!(self.writer, "    mov x2, x8\n")?;
+                gen_write!(self.writer, "    ldr x1, [sp], 0x10\n")?;
+                gen_write!(self.writer, "    ldr x0, [sp], 0x10\n")?;
+                self.call_runtime(&runtime::TEXT_SLICE)?;
+                gen_write!(self.writer, "    cbnz x1, Lslice_ok_{label}\n")?;
+                self.load_line(*span)?;
+                gen_write!(self.writer, "    b __hsl_slice_fail\n")?;
+                gen_write!(self.writer, "Lslice_ok_{label}:\n")?;
+                self.alloc_check(*span)?;
+                gen_write!(self.writer, "    mov x8, x0\n")?;
+                gen_write!(self.writer, "\n")
+            }
+            Interp { span, parts } => self.generate_interp(*span, parts, slt),
+            Text { span, init } => {
+                self.generate_arg(init, slt)?;
+                gen_write!(self.writer, "    // copy x8 into a dynamic string\n")?;
+                gen_write!(self.writer, "    mov x0, x8\n")?;
+                self.call_runtime(&runtime::TEXT_NEW)?;
+                self.alloc_check(*span)?;
+                gen_write!(self.writer, "    mov x8, x0\n")?;
                 gen_write!(self.writer, "\n")
             }
+            // The semantic pass only allows array literals and record constructions as the
+            // value of a let statement
+            Array { .. } | Record { .. } => unreachable!(),
+        }
+    }
+
+    /// Load the operands of a binary operation into x0 and x1, the first one is saved on the
+    /// stack while the second one is evaluated
+    fn generate_operands<'a>(
+        &mut self,
+        lhs: &'prog Arg,
+        rhs: &'prog Arg,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        self.generate_arg(lhs, slt)?;
+        gen_write!(self.writer, "    str x8, [sp, -0x10]!\n")?;
+        self.generate_arg(rhs, slt)?;
+        gen_write!(self.writer, "    mov x1, x8\n")?;
+        gen_write!(self.writer, "    ldr x0, [sp], 0x10\n")
+    }
+
+    /// Build an interpolated string into x8, the string being built is kept on the stack
+    /// while the parts are evaluated
+    fn generate_interp<'a>(
+        &mut self,
+        span: Span,
+        parts: &'prog [Part],
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        let (first, parts) = match parts {
+            [Part::Str(s), parts @ ..] => (*s, parts),
+            parts => ("", parts),
+        };
+
+        gen_write!(self.writer, "    // build an interpolated string\n")?;
+        let first = self.part_literal(first);
+        gen_write!(self.writer, "    adrp x0, {first}@PAGE\n")?;
+        gen_write!(self.writer, "    add x0, x0, {first}@PAGEOFF\n")?;
+        self.call_runtime(&runtime::TEXT_NEW)?;
+        self.alloc_check(span)?;
+        gen_write!(self.writer, "    str x0, [sp, -0x10]!\n")?;
+        self.write_newline()?;
+
+        for part in parts {
+            let arg = match part {
+                Part::Str(s) => {
+                    let lit = self.part_literal(s);
+                    gen_write!(self.writer, "    // append {lit} to the string\n")?;
+                    gen_write!(self.writer, "    adrp x1, {lit}@PAGE\n")?;
+                    gen_write!(self.writer, "    add x1, x1, {lit}@PAGEOFF\n")?;
+                    gen_write!(self.writer, "    ldr x0, [sp]\n")?;
+                    self.call_runtime(&runtime::TEXT_PUSH)?;
+                    self.alloc_check(span)?;
+                    gen_write!(self.writer, "    str x0, [sp]\n")?;
+                    self.write_newline()?;
+                    continue;
+                }
+                Part::Arg(arg) => arg,
+            };
+
+            self.generate_arg(arg, slt)?;
+            gen_write!(self.writer, "    // append x8 to the string\n")?;
+            gen_write!(self.writer, "    mov x1, x8\n")?;
+            gen_write!(self.writer, "    ldr x0, [sp]\n")?;
+
+            // SAFETY: this is safe because of the semantic pass
+            match crate::semantic::get_arg_ty(arg, slt).unwrap() {
+                Type::Val(InnerType::Int) => {
+                    gen_write!(self.writer, "    adrp x2, __hsl_text_fmt_int@PAGE\n")?;
+                    gen_write!(self.writer, "    add x2, x2, __hsl_text_fmt_int@PAGEOFF\n")?;
+                    self.call_runtime(&runtime::TEXT_PUSH_FMT)?;
+                }
+                Type::Val(InnerType::Char) => {
+                    gen_write!(self.writer, "    adrp x2, __hsl_text_fmt_char@PAGE\n")?;
+                    gen_write!(self.writer, "    add x2, x2, __hsl_text_fmt_char@PAGEOFF\n")?;
+                    self.call_runtime(&runtime::TEXT_PUSH_FMT)?;
+                }
+                Type::Val(InnerType::Bool) => self.call_runtime(&runtime::TEXT_PUSH_BOOL)?,
+                _ => self.call_runtime(&runtime::TEXT_PUSH)?,
+            }
+
+            self.alloc_check(span)?;
+            gen_write!(self.writer, "    str x0, [sp]\n")?;
+            self.write_newline()?;
+        }
+
+        gen_write!(self.writer, "    ldr x8, [sp], 0x10\n")?;
+        self.write_newline()
+    }
+
+    /// Get the label of a literal piece of an interpolated string
+    fn part_literal(&mut self, s: &'prog str) -> &'prog str {
+        self.fmt_str_cpt += 1;
+        let id = self
+            .arena
+            .strdup(format!("__lit_{}", self.fmt_str_cpt).as_str());
+        self.string_literal(id, s)
+    }
+
+    /// Tell if evaluating an expression calls a function, the registers that are not saved
+    /// by the callee are then clobbered
+    fn calls_runtime<'a>(
+        &self,
+        arg: &Arg,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> bool {
+        match arg {
+            Arg::Len { sym, .. } => sym
+                .and_then(|s| slt.resolve_variable(s))
+                .is_some_and(|var| var.ty == Type::Val(InnerType::Str)),
+            Arg::Index { index, .. } => self.calls_runtime(index, slt),
+            Arg::AddrOf { target, .. } | Arg::Not { arg: target, .. } => {
+                self.calls_runtime(target, slt)
+            }
+            // Only strings are compared through the C library
+            Arg::Cmp { lhs, rhs, .. }
+                if !crate::semantic::get_arg_ty(lhs, slt).is_some_and(|ty| ty.is_str()) =>
+            {
+                self.calls_runtime(lhs, slt) || self.calls_runtime(rhs, slt)
+            }
+            Arg::Logic { lhs, rhs, .. } => {
+                self.calls_runtime(lhs, slt) || self.calls_runtime(rhs, slt)
+            }
+            Arg::Lit(_)
+            | Arg::Id { .. }
+            | Arg::Field { .. }
+            | Arg::Deref { .. }
+            | Arg::Null { .. }
+            | Arg::Array { .. }
+            | Arg::Record { .. } => false,
+            Arg::Alloc { .. }
+            | Arg::Text { .. }
+            | Arg::Read { .. }
+            | Arg::Cmp { .. }
+            | Arg::Concat { .. }
+            | Arg::Substr { .. }
+            | Arg::Interp { .. } => true,
+        }
+    }
+
+    /// Load the address of a variable, an array element or a record field into x8
+    fn generate_addr_of<'a>(
+        &mut self,
+        target: &'prog Arg,
+        slt: &crate::parser::slt::NavigableSlt<'a, 'prog>,
+    ) -> codegen::error::Result<()> {
+        match target {
+            Arg::Id { id, sym, .. } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+                gen_write!(self.writer, "    // load address of {id} into x8\n")?;
+                if var.is_global() {
+                    let symbol = var.id;
+                    gen_write!(self.writer, "    adrp x8, __global_{symbol}@PAGE\n")?;
+                    gen_write!(self.writer, "    add x8, x8, __global_{symbol}@PAGEOFF\n")?;
+                } else {
+                    gen_write!(self.writer, "    sub x8, x29, #{:#02x}\n", var.offset * 8)?;
+                }
+            }
+            Arg::Index {
+                id,
+                span,
+                index,
+                sym,
+            } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+
+                self.generate_arg(index, slt)?;
+                self.checked_array_base(id, var, *span)?;
+                self.bounds_check(*span)?;
+                gen_write!(
+                    self.writer,
+                    "    // load address of element of {id} into x8\n"
+                )?;
+                gen_write!(self.writer, "    add x8, x9, x8, lsl #3\n")?;
+            }
+            Arg::Field { id, field, sym, .. } => {
+                // SAFETY: this is safe because of the resolver
+                let var = sym.and_then(|s| slt.resolve_variable(s)).unwrap();
+                // SAFETY: this is safe because of the semantic pass
+                let record = var.ty.record().and_then(|r| slt.find_record(r)).unwrap();
+                let field = record.get_field(field).unwrap();
+
+                self.record_base(id, var, "x9")?;
+                gen_write!(
+                    self.writer,
+                    "    // load address of field {} of {id} into x8\n",
+                    field.id
+                )?;
+                gen_write!(self.writer, "    add x8, x9, #{:#02x}\n", field.offset)?;
+            }
+            // The semantic pass only allows the address of variables, elements and fields
+            _ => unreachable!(),
         }
+
+        self.write_newline()
     }
 
     fn generate_lit(&mut self, lit: &'prog Lit) -> codegen::error::Result<()> {
@@ -354,21 +1689,10 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
         match lit {
             Int(val) => {
                 gen_write!(self.writer, "    // pushing variable {} to x8\n", curr_id)?;
-                gen_write!(self.writer, "    mov x8, #{}\n", val)?;
+                self.load_immediate("x8", *val)?;
             }
             Str(s) => {
-                // Check if a similar string literal has already been pushed so we avoid duping items
-                let lit_str_id = if let Some(name) = self
-                    .string_literals
-                    .iter()
-                    .find(|(_, value)| value == s)
-                    .map(|(value, _)| *value)
-                {
-                    name
-                } else {
-                    self.string_literals.push((curr_id, s));
-                    curr_id
-                };
+                let lit_str_id = self.string_literal(curr_id, s);
 
                 gen_write!(
                     self.writer,
@@ -382,12 +1706,195 @@ impl<'prog, W: io::Write> Codegen<'prog, W> {
                 gen_write!(self.writer, "    // pushing variable {} to x8\n", curr_id)?;
                 gen_write!(self.writer, "    mov x8, #{}\n", *b as u8)?;
             }
+            Char(c) => {
+                gen_write!(
+                    self.writer,
+                    "    // pushing variable {} to x8 ({c:?})\n",
+                    curr_id
+                )?;
+                self.load_immediate("x8", *c as i64)?;
+            }
         }
 
         self.write_newline()
     }
 
+    /// Load a 64 bits immediate, values that don't fit in a single `mov` are loaded 16 bits
+    /// at a time
+    fn load_immediate(&mut self, reg: &str, value: i64) -> codegen::error::Result<()> {
+        if (-0x10000..0x10000).contains(&value) {
+            return gen_write!(self.writer, "    mov {reg}, #{value}\n");
+        }
+
+        let bits = value as u64;
+        gen_write!(self.writer, "    movz {reg}, #{:#x}\n", bits & 0xffff)?;
+        for shift in [16, 32, 48] {
+            let chunk = (bits >> shift) & 0xffff;
+            if chunk != 0 {
+                gen_write!(self.writer, "    movk {reg}, #{chunk:#x}, lsl #{shift}\n")?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn write_newline(&mut self) -> codegen::error::Result<()> {
         gen_write!(self.writer, "\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assembly of the functions of the program in `source` followed by its string literals
+    fn assembly(source: &str) -> String {
+        let program: std::ffi::OsString = "hsl".into();
+        let args: Vec<std::ffi::OsString> = vec!["check".into(), "checks.hsl".into()];
+        let project = std::cell::OnceCell::new();
+        let crate::flags::FlagsResult::Ok(flags) = crate::flags::Flags::parse(
+            Some(&program),
+            args.iter().map(|a| a.as_os_str()),
+            None,
+            &project,
+        ) else {
+            panic!("invalid flags");
+        };
+
+        let arena = crate::arena::Arena::new();
+        let mut c = crate::compiler::Compiler::new(&arena, flags).unwrap();
+        c.unsaved.push(("checks.hsl".into(), source.to_string()));
+        assert_eq!(c.parse_modules(), Ok(()));
+
+        let mut builder = crate::parser::slt::Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(
+            crate::resolver::resolve(&mut c.program, &mut builder, &mut slt, false),
+            0
+        );
+        let slt: crate::parser::slt::NavigableSlt = (&slt).into();
+
+        let mut codegen = Codegen::new(&c, Vec::new());
+        for func in c.program.func.iter() {
+            let slt = slt.child(func.region.unwrap()).unwrap();
+            codegen.generate_fn_decl(func, &slt).unwrap();
+        }
+        let mut asm = String::from_utf8(codegen.writer).unwrap();
+        for (name, s) in codegen.string_literals.iter() {
+            asm.push_str(&format!("    {name}:\n        .asciz \"{s}\"\n"));
+        }
+        asm
+    }
+
+    /// Lines loaded in x12 for the runtime messages
+    fn reported_lines(assembly: &str) -> Vec<&str> {
+        assembly
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("mov x12, #"))
+            .collect()
+    }
+
+    /// Messages loaded in x13 for the panics
+    fn reported_messages(assembly: &str) -> Vec<&str> {
+        let lines: Vec<_> = assembly.lines().map(str::trim).collect();
+        lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("adrp x13, ")?.strip_suffix("@PAGE"))
+            .map(|name| {
+                let label = lines.iter().position(|l| *l == format!("{name}:")).unwrap();
+                let data = lines[label + 1].strip_prefix(".asciz \"").unwrap();
+                data.strip_suffix('"').unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn assertions_report_lines_from_one() {
+        let asm = assembly(
+            "A long time ago in a galaxy far, far away...\n\
+             \x20   I am a big deal in the resistance. shields\n\
+             \x20   Who, mesa ? 3\n\
+             \x20   I have a bad feeling about this. Bigger than a Star Destroyer. shields 0 \"shields up\"\n\
+             \n\
+             \x20   It's a trap! \"the shields are down\"\n\
+             May the force be with you.\n",
+        );
+        assert_eq!(reported_lines(&asm), ["4", "6"]);
+        assert_eq!(
+            reported_messages(&asm),
+            ["assertion failed: shields up", "the shields are down"]
+        );
+    }
+
+    #[test]
+    fn runtime_checks_report_lines_from_one() {
+        let asm = assembly(
+            "A long time ago in a galaxy far, far away...\n\
+             \x20   I am a big deal in the resistance. fleet\n\
+             \x20   Who, mesa ? Lock S-foils in attack position. 12 7 Stay on target.\n\
+             \x20   I am a big deal in the resistance. shields\n\
+             \x20   Who, mesa ? What is thy bidding, my master? Credit\n\
+             \x20   What a piece of junk! shields\n\
+             \x20       Not to worry, we are still flying half a ship. shields\n\
+             \x20   The garbage will do.\n\
+             \x20   You'll find I'm full of surprises. fleet at sector shields\n\
+             May the force be with you.\n",
+        );
+        assert_eq!(reported_lines(&asm), ["5", "6", "6", "9"]);
+    }
+
+    /// Instructions emitted right before branching to the runtime routine `name`
+    fn before_fail<'a>(assembly: &'a str, name: &str) -> Vec<&'a str> {
+        let lines: Vec<_> = assembly.lines().map(str::trim).collect();
+        let branch = format!("b __hsl_{name}_fail");
+        let end = lines.iter().position(|l| *l == branch).unwrap();
+        lines[end - 4..end].to_vec()
+    }
+
+    #[test]
+    fn bounds_checks_report_their_file() {
+        let asm = assembly(
+            "A long time ago in a galaxy far, far away...\n\
+             \x20   I am a big deal in the resistance. fleet\n\
+             \x20   Who, mesa ? Lock S-foils in attack position. 12 7 Stay on target.\n\
+             \x20   You'll find I'm full of surprises. fleet at sector 3\n\
+             May the force be with you.\n",
+        );
+        let before = before_fail(&asm, "bounds");
+        assert_eq!(before[0], "mov x13, x11");
+        assert!(before[1].starts_with("adrp x11, __hsl_file_"));
+        assert_eq!(before[3], "mov x12, #4");
+    }
+
+    #[test]
+    fn null_checks_report_their_file() {
+        let asm = assembly(
+            "A long time ago in a galaxy far, far away...\n\
+             \x20   Cargo\n\
+             \x20       Tracker Credit distance\n\
+             \x20   UnloadCargo\n\
+             \x20   You'll find I'm full of surprises. Jump to distance\n\
+             May the force be with you.\n",
+        );
+        let before = before_fail(&asm, "null");
+        assert!(before[1].starts_with("adrp x11, __hsl_file_"));
+        assert_eq!(before[3], "mov x12, #5");
+    }
+
+    #[test]
+    fn globals_are_named_after_their_module() {
+        let asm = assembly(
+            "I am a big deal in the resistance. speed\n\
+             Who, mesa ? 3\n\
+             A long time ago in a galaxy far, far away...\n\
+             \x20   What a piece of junk! speed\n\
+             \x20       Proceed with the countdown. 1\n\
+             \x20   The garbage will do.\n\
+             \x20   You'll find I'm full of surprises. speed\n\
+             May the force be with you.\n",
+        );
+        assert!(asm.contains("_checks.galaxy:"));
+        assert!(asm.contains("adrp x9, __global_checks.speed@PAGE"));
+        assert!(!asm.contains("__global_speed"));
+    }
+}
@@ -0,0 +1,423 @@
+//! Runtime routines of the aarch64 backend
+//!
+//! The routines are written in assembly and emitted in the output only when the program
+//! uses them. They rely on the C library linked by `cc` for the heap storage.
+//!
+//! Growable arrays and dynamic strings share the same layout: a block allocated by
+//! `malloc` holding the capacity and the length of the value in front of its elements. The
+//! variables hold the address of the first element so that the length is found at `-8`
+//! like for stack arrays. Dynamic strings are always null terminated, the terminator is not
+//! counted in the capacity.
+
+pub struct Routine {
+    pub name: &'static str,
+    pub code: &'static str,
+    // String literals used by the routine with their label
+    pub data: &'static [(&'static str, &'static str)],
+    // Other routines called by the routine
+    pub deps: &'static [&'static Routine],
+}
+
+/// Allocate a growable array of x0 zeroed elements, its address is returned in x0 (null if
+/// the allocation failed)
+pub const HEAP_ALLOC: Routine = Routine {
+    name: "__hsl_heap_alloc",
+    code: "    stp x29, lr, [sp, -0x20]!
+    mov x29, sp
+    str x0, [x29, #0x10]
+    // the capacity and the length are stored in front of the elements
+    add x0, x0, #2
+    mov x1, #8
+    bl _calloc
+    cbz x0, Lhsl_heap_alloc_end
+    ldr x1, [x29, #0x10]
+    stp x1, x1, [x0], #16
+Lhsl_heap_alloc_end:
+    ldp x29, lr, [sp], 0x20
+    ret
+",
+    data: &[],
+    deps: &[],
+};
+
+/// Push x1 onto the growable array x0, the storage doubles when it is full. The address of
+/// the array is returned in x0 as the storage may move (null if the allocation failed)
+pub const HEAP_PUSH: Routine = Routine {
+    name: "__hsl_heap_push",
+    code: "    stp x29, lr, [sp, -0x20]!
+    mov x29, sp
+    str x1, [x29, #0x10]
+    ldur x2, [x0, #-16]
+    ldur x3, [x0, #-8]
+    cmp x3, x2
+    b.lo Lhsl_heap_push_store
+    // grow the capacity to 2 * capacity + 4 elements
+    lsl x2, x2, #1
+    add x2, x2, #4
+    str x2, [x29, #0x18]
+    sub x0, x0, #16
+    add x1, x2, #2
+    lsl x1, x1, #3
+    bl _realloc
+    cbz x0, Lhsl_heap_push_end
+    ldr x2, [x29, #0x18]
+    str x2, [x0], #16
+    ldur x3, [x0, #-8]
+Lhsl_heap_push_store:
+    ldr x1, [x29, #0x10]
+    str x1, [x0, x3, lsl #3]
+    add x3, x3, #1
+    stur x3, [x0, #-8]
+Lhsl_heap_push_end:
+    ldp x29, lr, [sp], 0x20
+    ret
+",
+    data: &[],
+    deps: &[],
+};
+
+/// Copy the string x0 into a new dynamic string, its address is returned in x0 (null if the
+/// allocation failed)
+pub const TEXT_NEW: Routine = Routine {
+    name: "__hsl_text_new",
+    code: "    stp x29, lr, [sp, -0x20]!
+    mov x29, sp
+    str x0, [x29, #0x10]
+    bl _strlen
+    str x0, [x29, #0x18]
+    // capacity, length and null terminator
+    add x0, x0, #17
+    bl _malloc
+    cbz x0, Lhsl_text_new_end
+    ldr x2, [x29, #0x18]
+    stp x2, x2, [x0], #16
+    ldr x1, [x29, #0x10]
+    add x2, x2, #1
+    bl _memcpy
+Lhsl_text_new_end:
+    ldp x29, lr, [sp], 0x20
+    ret
+",
+    data: &[],
+    deps: &[],
+};
+
+/// Append the string x1 to the dynamic string x0, the storage grows to twice the needed
+/// length when it is full. The address of the string is returned in x0 as the storage may
+/// move (null if the allocation failed)
+pub const TEXT_PUSH: Routine = Routine {
+    name: "__hsl_text_push",
+    code: "    stp x29, lr, [sp, -0x30]!
+    mov x29, sp
+    stp x0, x1, [x29, #0x10]
+    mov x0, x1
+    bl _strlen
+    str x0, [x29, #0x20]
+    ldr x0, [x29, #0x10]
+    ldur x2, [x0, #-16]
+    ldur x3, [x0, #-8]
+    ldr x4, [x29, #0x20]
+    add x4, x3, x4
+    cmp x4, x2
+    b.ls Lhsl_text_push_copy
+    // grow the capacity to twice the needed length
+    lsl x4, x4, #1
+    str x4, [x29, #0x28]
+    sub x0, x0, #16
+    add x1, x4, #17
+    bl _realloc
+    cbz x0, Lhsl_text_push_end
+    ldr x4, [x29, #0x28]
+    str x4, [x0], #16
+    str x0, [x29, #0x10]
+Lhsl_text_push_copy:
+    // copy the suffix with its null terminator at the end of the string
+    ldur x3, [x0, #-8]
+    ldr x2, [x29, #0x20]
+    add x4, x3, x2
+    stur x4, [x0, #-8]
+    add x0, x0, x3
+    ldr x1, [x29, #0x18]
+    add x2, x2, #1
+    bl _memcpy
+    ldr x0, [x29, #0x10]
+Lhsl_text_push_end:
+    ldp x29, lr, [sp], 0x30
+    ret
+",
+    data: &[],
+    deps: &[],
+};
+
+/// Print the Credit x0 followed by a newline
+pub const PRINT_INT: Routine = Routine {
+    name: "__hsl_print_int",
+    code: "    stp x29, lr, [sp, -0x10]!
+    mov x29, sp
+    // variadic arguments of printf are passed on the stack
+    str x0, [sp, -0x10]!
+    adrp x0, __hsl_print_int_fmt@PAGE
+    add x0, x0, __hsl_print_int_fmt@PAGEOFF
+    bl _printf
+    add sp, sp, 0x10
+    ldp x29, lr, [sp], 0x10
+    ret
+",
+    data: &[("__hsl_print_int_fmt", "%ld\\n")],
+    deps: &[],
+};
+
+/// Print the Aurebesh x0 followed by a newline
+pub const PRINT_CHAR: Routine = Routine {
+    name: "__hsl_print_char",
+    code: "    stp x29, lr, [sp, -0x10]!
+    mov x29, sp
+    // variadic arguments of printf are passed on the stack
+    str x0, [sp, -0x10]!
+    adrp x0, __hsl_print_char_fmt@PAGE
+    add x0, x0, __hsl_print_char_fmt@PAGEOFF
+    bl _printf
+    add sp, sp, 0x10
+    ldp x29, lr, [sp], 0x10
+    ret
+",
+    data: &[("__hsl_print_char_fmt", "%lc\\n")],
+    deps: &[],
+};
+
+/// Print the Signal x0 as a word followed by a newline
+pub const PRINT_BOOL: Routine = Routine {
+    name: "__hsl_print_bool",
+    code: "    stp x29, lr, [sp, -0x10]!
+    mov x29, sp
+    adrp x1, __hsl_print_true@PAGE
+    add x1, x1, __hsl_print_true@PAGEOFF
+    adrp x2, __hsl_print_false@PAGE
+    add x2, x2, __hsl_print_false@PAGEOFF
+    cmp x0, #0
+    csel x0, x2, x1, eq
+    bl _puts
+    ldp x29, lr, [sp], 0x10
+    ret
+",
+    data: &[("__hsl_print_true", "true"), ("__hsl_print_false", "false")],
+    deps: &[],
+};
+
+/// Print the Holotext x0 followed by a newline
+pub const PRINT_STR: Routine = Routine {
+    name: "__hsl_print_str",
+    code: "    stp x29, lr, [sp, -0x10]!
+    mov x29, sp
+    bl _puts
+    ldp x29, lr, [sp], 0x10
+    ret
+",
+    data: &[],
+    deps: &[],
+};
+
+/// Read a line of the standard input into a dynamic string without its newline, the end of
+/// the input reads an empty line. The address of the string is returned in x0 (null if the
+/// allocation failed)
+pub const READ_LINE: Routine = Routine {
+    name: "__hsl_read_line",
+    code: "    stp x29, lr, [sp, -0x20]!
+    mov x29, sp
+    // buffer and capacity allocated by getline
+    stp xzr, xzr, [x29, #0x10]
+    add x0, x29, #0x10
+    add x1, x29, #0x18
+    adrp x2, ___stdinp@GOTPAGE
+    ldr x2, [x2, ___stdinp@GOTPAGEOFF]
+    ldr x2, [x2]
+    bl _getline
+    cmp x0, #0
+    b.gt Lhsl_read_line_strip
+    adrp x0, __hsl_read_line_empty@PAGE
+    add x0, x0, __hsl_read_line_empty@PAGEOFF
+    bl __hsl_text_new
+    b Lhsl_read_line_free
+Lhsl_read_line_strip:
+    ldr x1, [x29, #0x10]
+    sub x0, x0, #1
+    ldrb w2, [x1, x0]
+    cmp w2, #10
+    b.ne Lhsl_read_line_copy
+    strb wzr, [x1, x0]
+Lhsl_read_line_copy:
+    mov x0, x1
+    bl __hsl_text_new
+Lhsl_read_line_free:
+    str x0, [x29, #0x18]
+    ldr x0, [x29, #0x10]
+    bl _free
+    ldr x0, [x29, #0x18]
+    ldp x29, lr, [sp], 0x20
+    ret
+",
+    data: &[("__hsl_read_line_empty", "")],
+    deps: &[&TEXT_NEW],
+};
+
+/// Read a line of the standard input holding a Credit, the value is returned in x0 and x1
+/// is set to 0 if the line is not a Credit (spaces around the number are allowed)
+pub const READ_INT: Routine = Routine {
+    name: "__hsl_read_int",
+    code: "    stp x29, lr, [sp, -0x40]!
+    mov x29, sp
+    // buffer and capacity allocated by getline, end of the number, value and validity
+    stp xzr, xzr, [x29, #0x10]
+    stp xzr, xzr, [x29, #0x28]
+    str xzr, [x29, #0x20]
+    add x0, x29, #0x10
+    add x1, x29, #0x18
+    adrp x2, ___stdinp@GOTPAGE
+    ldr x2, [x2, ___stdinp@GOTPAGEOFF]
+    ldr x2, [x2]
+    bl _getline
+    cmp x0, #0
+    b.le Lhsl_read_int_end
+    // errno is set by strtol when the number is out of range
+    bl ___error
+    str wzr, [x0]
+    ldr x0, [x29, #0x10]
+    add x1, x29, #0x20
+    mov x2, #10
+    bl _strtol
+    str x0, [x29, #0x28]
+    bl ___error
+    ldr w3, [x0]
+    cbnz w3, Lhsl_read_int_end
+    ldr x1, [x29, #0x10]
+    ldr x2, [x29, #0x20]
+    cmp x1, x2
+    b.eq Lhsl_read_int_end
+Lhsl_read_int_trailing:
+    ldrb w3, [x2], #1
+    cbz w3, Lhsl_read_int_ok
+    cmp w3, #32
+    b.eq Lhsl_read_int_trailing
+    cmp w3, #9
+    b.lo Lhsl_read_int_end
+    cmp w3, #13
+    b.ls Lhsl_read_int_trailing
+    b Lhsl_read_int_end
+Lhsl_read_int_ok:
+    mov x3, #1
+    str x3, [x29, #0x30]
+Lhsl_read_int_end:
+    ldr x0, [x29, #0x10]
+    bl _free
+    ldr x0, [x29, #0x28]
+    ldr x1, [x29, #0x30]
+    ldp x29, lr, [sp], 0x40
+    ret
+",
+    data: &[],
+    deps: &[],
+};
+
+/// Concatenate the strings x0 and x1 into a new dynamic string, its address is returned in
+/// x0 (null if the allocation failed)
+pub const TEXT_CONCAT: Routine = Routine {
+    name: "__hsl_text_concat",
+    code: "    stp x29, lr, [sp, -0x20]!
+    mov x29, sp
+    str x1, [x29, #0x10]
+    bl __hsl_text_new
+    cbz x0, Lhsl_text_concat_end
+    ldr x1, [x29, #0x10]
+    bl __hsl_text_push
+Lhsl_text_concat_end:
+    ldp x29, lr, [sp], 0x20
+    ret
+",
+    data: &[],
+    deps: &[&TEXT_NEW, &TEXT_PUSH],
+};
+
+/// Copy the x2 bytes of the string x0 starting at x1 into a new dynamic string, its address
+/// is returned in x0 (null if the allocation failed) and x1 is set to 0 if the bytes are
+/// out of the bounds of the string
+pub const TEXT_SLICE: Routine = Routine {
+    name: "__hsl_text_slice",
+    code: "    stp x29, lr, [sp, -0x30]!
+    mov x29, sp
+    stp x0, x1, [x29, #0x10]
+    str x2, [x29, #0x20]
+    bl _strlen
+    ldp x1, x2, [x29, #0x18]
+    // negative values are caught by the unsigned comparisons
+    cmp x1, x0
+    b.hi Lhsl_text_slice_fail
+    sub x0, x0, x1
+    cmp x2, x0
+    b.hi Lhsl_text_slice_fail
+    // capacity, length and null terminator
+    add x0, x2, #17
+    bl _malloc
+    cbz x0, Lhsl_text_slice_ok
+    ldr x2, [x29, #0x20]
+    stp x2, x2, [x0], #16
+    strb wzr, [x0, x2]
+    str x0, [x29, #0x28]
+    ldp x1, x3, [x29, #0x10]
+    add x1, x1, x3
+    bl _memcpy
+    ldr x0, [x29, #0x28]
+Lhsl_text_slice_ok:
+    mov x1, #1
+    b Lhsl_text_slice_end
+Lhsl_text_slice_fail:
+    mov x0, #0
+    mov x1, #0
+Lhsl_text_slice_end:
+    ldp x29, lr, [sp], 0x30
+    ret
+",
+    data: &[],
+    deps: &[],
+};
+
+/// Append the value x1 formatted with the printf format x2 to the dynamic string x0, the
+/// address of the string is returned in x0 (null if the allocation failed)
+pub const TEXT_PUSH_FMT: Routine = Routine {
+    name: "__hsl_text_push_fmt",
+    code: "    stp x29, lr, [sp, -0x40]!
+    mov x29, sp
+    str x0, [x29, #0x10]
+    // variadic arguments of snprintf are passed on the stack
+    str x1, [sp, -0x10]!
+    add x0, x29, #0x18
+    mov x1, #32
+    bl _snprintf
+    add sp, sp, 0x10
+    ldr x0, [x29, #0x10]
+    add x1, x29, #0x18
+    bl __hsl_text_push
+    ldp x29, lr, [sp], 0x40
+    ret
+",
+    data: &[
+        ("__hsl_text_fmt_int", "%ld"),
+        ("__hsl_text_fmt_char", "%lc"),
+    ],
+    deps: &[&TEXT_PUSH],
+};
+
+/// Append the Signal x1 as a word to the dynamic string x0, the address of the string is
+/// returned in x0 (null if the allocation failed)
+pub const TEXT_PUSH_BOOL: Routine = Routine {
+    name: "__hsl_text_push_bool",
+    code: "    adrp x2, __hsl_text_true@PAGE
+    add x2, x2, __hsl_text_true@PAGEOFF
+    adrp x3, __hsl_text_false@PAGE
+    add x3, x3, __hsl_text_false@PAGEOFF
+    cmp x1, #0
+    csel x1, x3, x2, eq
+    b __hsl_text_push
+",
+    data: &[("__hsl_text_true", "true"), ("__hsl_text_false", "false")],
+    deps: &[&TEXT_PUSH],
+};
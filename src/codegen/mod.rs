@@ -1,6 +1,6 @@
 use crate::command::Cmd;
 use crate::ir;
-use crate::parser::slt::{ChildIterator, NavigableSlt};
+use crate::parser::slt::NavigableSlt;
 
 #[macro_use]
 pub mod error;
@@ -15,7 +15,6 @@ pub trait Codegen<'prog> {
         &mut self,
         program: &'prog ir::Program,
         slt: &'prog NavigableSlt<'prog, 'prog>,
-        childs: &mut ChildIterator<'prog, 'prog>,
         cmd: &mut Cmd<'prog>,
     ) -> error::Result<()>;
     fn run_program(&mut self, cmd: &mut Cmd<'prog>) -> error::Result<()>;
@@ -10,6 +10,19 @@ pub struct Compiler<'prog> {
     pub program_path: &'prog str,
     pub object_path: &'prog str,
     pub output_path: &'prog str,
+
+    // Content of the files edited in the language server by canonical path, it is compiled
+    // instead of the content on disk
+    pub unsaved: Vec<(std::path::PathBuf, String)>,
+}
+
+/// Why the modules of the program could not be loaded
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    // A source file could not be read
+    Io,
+    // The number of errors found in the sources
+    Parse(usize),
 }
 
 impl<'prog> Compiler<'prog> {
@@ -20,7 +33,17 @@ impl<'prog> Compiler<'prog> {
             return None;
         }
 
-        let Some(target) = flags.target_name.and_then(crate::target::Target::by_name) else {
+        // `hsl check` and `hsl doc` generate no code, they do not need a target when the host
+        // is not one
+        let check = matches!(
+            flags.command,
+            crate::flags::Command::Check | crate::flags::Command::Doc
+        );
+        let target = match flags.target_name {
+            None if check => Some(crate::target::TARGET_ORDER[0]),
+            name => name.and_then(crate::target::Target::by_name),
+        };
+        let Some(target) = target else {
             error!("invalid target used please specify a valid target");
             eprint!("{}", flags.help_string());
             return None;
@@ -29,7 +52,8 @@ impl<'prog> Compiler<'prog> {
         let program_path =
             build_program_path(arena, flags.output_path, flags.source_files[0], target);
 
-        let (object_path, output_path) = build_object_and_output_path(arena, program_path)?;
+        // The intermediate files are never produced without code generation
+        let (object_path, output_path) = build_object_and_output_path(arena, program_path, !check)?;
 
         Some(Self {
             arena,
@@ -39,25 +63,213 @@ impl<'prog> Compiler<'prog> {
             program_path,
             object_path,
             output_path,
+            unsaved: Vec::new(),
         })
     }
 
-    pub fn program_mut(&mut self) -> &mut crate::ir::Program<'prog> {
-        &mut self.program
+    /// Parse the source files and the modules they import, each file is parsed once. Imports
+    /// are relative to the importing file, except the ones of the standard library starting
+    /// with `std/`. The errors are reported as they are found
+    pub fn parse_modules(&mut self) -> Result<(), LoadError> {
+        let mut err_cpt = 0;
+        let mut unreadable = false;
+        // Canonical paths of the modules, they tell if a file has already been loaded
+        let mut loaded = Vec::new();
+
+        for file in self.flags.source_files.clone() {
+            if self.add_module(file, None, &mut loaded).is_none() {
+                error!(
+                    "unable to find file `{file}` continuing compilation to collect more errors"
+                );
+                unreadable = true;
+            }
+        }
+
+        // Imported modules are pushed while the previous ones are parsed
+        let mut index = 0;
+        while index < self.program.modules.len() {
+            let module = &self.program.modules[index];
+            let (path, name) = (module.path, module.name);
+
+            let unsaved = self.unsaved.iter().find(|(p, _)| *p == loaded[index]);
+            let content = match (module.source, unsaved) {
+                (Some(source), _) => source.to_string(),
+                (None, Some((_, content))) => content.clone(),
+                (None, None) => match std::fs::read_to_string(path) {
+                    Ok(content) => content,
+                    Err(_) => {
+                        error!(
+                            "unable to read file `{path}` continuing compilation to collect more errors"
+                        );
+                        unreadable = true;
+                        index += 1;
+                        continue;
+                    }
+                },
+            };
+
+            crate::log::set_context(Some(path), None);
+            let mut parser = crate::parser::Parser::new(&content, self.arena);
+            parser.file = path;
+            parser.module = index;
+            parser.module_name = name;
+            parser.parse(&mut self.program);
+            err_cpt += parser.err_cpt;
+
+            let mut imports = core::mem::take(&mut parser.imports);
+            for import in imports.iter_mut() {
+                if import.path.starts_with(crate::stdlib::PREFIX) {
+                    import.module = crate::stdlib::find(import.path)
+                        .and_then(|source| self.add_module(import.path, Some(source), &mut loaded));
+                    if import.module.is_none() {
+                        error!(
+                            "line {}: unknown module `{}` of the standard library imported by `{path}`",
+                            import.span.line + 1, import.path
+                        );
+                        err_cpt += 1;
+                    }
+                    continue;
+                }
+
+                let mut target = std::path::Path::new(path)
+                    .parent()
+                    .unwrap_or(std::path::Path::new(""))
+                    .join(import.path);
+                if target.extension().is_none() {
+                    target.set_extension("hsl");
+                }
+
+                import.module = target.to_str().and_then(|target| {
+                    self.add_module(self.arena.strdup(target), None, &mut loaded)
+                });
+                if import.module.is_none() {
+                    error!(
+                        "line {}: unable to find module `{}` imported by `{path}`",
+                        import.span.line + 1,
+                        import.path
+                    );
+                    err_cpt += 1;
+                }
+            }
+            self.program.modules[index].imports = imports;
+
+            index += 1;
+        }
+
+        crate::log::set_context(None, None);
+        err_cpt += self.check_import_cycles();
+
+        if unreadable {
+            Err(LoadError::Io)
+        } else if err_cpt > 0 {
+            Err(LoadError::Parse(err_cpt))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Index of the module of a file, a new module is added unless the file has already been
+    /// loaded. Modules of the standard library are given their embedded `source` and are
+    /// identified by their import path. Returns `None` if the file does not exist
+    fn add_module(
+        &mut self,
+        path: &'prog str,
+        source: Option<&'static str>,
+        loaded: &mut Vec<std::path::PathBuf>,
+    ) -> Option<usize> {
+        // The documents of the language server may not be saved yet, their unsaved content is
+        // known by their path
+        let canonical = match source {
+            Some(_) => std::path::PathBuf::from(path),
+            None => match std::fs::canonicalize(path) {
+                Ok(canonical) => canonical,
+                Err(_) => self
+                    .unsaved
+                    .iter()
+                    .find(|(unsaved, _)| unsaved == std::path::Path::new(path))?
+                    .0
+                    .clone(),
+            },
+        };
+        if let Some(index) = loaded.iter().position(|p| *p == canonical) {
+            return Some(index);
+        }
+
+        // Module names are used in symbols, files with the same stem get distinct names
+        let stem = match source {
+            Some(_) => path,
+            None => crate::fs::get_file_stem(path).unwrap_or("module"),
+        };
+        let mut name: String = stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if self.program.modules.iter().any(|m| m.name == name) {
+            name = format!("{name}_{}", self.program.modules.len());
+        }
+
+        loaded.push(canonical);
+        self.program.modules.push(crate::ir::Module {
+            path,
+            name: self.arena.strdup(&name),
+            imports: Vec::new(),
+            source,
+        });
+        Some(self.program.modules.len() - 1)
     }
 
-    pub fn try_for_each_source_files<F, E>(&mut self, mut func: F) -> Result<(), E>
-    where
-        F: FnMut(&mut Self, &str) -> Result<(), E>,
-    {
-        let source_files = core::mem::take(&mut self.flags.source_files);
-        let res = source_files.iter().try_for_each(|f| func(self, f));
-        let _ = core::mem::replace(&mut self.flags.source_files, source_files);
-        res
+    /// Report the cycles of the import graph, a module cannot import itself even indirectly
+    fn check_import_cycles(&self) -> usize {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            New,
+            Visiting,
+            Done,
+        }
+
+        fn visit(
+            modules: &[crate::ir::Module<'_>],
+            index: usize,
+            states: &mut [State],
+            path: &mut Vec<usize>,
+        ) -> usize {
+            match states[index] {
+                State::Done => return 0,
+                State::Visiting => {
+                    let start = path.iter().position(|m| *m == index).unwrap_or(0);
+                    let cycle: Vec<_> = path[start..]
+                        .iter()
+                        .chain([&index])
+                        .map(|m| modules[*m].path)
+                        .collect();
+                    error!("import cycle detected: {}", cycle.join(" -> "));
+                    return 1;
+                }
+                State::New => (),
+            }
+
+            states[index] = State::Visiting;
+            path.push(index);
+
+            let mut err_cpt = 0;
+            for import in modules[index].imports.iter().filter_map(|i| i.module) {
+                err_cpt += visit(modules, import, states, path);
+            }
+
+            path.pop();
+            states[index] = State::Done;
+            err_cpt
+        }
+
+        let modules = &self.program.modules;
+        let mut states = vec![State::New; modules.len()];
+        (0..modules.len())
+            .map(|index| visit(modules, index, &mut states, &mut Vec::new()))
+            .sum()
     }
 }
 
-fn build_program_path<'prog>(
+pub fn build_program_path<'prog>(
     arena: &'prog Arena,
     output_path: Option<&'prog str>,
     source_file: &'prog str,
@@ -79,11 +291,52 @@ fn build_program_path<'prog>(
 fn build_object_and_output_path<'prog>(
     arena: &'prog Arena,
     program_path: &'prog str,
+    create_dirs: bool,
 ) -> Option<(&'prog str, &'prog str)> {
-    let files = crate::fs::Files::new(program_path)?;
+    let files = crate::fs::Files::new(program_path, create_dirs)?;
 
     let object_path = files.object_path.to_str()?;
     let output_path = files.output_path.to_str()?;
 
     Some((arena.strdup(object_path), arena.strdup(output_path)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_writes_nothing() {
+        let dir = std::env::temp_dir().join("hsl_check");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("main.hsl");
+        std::fs::write(
+            &file,
+            "A long time ago in a galaxy far, far away...\nMay the force be with you.\n",
+        )
+        .unwrap();
+
+        let program: std::ffi::OsString = "hsl".into();
+        let args: Vec<std::ffi::OsString> = vec!["check".into(), file.into_os_string()];
+        let project = std::cell::OnceCell::new();
+        // No target is given, the check does not need one
+        let crate::flags::FlagsResult::Ok(flags) = Flags::parse(
+            Some(&program),
+            args.iter().map(|a| a.as_os_str()),
+            None,
+            &project,
+        ) else {
+            panic!("invalid flags");
+        };
+
+        let arena = Arena::new();
+        let mut c = Compiler::new(&arena, flags).unwrap();
+        assert_eq!(c.parse_modules(), Ok(()));
+        assert_eq!(
+            std::fs::read_dir(&dir).unwrap().count(),
+            1,
+            "only the source file is in the directory"
+        );
+    }
+}
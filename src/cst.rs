@@ -0,0 +1,234 @@
+//! Concrete syntax tree of the HSL language
+//!
+//! Unlike the AST of the parser, the concrete syntax tree keeps every byte of the source: its
+//! leaves are the tokens of the trivia-preserving lexer and printing the tree gives back the
+//! source. The nodes are the blocks of the language, delimited by their opening and closing
+//! tokens. The tree is built even for invalid programs, a closing token which does not match
+//! the innermost block is kept as a token of that block.
+
+use std::fmt;
+
+use crate::lexer::token::TokenKind;
+use crate::lexer::trivia::{self, TriviaToken};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NodeKind {
+    Root,
+    Function,
+    Params,
+    Call,
+    If,
+    While,
+    Assign,
+    Extern,
+    Record,
+    New,
+    Array,
+}
+
+impl NodeKind {
+    /// The block opened by `kind`, a function starts at the first part of its declaration
+    fn opened_by(kind: TokenKind) -> Option<Self> {
+        Some(match kind {
+            T![OFnDecl1] => Self::Function,
+            T![OFnParams] => Self::Params,
+            T![OFnCall] => Self::Call,
+            T![If] => Self::If,
+            T![OWhile] => Self::While,
+            T![OAssign] => Self::Assign,
+            T![OExtrnFn] => Self::Extern,
+            T![ORecord] => Self::Record,
+            T![ONew] => Self::New,
+            T![OArray] => Self::Array,
+            _ => return None,
+        })
+    }
+
+    fn closed_by(kind: TokenKind) -> Option<Self> {
+        Some(match kind {
+            T![CFnDecl] => Self::Function,
+            T![CFnParams] => Self::Params,
+            T![CFnCall] => Self::Call,
+            T![IfEnd] => Self::If,
+            T![CWhile] => Self::While,
+            T![CAssign] => Self::Assign,
+            T![CExtrnFn] => Self::Extern,
+            T![CRecord] => Self::Record,
+            T![CNew] => Self::New,
+            T![CArray] => Self::Array,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Element<'input> {
+    Node(Node<'input>),
+    Token(TriviaToken<'input>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Node<'input> {
+    pub kind: NodeKind,
+    // The opening token comes first and the closing token, if any, last
+    pub children: Vec<Element<'input>>,
+}
+
+impl<'input> Node<'input> {
+    fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            children: Vec::new(),
+        }
+    }
+
+    /// Whether `token` is the opening or the closing token of the block, or the else of a
+    /// condition
+    pub fn is_delimiter(&self, token: &TriviaToken) -> bool {
+        let kind = token.token.kind;
+        NodeKind::opened_by(kind) == Some(self.kind)
+            || NodeKind::closed_by(kind) == Some(self.kind)
+            || (self.kind == NodeKind::If && kind == T![Else])
+    }
+
+    /// The tokens of the node and of its descendants in source order
+    pub fn tokens(&self) -> Vec<&TriviaToken<'input>> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a TriviaToken<'input>>) {
+        for child in &self.children {
+            match child {
+                Element::Node(node) => node.collect_tokens(tokens),
+                Element::Token(token) => tokens.push(token),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                Element::Node(node) => write!(f, "{node}")?,
+                Element::Token(token) => write!(f, "{token}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Build the concrete syntax tree of the source, the root ends with the `T![EOF]` token
+pub fn parse(source: &str) -> Node<'_> {
+    let mut stack = vec![Node::new(NodeKind::Root)];
+
+    for token in trivia::tokenize(source) {
+        let kind = token.token.kind;
+        if let Some(opened) = NodeKind::opened_by(kind) {
+            let mut node = Node::new(opened);
+            node.children.push(Element::Token(token));
+            stack.push(node);
+            continue;
+        }
+
+        // SAFETY: the root is never popped
+        let current = stack.last_mut().unwrap();
+        let closes = NodeKind::closed_by(kind) == Some(current.kind);
+        current.children.push(Element::Token(token));
+
+        if closes {
+            // SAFETY: the root can't be closed so there is a parent
+            let node = stack.pop().unwrap();
+            stack.last_mut().unwrap().children.push(Element::Node(node));
+        }
+    }
+
+    // Blocks left open at the end of an invalid program are attached to their parent
+    while stack.len() > 1 {
+        // SAFETY: there is a parent
+        let node = stack.pop().unwrap();
+        stack.last_mut().unwrap().children.push(Element::Node(node));
+    }
+
+    // SAFETY: the root is the last node of the stack
+    stack.pop().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples_round_trip() {
+        let examples = concat!(env!("CARGO_MANIFEST_DIR"), "/examples");
+        let mut dirs = vec![std::path::PathBuf::from(examples)];
+        let mut count = 0;
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                if path.extension().is_none_or(|ext| ext != "hsl") {
+                    continue;
+                }
+
+                let source = std::fs::read_to_string(&path).unwrap();
+                assert_eq!(parse(&source).to_string(), source, "{}", path.display());
+                count += 1;
+            }
+        }
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn invalid_sources_round_trip() {
+        for source in [
+            "",
+            "\n\n  <(-.-)> only a comment",
+            "A long time ago in a galaxy far, far away... $ é\r\n",
+            "Order executed\nExecute order printf \"unterminated",
+            "Do, or do not. There is no try. Great, kid. Don't get cocky.",
+            "x <(o.o) unterminated <(o.o) nested (o.o)>\n y",
+        ] {
+            assert_eq!(parse(source).to_string(), source);
+        }
+    }
+
+    #[test]
+    fn blocks() {
+        let source = "A long time ago in a galaxy far, far away...\n    Do, or do not. There is no try. x\n        Execute order f Order executed\n    These aren't the droids you're looking for.\n    You have failed me for the last time.\nMay the force be with you.\n";
+        let root = parse(source);
+
+        let [Element::Node(function), Element::Token(eof)] = &root.children[..] else {
+            panic!("expected a function and the end of file");
+        };
+        assert_eq!(function.kind, NodeKind::Function);
+        assert_eq!(eof.token.kind, T![EOF]);
+
+        let condition = function.children.iter().find_map(|child| match child {
+            Element::Node(node) => Some(node),
+            Element::Token(_) => None,
+        });
+        let condition = condition.unwrap();
+        assert_eq!(condition.kind, NodeKind::If);
+        assert!(matches!(
+            &condition.children[2],
+            Element::Node(Node {
+                kind: NodeKind::Call,
+                ..
+            })
+        ));
+
+        let delimiters: Vec<_> = condition
+            .tokens()
+            .into_iter()
+            .filter(|t| condition.is_delimiter(t))
+            .map(|t| t.token.kind)
+            .collect();
+        assert_eq!(delimiters, [T![If], T![Else], T![IfEnd]]);
+    }
+}
@@ -0,0 +1,364 @@
+//! Documentation generator of `hsl doc`
+//!
+//! The functions and the externs of the modules of the program, the standard library aside,
+//! are listed with their signature and the doc comments written above them. A name between
+//! backticks in a doc comment links to the documentation of that function or extern.
+
+use std::fmt::Write;
+
+use crate::ir::{InnerType, Program, Type};
+
+pub const FORMAT_NAMES: &[&str] = &["markdown", "html"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "markdown" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    pub fn file_ext(self) -> &'static str {
+        match self {
+            Self::Markdown => ".md",
+            Self::Html => ".html",
+        }
+    }
+}
+
+/// A documented function or extern
+struct Entry<'prog> {
+    name: &'prog str,
+    // Unique identifier of the entry in the generated document
+    anchor: String,
+    signature: String,
+    kind: &'static str,
+    // Number of fixed parameters of a variadic function
+    variadic: Option<usize>,
+    doc: Option<&'prog str>,
+}
+
+impl Entry<'_> {
+    fn summary(&self) -> String {
+        match self.variadic {
+            Some(1) => format!("{}, variadic after 1 fixed parameter", self.kind),
+            Some(n) => format!("{}, variadic after {n} fixed parameters", self.kind),
+            None => self.kind.to_string(),
+        }
+    }
+}
+
+/// A group of entries, the functions of a module or the externs
+struct Section<'prog> {
+    title: &'prog str,
+    entries: Vec<Entry<'prog>>,
+}
+
+/// Spelling of the type in the source
+fn spelling(ty: &Type) -> String {
+    match ty {
+        Type::Val(inner) => inner.to_string(),
+        Type::Ptr(inner) => format!("Tracker {inner}"),
+        Type::Slice(inner) | Type::Array(inner, _) | Type::Heap(inner) => {
+            format!("Squadron {inner}")
+        }
+        Type::Record(id) | Type::RecordRef(id) => id.to_string(),
+        Type::Text => InnerType::Str.to_string(),
+        ty => ty.to_string(),
+    }
+}
+
+fn signature(name: &str, params: impl Iterator<Item = String>, variadic: bool) -> String {
+    let mut params: Vec<_> = params.collect();
+    if variadic {
+        params.push("...".to_string());
+    }
+    format!("{name}({})", params.join(", "))
+}
+
+fn sections<'prog>(program: &Program<'prog>) -> Vec<Section<'prog>> {
+    let user_module = |module: usize| program.modules[module].source.is_none();
+
+    let mut sections: Vec<_> = program
+        .modules
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| user_module(*index))
+        .map(|(index, module)| Section {
+            title: module.path,
+            entries: program
+                .func
+                .iter()
+                .filter(|f| f.module == index)
+                .map(|f| Entry {
+                    name: f.id,
+                    anchor: f.symbol.to_string(),
+                    signature: signature(
+                        f.id,
+                        f.args
+                            .iter()
+                            .map(|(id, ty)| format!("{} {id}", spelling(ty))),
+                        f.variadic.is_some(),
+                    ),
+                    kind: if f.public {
+                        "public function"
+                    } else {
+                        "function"
+                    },
+                    variadic: f.variadic,
+                    doc: f.doc,
+                })
+                .collect(),
+        })
+        .collect();
+
+    // An extern may be declared by several modules, the documented declaration is kept
+    let mut externs: Vec<Entry> = Vec::new();
+    for extrn in program.extrn.iter().filter(|e| user_module(e.module)) {
+        if let Some(entry) = externs.iter_mut().find(|e| e.name == extrn.id) {
+            entry.doc = entry.doc.or(extrn.doc);
+            continue;
+        }
+        externs.push(Entry {
+            name: extrn.id,
+            anchor: format!("extern_{}", extrn.id),
+            signature: signature(
+                extrn.id,
+                extrn.args.iter().map(spelling),
+                extrn.variadic.is_some(),
+            ),
+            kind: "extern",
+            variadic: extrn.variadic,
+            doc: extrn.doc,
+        });
+    }
+    sections.push(Section {
+        title: "externs",
+        entries: externs,
+    });
+
+    sections.retain(|s| !s.entries.is_empty());
+    sections
+}
+
+/// Anchor of the entry documenting `name`, the first one declared wins
+fn anchor<'a>(sections: &'a [Section], name: &str) -> Option<&'a str> {
+    sections
+        .iter()
+        .flat_map(|s| s.entries.iter())
+        .find(|e| e.name == name)
+        .map(|e| e.anchor.as_str())
+}
+
+/// Split a doc comment into its text and its code spans, the code spans are between backticks
+fn spans(doc: &str) -> impl Iterator<Item = (bool, &str)> {
+    doc.split('`')
+        .enumerate()
+        .map(|(index, span)| (index % 2 == 1, span))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn markdown(sections: &[Section], title: &str) -> String {
+    let mut out = format!("# {title}\n\n");
+    for entry in sections.iter().flat_map(|s| s.entries.iter()) {
+        let _ = writeln!(out, "- [`{}`](#{})", entry.name, entry.anchor);
+    }
+
+    for section in sections {
+        let _ = write!(out, "\n## `{}`\n", section.title);
+        for entry in &section.entries {
+            let _ = write!(
+                out,
+                "\n### <a id=\"{}\"></a>`{}`\n\n```\n{}\n```\n\n*{}*\n",
+                entry.anchor,
+                entry.name,
+                entry.signature,
+                entry.summary()
+            );
+
+            let Some(doc) = entry.doc else {
+                continue;
+            };
+            out.push('\n');
+            for (code, span) in spans(doc) {
+                match anchor(sections, span) {
+                    Some(anchor) if code => {
+                        let _ = write!(out, "[`{span}`](#{anchor})");
+                    }
+                    _ if code => {
+                        let _ = write!(out, "`{span}`");
+                    }
+                    _ => out.push_str(span),
+                }
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+fn html(sections: &[Section], title: &str) -> String {
+    let title = escape(title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>body {{ font-family: sans-serif; max-width: 50em; margin: auto; }} \
+         pre {{ background: #eee; padding: 0.5em; }}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<ul>\n"
+    );
+    for entry in sections.iter().flat_map(|s| s.entries.iter()) {
+        let _ = writeln!(
+            out,
+            "<li><a href=\"#{}\"><code>{}</code></a></li>",
+            entry.anchor, entry.name
+        );
+    }
+    out.push_str("</ul>\n");
+
+    for section in sections {
+        let _ = writeln!(out, "<h2><code>{}</code></h2>", escape(section.title));
+        for entry in &section.entries {
+            let _ = write!(
+                out,
+                "<section id=\"{}\">\n<h3><code>{}</code></h3>\n<pre>{}</pre>\n<p><em>{}</em></p>\n",
+                entry.anchor,
+                entry.name,
+                escape(&entry.signature),
+                entry.summary()
+            );
+
+            for paragraph in entry.doc.into_iter().flat_map(|doc| doc.split("\n\n")) {
+                out.push_str("<p>");
+                for (code, span) in spans(paragraph.trim()) {
+                    match anchor(sections, span) {
+                        Some(anchor) if code => {
+                            let _ = write!(out, "<a href=\"#{anchor}\"><code>{span}</code></a>");
+                        }
+                        _ if code => {
+                            let _ = write!(out, "<code>{}</code>", escape(span));
+                        }
+                        _ => out.push_str(&escape(span)),
+                    }
+                }
+                out.push_str("</p>\n");
+            }
+            out.push_str("</section>\n");
+        }
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Generate the documentation of the program titled `title`
+pub fn generate(program: &Program, title: &str, format: Format) -> String {
+    let sections = sections(program);
+    match format {
+        Format::Markdown => markdown(&sections, title),
+        Format::Html => html(&sections, title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    const SOURCE: &str = r#"
+<(^.^)> Print the greeting `times` times, see `printf`
+Hello there!
+A long time ago in a greet far, far away...
+    Cargo
+        Holotext name
+        Credit times
+        Tracker Credit total
+    UnloadCargo
+May the force be with you.
+
+<(^.^)> Formatted print
+<(^.^)>
+<(^.^)> Returns <0 on error
+Hypersignal printf Starfield 1
+    Holotext
+Jamsignal
+
+Hypersignal printf Starfield 1
+    Holotext
+Jamsignal
+"#;
+
+    fn program<'prog>(arena: &'prog crate::arena::Arena<'prog>) -> Program<'prog> {
+        let mut program = Program::new();
+        program.modules.push(crate::ir::Module {
+            path: "main.hsl",
+            name: "main",
+            imports: Vec::new(),
+            source: None,
+        });
+        let mut parser = Parser::new(SOURCE, arena);
+        parser.parse(&mut program);
+        assert_eq!(parser.err_cpt, 0);
+        program
+    }
+
+    #[test]
+    fn markdown_signatures_and_links() {
+        let arena = crate::arena::Arena::new();
+        let program = program(&arena);
+        let doc = generate(&program, "falcon", Format::Markdown);
+
+        assert!(doc.starts_with("# falcon\n"));
+        assert!(doc.contains("greet(Holotext name, Credit times, Tracker Credit total)"));
+        assert!(doc.contains("*public function*"));
+        assert!(doc.contains("printf(Holotext, ...)"));
+        assert!(doc.contains("*extern, variadic after 1 fixed parameter*"));
+        assert!(doc.contains("the greeting `times` times, see [`printf`](#extern_printf)"));
+        assert!(doc.contains("Formatted print\n\nReturns <0 on error"));
+        assert_eq!(doc.matches("### ").count(), 2);
+    }
+
+    #[test]
+    fn doc_comments_right_above() {
+        let source = "<(^.^)> Ignored\n\n<(^.^)> Launch\n  <(^.^)>   the  ship \n<(-.-)> Note\n<(^.^)>\n<(^.^)>It flies\nHypersignal launch\n    Holotext <(^.^)> Not on its own line\nJamsignal\n<(^.^)> Too far\n\nHypersignal land\n    Holotext\nJamsignal\n<(^.^)> Speed\nI am a big deal in the resistance. speed\nWho, mesa ? 3\n";
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        let (err_cpt, records) = crate::log::capture(|| {
+            let mut parser = Parser::new(source, &arena);
+            parser.parse(&mut program);
+            parser.err_cpt
+        });
+        assert_eq!(err_cpt, 0);
+
+        assert_eq!(program.extrn[0].doc, Some("\nIt flies"));
+        assert_eq!(program.extrn[1].doc, None);
+        let messages: Vec<_> = records.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["line 17: doc comment not attached to a function or an extern"]
+        );
+    }
+
+    #[test]
+    fn html_is_escaped() {
+        let arena = crate::arena::Arena::new();
+        let program = program(&arena);
+        let doc = generate(&program, "<falcon>", Format::Html);
+
+        assert!(doc.contains("<title>&lt;falcon&gt;</title>"));
+        assert!(doc.contains("<p>Returns &lt;0 on error</p>"));
+        assert!(doc.contains("see <a href=\"#extern_printf\"><code>printf</code></a>"));
+        assert!(doc.ends_with("</html>\n"));
+    }
+}
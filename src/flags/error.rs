@@ -37,6 +37,12 @@ pub enum FlagsError {
 
     /// When an invalid program path is passed to the flags
     InvalidProgramPath,
+
+    /// When no source file is given and no project is found
+    NoProject,
+
+    /// When `new` is not given exactly one project path
+    ProjectPath,
 }
 
 impl core::fmt::Display for FlagsError {
@@ -65,6 +71,12 @@ impl core::fmt::Display for FlagsError {
                 }
             }
             Self::ArgumentNeedsValue(arg) => writeln!(f, "argument {arg} need a value"),
+            Self::NoProject => writeln!(
+                f,
+                "no source files given and no {} found, create a project with `hsl new`",
+                crate::project::MANIFEST
+            ),
+            Self::ProjectPath => writeln!(f, "expected the path of the project to create"),
             _ => Ok(()),
         }
     }
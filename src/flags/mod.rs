@@ -5,41 +5,124 @@ use error::FlagsError;
 mod error;
 mod parser;
 
+/// What the compiler is asked to do, given by the subcommand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Build,
+    Run,
+    Check,
+    Fmt,
+    Test,
+    Clean,
+    New,
+    Lsp,
+    Doc,
+}
+
+#[derive(Clone)]
 pub struct Flags<'args> {
     program_path: &'args str,
+    subcommand: &'static parser::Subcommand,
 
+    pub command: Command,
     pub output_path: Option<&'args str>,
     pub source_files: Vec<&'args str>,
     pub target_name: Option<&'args str>,
     pub quiet: bool,
-    pub run: bool,
+    pub lint_shadowing: bool,
+    // Release builds don't emit the runtime checks of debug builds
+    pub release: bool,
+    // Arithmetic overflows and divisions by zero are checked at runtime, on by default in
+    // debug builds
+    pub checked_arith: bool,
+    // Libraries given to the linker, they are declared by the manifest of a project
+    pub libs: Vec<&'args str>,
+    // Project found when no source file is given, its manifest gives the defaults
+    pub project: Option<&'args crate::project::Project>,
+    // Directory of the project created by `hsl new`
+    pub project_path: Option<&'args str>,
+    // `hsl fmt` reports the unformatted files instead of writing them
+    pub check_format: bool,
+    pub doc_format: crate::doc::Format,
 }
 
 impl<'args> Flags<'args> {
+    /// Parse the command line arguments, the first one may be a subcommand. Without a
+    /// subcommand the flat form is used, it builds the files and runs them with `--run`. When
+    /// no source file is given the project of the current directory is loaded into `project`:
+    /// its source files, its output and the defaults of the target and of the build mode come
+    /// from its manifest
     pub fn parse<I>(
         program_path: Option<&'args std::ffi::OsString>,
         args: I,
         default_target: Option<&'args str>,
+        project: &'args std::cell::OnceCell<crate::project::Project>,
     ) -> FlagsResult<'args>
     where
         I: IntoIterator<Item = &'args std::ffi::OsStr>,
     {
         use parser::{Matches, Strictness};
 
-        let Matches { flags, frees } = match ALL_ARGS.parse(args, Strictness::UseLastArgument) {
-            Ok(m) => m,
-            Err(e) => return FlagsResult::InvalidFlags(error::FlagsError::ParseError(e)),
+        let mut args = args.into_iter().peekable();
+        let subcommand = match args.peek().and_then(|arg| SUBCOMMANDS.lookup(arg)) {
+            Some(subcommand) => {
+                args.next();
+                subcommand
+            }
+            None => &FLAT,
         };
 
+        let Matches { flags, frees } =
+            match subcommand.args.parse(args, Strictness::UseLastArgument) {
+                Ok(m) => m,
+                Err(e) => return FlagsResult::InvalidFlags(error::FlagsError::ParseError(e)),
+            };
+
         let Some(program_path) = program_path.and_then(|p| p.to_str()) else {
             return FlagsResult::InvalidFlags(FlagsError::InvalidProgramPath);
         };
 
-        if let Some(help) = HelpString::deduce(program_path, &flags) {
+        if let Some(help) = HelpString::deduce(program_path, subcommand, &flags) {
             return FlagsResult::Help(help);
         }
 
-        match Self::deduce(program_path, default_target, &flags, frees) {
+        let command = match subcommand.name {
+            "build" => Command::Build,
+            "run" => Command::Run,
+            "check" => Command::Check,
+            "fmt" => Command::Fmt,
+            "test" => Command::Test,
+            "clean" => Command::Clean,
+            "new" => Command::New,
+            "lsp" => Command::Lsp,
+            "doc" => Command::Doc,
+            _ if flags.count(&RUN) > 0 => Command::Run,
+            _ => Command::Build,
+        };
+
+        // The flat form keeps compiling loose files only
+        let standalone = matches!(command, Command::New | Command::Lsp);
+        let project = if frees.is_empty() && !standalone && !subcommand.name.is_empty() {
+            let found = std::env::current_dir()
+                .ok()
+                .and_then(|dir| crate::project::Project::find(&dir));
+            match found {
+                Some(found) => Some(project.get_or_init(|| found)),
+                None => return FlagsResult::InvalidFlags(FlagsError::NoProject),
+            }
+        } else {
+            None
+        };
+
+        match Self::deduce(
+            program_path,
+            subcommand,
+            command,
+            default_target,
+            &flags,
+            frees,
+            project,
+        ) {
             Ok(p) => FlagsResult::Ok(p),
             Err(e) => FlagsResult::InvalidFlags(e),
         }
@@ -47,9 +130,12 @@ impl<'args> Flags<'args> {
 
     fn deduce(
         program_path: &'args str,
+        subcommand: &'static parser::Subcommand,
+        command: Command,
         default_target: Option<&'args str>,
         matches: &parser::MatchedFlags<'args>,
         frees: Vec<&'args std::ffi::OsStr>,
+        project: Option<&'args crate::project::Project>,
     ) -> Result<Self, FlagsError> {
         let output_path = match matches.get(&OUTPUT)? {
             Some(os_str) => Some(
@@ -57,7 +143,7 @@ impl<'args> Flags<'args> {
                     .to_str()
                     .ok_or(FlagsError::BadArgument(&OUTPUT, os_str.into()))?,
             ),
-            None => None,
+            None => project.map(|p| p.output.as_str()),
         };
 
         let target_name = match matches.get(&TARGET)? {
@@ -66,28 +152,106 @@ impl<'args> Flags<'args> {
                     .to_str()
                     .ok_or(FlagsError::BadArgument(&OUTPUT, os_str.into()))?,
             ),
-            None => default_target,
+            None => project
+                .and_then(|p| p.manifest.target.as_deref())
+                .or(default_target),
         };
 
-        let run = matches.get(&RUN)?.is_some();
-        let quiet = matches.get(&QUIET)?.is_some();
+        let quiet = matches.count(&QUIET) > 0;
+        let lint_shadowing = matches.count(&SHADOWING) > 0;
+        let release =
+            matches.count(&RELEASE) > 0 || project.is_some_and(|p| p.manifest.opt_level > 0);
+        let checked_arith = !release || matches.count(&CHECKED_ARITH) > 0;
+        let check_format = matches.count(&FMT_CHECK) > 0;
+        let doc_format = match matches.get(&DOC_FORMAT)? {
+            Some(os_str) => os_str
+                .to_str()
+                .and_then(crate::doc::Format::by_name)
+                .ok_or(FlagsError::BadArgument(&DOC_FORMAT, os_str.into()))?,
+            None => crate::doc::Format::Markdown,
+        };
 
-        Ok(Self {
-            program_path,
-            output_path,
-            source_files: frees
+        let mut project_path = None;
+        let source_files = match (command, project) {
+            (Command::Lsp, _) => Vec::new(),
+            (Command::New, _) => {
+                let [path] = frees[..] else {
+                    return Err(FlagsError::ProjectPath);
+                };
+                project_path = Some(path.to_str().ok_or(FlagsError::ProjectPath)?);
+                Vec::new()
+            }
+            (Command::Test, Some(project)) => project.tests.iter().map(String::as_str).collect(),
+            (Command::Fmt, Some(project)) => project
+                .files
+                .iter()
+                .chain(project.tests.iter())
+                .map(String::as_str)
+                .collect(),
+            (_, Some(project)) => project.files.iter().map(String::as_str).collect(),
+            (_, None) => frees
                 .iter()
                 .flat_map(|s| s.to_str())
-                .filter(|s| s.ends_with(".hsl"))
+                .filter(|s| {
+                    let source = s.ends_with(".hsl");
+                    if !source {
+                        warning!("ignoring `{s}`, source files have the `.hsl` extension");
+                    }
+                    source
+                })
                 .collect(),
+        };
+
+        Ok(Self {
+            program_path,
+            subcommand,
+            command,
+            output_path,
+            source_files,
             target_name,
             quiet,
-            run,
+            lint_shadowing,
+            release,
+            checked_arith,
+            libs: project
+                .map(|p| p.manifest.libs.iter().map(String::as_str).collect())
+                .unwrap_or_default(),
+            project,
+            project_path,
+            check_format,
+            doc_format,
         })
     }
 
+    /// The flags of `hsl check` on `source_files`, the language server checks the files it
+    /// edits with them. The defaults come from the manifest of `project`
+    pub fn check(
+        program_path: &'args str,
+        source_files: Vec<&'args str>,
+        project: Option<&'args crate::project::Project>,
+    ) -> Self {
+        let release = project.is_some_and(|p| p.manifest.opt_level > 0);
+        Self {
+            program_path,
+            subcommand: &CHECK,
+            command: Command::Check,
+            output_path: None,
+            source_files,
+            target_name: project.and_then(|p| p.manifest.target.as_deref()),
+            quiet: true,
+            lint_shadowing: false,
+            release,
+            checked_arith: !release,
+            libs: Vec::new(),
+            project,
+            project_path: None,
+            check_format: false,
+            doc_format: crate::doc::Format::Markdown,
+        }
+    }
+
     pub fn help_string(&self) -> HelpString<'args> {
-        HelpString(self.program_path)
+        HelpString(self.program_path, self.subcommand)
     }
 }
 
@@ -97,12 +261,16 @@ pub enum FlagsResult<'args> {
     Help(HelpString<'args>),
 }
 
-pub struct HelpString<'args>(&'args str);
+pub struct HelpString<'args>(&'args str, &'static parser::Subcommand);
 
 impl<'args> HelpString<'args> {
-    fn deduce(program_path: &'args str, matches: &parser::MatchedFlags<'args>) -> Option<Self> {
+    fn deduce(
+        program_path: &'args str,
+        subcommand: &'static parser::Subcommand,
+        matches: &parser::MatchedFlags<'args>,
+    ) -> Option<Self> {
         if matches.count(&HELP) > 0 {
-            Some(Self(program_path))
+            Some(Self(program_path, subcommand))
         } else {
             None
         }
@@ -111,15 +279,25 @@ impl<'args> HelpString<'args> {
 
 impl<'args> fmt::Display for HelpString<'args> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "USAGE:\n{:4}{} [OPTIONS] [...]\n\nOPTIONS:\n{}",
-            "", self.0, ALL_ARGS
-        )
+        let Self(program_path, subcommand) = self;
+
+        if subcommand.name.is_empty() {
+            write!(
+                f,
+                "USAGE:\n{:4}{program_path} <COMMAND> [OPTIONS] [...]\n{:4}{program_path} {}\n\nCOMMANDS:\n{}\nOPTIONS:\n{}",
+                "", "", subcommand.usage, SUBCOMMANDS, subcommand.args
+            )
+        } else {
+            write!(
+                f,
+                "USAGE:\n{:4}{program_path} {} {}\n\n{}\n\nOPTIONS:\n{}",
+                "", subcommand.name, subcommand.usage, subcommand.description, subcommand.args
+            )
+        }
     }
 }
 
-use parser::{Arg, Args, TakesValue};
+use parser::{Arg, Args, Subcommand, Subcommands, TakesValue};
 
 static HELP: Arg = Arg {
     short: Some(b'h'),
@@ -156,4 +334,229 @@ static QUIET: Arg = Arg {
     description: "quiet the steps of compilation and run",
 };
 
-static ALL_ARGS: Args = Args(&[&HELP, &OUTPUT, &TARGET, &RUN, &QUIET]);
+static SHADOWING: Arg = Arg {
+    short: None,
+    long: "shadowing",
+    takes_value: TakesValue::Forbidden,
+    description: "warn when a variable shadows a variable of an outer block",
+};
+
+static RELEASE: Arg = Arg {
+    short: None,
+    long: "release",
+    takes_value: TakesValue::Forbidden,
+    description: "build without the runtime checks (bounds, null, arithmetic)",
+};
+
+static CHECKED_ARITH: Arg = Arg {
+    short: None,
+    long: "checked-arith",
+    takes_value: TakesValue::Forbidden,
+    description: "check arithmetic overflows and divisions by zero in release builds",
+};
+
+static FMT_CHECK: Arg = Arg {
+    short: None,
+    long: "check",
+    takes_value: TakesValue::Forbidden,
+    description: "report the files that are not formatted without writing them",
+};
+
+static DOC_FORMAT: Arg = Arg {
+    short: Some(b'f'),
+    long: "format",
+    takes_value: TakesValue::Optional(Some(crate::doc::FORMAT_NAMES)),
+    description: "the format of the documentation, markdown by default",
+};
+
+static BUILD_ARGS: &[&Arg] = &[
+    &HELP,
+    &OUTPUT,
+    &TARGET,
+    &QUIET,
+    &SHADOWING,
+    &RELEASE,
+    &CHECKED_ARITH,
+];
+
+static BUILD: Subcommand = Subcommand {
+    name: "build",
+    usage: "[OPTIONS] [FILES...]",
+    description: "compile the files, or the project of the current directory",
+    args: Args(BUILD_ARGS),
+};
+
+static RUN_CMD: Subcommand = Subcommand {
+    name: "run",
+    usage: "[OPTIONS] [FILES...]",
+    description: "compile the files, or the project of the current directory, and run the program",
+    args: Args(BUILD_ARGS),
+};
+
+static CHECK: Subcommand = Subcommand {
+    name: "check",
+    usage: "[OPTIONS] [FILES...]",
+    description: "report the errors of the files, or of the project, without building them",
+    args: Args(&[&HELP, &TARGET, &QUIET, &SHADOWING]),
+};
+
+static FMT: Subcommand = Subcommand {
+    name: "fmt",
+    usage: "[OPTIONS] [FILES...]",
+    description: "format the files, or the files and tests of the project",
+    args: Args(&[&HELP, &FMT_CHECK, &QUIET]),
+};
+
+static TEST: Subcommand = Subcommand {
+    name: "test",
+    usage: "[OPTIONS] [FILES...]",
+    description: "build and run each test program, by default the files of the tests directory of the project",
+    args: Args(&[
+        &HELP,
+        &TARGET,
+        &QUIET,
+        &SHADOWING,
+        &RELEASE,
+        &CHECKED_ARITH,
+    ]),
+};
+
+static CLEAN: Subcommand = Subcommand {
+    name: "clean",
+    usage: "[OPTIONS] [FILES...]",
+    description:
+        "remove the program and the intermediate files built from the files, or of the project",
+    args: Args(&[&HELP, &OUTPUT, &TARGET, &QUIET]),
+};
+
+static NEW: Subcommand = Subcommand {
+    name: "new",
+    usage: "[OPTIONS] <PATH>",
+    description: "create a project in the directory PATH",
+    args: Args(&[&HELP, &QUIET]),
+};
+
+static LSP: Subcommand = Subcommand {
+    name: "lsp",
+    usage: "[OPTIONS]",
+    description: "start a language server speaking LSP over the standard input and output",
+    args: Args(&[&HELP]),
+};
+
+static DOC: Subcommand = Subcommand {
+    name: "doc",
+    usage: "[OPTIONS] [FILES...]",
+    description:
+        "generate the documentation of the functions and externs of the files, or of the project",
+    args: Args(&[&HELP, &OUTPUT, &DOC_FORMAT, &QUIET]),
+};
+
+static SUBCOMMANDS: Subcommands = Subcommands(&[
+    &BUILD, &RUN_CMD, &CHECK, &FMT, &TEST, &CLEAN, &NEW, &LSP, &DOC,
+]);
+
+// The flat form predates the subcommands, it compiles the files and runs them with `--run`
+static FLAT: Subcommand = Subcommand {
+    name: "",
+    usage: "[OPTIONS] [FILES...]",
+    description: "",
+    args: Args(&[
+        &HELP,
+        &OUTPUT,
+        &TARGET,
+        &RUN,
+        &QUIET,
+        &SHADOWING,
+        &RELEASE,
+        &CHECKED_ARITH,
+    ]),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&'static str]) -> FlagsResult<'static> {
+        static PROGRAM: std::sync::LazyLock<std::ffi::OsString> =
+            std::sync::LazyLock::new(|| "hsl".into());
+        // The project is only loaded when no file is given, these tests give files
+        let project = Box::leak(Box::new(std::cell::OnceCell::new()));
+        Flags::parse(
+            Some(&PROGRAM),
+            args.iter().map(|arg| std::ffi::OsStr::new(*arg)),
+            Some("aarch64-darwin"),
+            project,
+        )
+    }
+
+    fn command(args: &[&'static str]) -> Option<Command> {
+        match parse(args) {
+            FlagsResult::Ok(flags) => Some(flags.command),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn subcommands() {
+        assert_eq!(command(&["build", "a.hsl"]), Some(Command::Build));
+        assert_eq!(command(&["run", "-q", "a.hsl"]), Some(Command::Run));
+        assert_eq!(command(&["check", "a.hsl"]), Some(Command::Check));
+        assert_eq!(command(&["fmt", "a.hsl"]), Some(Command::Fmt));
+        assert_eq!(command(&["fmt", "--check", "a.hsl"]), Some(Command::Fmt));
+        assert_eq!(command(&["test", "a.hsl"]), Some(Command::Test));
+        assert_eq!(command(&["clean", "a.hsl"]), Some(Command::Clean));
+        assert_eq!(command(&["lsp"]), Some(Command::Lsp));
+        assert_eq!(command(&["doc", "a.hsl"]), Some(Command::Doc));
+        assert_eq!(
+            command(&["doc", "--format=html", "a.hsl"]),
+            Some(Command::Doc)
+        );
+        assert_eq!(command(&["doc", "--format=pdf", "a.hsl"]), None);
+
+        // Each subcommand has its own arguments
+        assert_eq!(command(&["check", "-o", "a", "a.hsl"]), None);
+        assert_eq!(command(&["build", "--run", "a.hsl"]), None);
+        assert_eq!(command(&["build", "--check", "a.hsl"]), None);
+    }
+
+    #[test]
+    fn flat_alias() {
+        assert_eq!(command(&["a.hsl"]), Some(Command::Build));
+        assert_eq!(command(&["-r", "a.hsl"]), Some(Command::Run));
+
+        // A file named like a subcommand is given after the flags
+        let FlagsResult::Ok(flags) = parse(&["-q", "build.hsl"]) else {
+            panic!("invalid flags");
+        };
+        assert_eq!(flags.source_files, ["build.hsl"]);
+    }
+
+    #[test]
+    fn new_project() {
+        let FlagsResult::Ok(flags) = parse(&["new", "falcon"]) else {
+            panic!("invalid flags");
+        };
+        assert_eq!(flags.command, Command::New);
+        assert_eq!(flags.project_path, Some("falcon"));
+
+        assert!(matches!(
+            parse(&["new"]),
+            FlagsResult::InvalidFlags(FlagsError::ProjectPath)
+        ));
+    }
+
+    #[test]
+    fn help() {
+        let FlagsResult::Help(help) = parse(&["--help"]) else {
+            panic!("expected the help");
+        };
+        let help = help.to_string();
+        assert!(help.contains("COMMANDS:") && help.contains("--run"));
+
+        let FlagsResult::Help(help) = parse(&["check", "-h"]) else {
+            panic!("expected the help");
+        };
+        let help = help.to_string();
+        assert!(help.contains("hsl check [OPTIONS]") && !help.contains("--output"));
+    }
+}
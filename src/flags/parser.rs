@@ -259,8 +259,11 @@ impl Args {
 
 impl fmt::Display for Args {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Descriptions are aligned after the longest flag
+        let longest = self.0.iter().map(|arg| arg.long.len()).max().unwrap_or(0);
+
         for arg in self.0 {
-            let width = 10 - arg.long.len();
+            let width = longest.max(8) + 2 - arg.long.len();
 
             if let Some(short) = arg.short {
                 writeln!(
@@ -287,6 +290,42 @@ impl fmt::Display for Args {
     }
 }
 
+/// A subcommand of the command line, e.g. `build` in `hsl build`, with its own arguments
+pub struct Subcommand {
+    pub name: &'static str,
+
+    /// Arguments following the options in the usage line, e.g. `[FILES...]`
+    pub usage: &'static str,
+
+    /// Subcommand description to display in the help command
+    pub description: &'static str,
+
+    pub args: Args,
+}
+
+pub struct Subcommands(pub &'static [&'static Subcommand]);
+
+impl Subcommands {
+    /// Find the subcommand named `name`
+    pub fn lookup(&self, name: &OsStr) -> Option<&'static Subcommand> {
+        self.0.iter().find(|sub| sub.name == name).copied()
+    }
+}
+
+impl fmt::Display for Subcommands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Descriptions are aligned after the longest name
+        let longest = self.0.iter().map(|sub| sub.name.len()).max().unwrap_or(0);
+
+        for sub in self.0 {
+            let width = longest + 4 - sub.name.len();
+            writeln!(f, "{:4}{}{:width$}{}", "", sub.name, "", sub.description)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Matches<'args> {
     pub flags: MatchedFlags<'args>,
@@ -0,0 +1,416 @@
+//! Source code formatter of the HSL language
+//!
+//! The formatter works on the concrete syntax tree keeping the comments, it does not need the
+//! program to be valid past the lexer. The lines of the source are kept: each one is
+//! re-emitted with its tokens separated by a space and indented by four spaces per block
+//! (function bodies and parameters, call arguments, if/else and loop bodies, assignment,
+//! extern, record and array blocks). Consecutive blank lines are collapsed into one and the
+//! trailing comments of a file are aligned on the same column. Block comments stay between
+//! the tokens they separate, the lines inside them are left untouched.
+//!
+//! Declarations and assignments have synonymous spellings, the formatter picks the one of the
+//! `Style` depending on where the token is used.
+
+use crate::cst::{self, Element, Node};
+use crate::lexer::token::TokenKind;
+use crate::lexer::trivia::{TriviaKind, TriviaToken};
+use crate::lexer::Lexer;
+
+const INDENT: usize = 4;
+// Trailing comments are aligned on a multiple of this column
+const COMMENT_ALIGN: usize = 4;
+
+/// Canonical spellings of the synonymous tokens
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    // Declaration of a global variable or constant
+    pub global: &'static str,
+    // Declaration of a variable in a function
+    pub local: &'static str,
+    // Initialiser of a declaration
+    pub init: &'static str,
+    // Assignment in a `What a piece of junk!` block
+    pub assign: &'static str,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            global: "That's one hell of a pilot.",
+            local: "I am a big deal in the resistance.",
+            init: "Who, mesa ?",
+            assign: "I am your father.",
+        }
+    }
+}
+
+impl Style {
+    /// Set the spelling of `key`, the error holds the expected spellings when `spelling` is
+    /// not a synonym of the token
+    pub fn set(&mut self, key: &str, spelling: &str) -> Result<(), String> {
+        let (slot, kind) = match key {
+            "global" => (&mut self.global, T![Let]),
+            "local" => (&mut self.local, T![Let]),
+            "init" => (&mut self.init, T![Assign]),
+            "assign" => (&mut self.assign, T![Assign]),
+            _ => return Err("one of global, local, init or assign".to_string()),
+        };
+
+        let synonyms = crate::lexer::synonyms(kind);
+        match synonyms.iter().find(|s| **s == spelling) {
+            Some(s) => {
+                *slot = s;
+                Ok(())
+            }
+            None => Err(format!("one of \"{}\"", synonyms.join("\", \""))),
+        }
+    }
+}
+
+/// Format the source, `None` is returned if the lexer does not recognise every token
+pub fn format(source: &str, style: &Style) -> Option<String> {
+    let root = cst::parse(source);
+
+    let skipped = root
+        .tokens()
+        .into_iter()
+        .flat_map(|t| t.leading.iter().chain(&t.trailing))
+        .find(|t| t.kind == TriviaKind::Skipped);
+    if let Some(trivia) = skipped {
+        error!(
+            "line {}: unable to format the file because of an invalid token",
+            trivia.span.line + 1
+        );
+        return None;
+    }
+
+    let lines = layout(&root, style);
+
+    // Trailing comments are aligned after the longest line holding one
+    let column = lines
+        .iter()
+        .filter(|l| l.comment.is_some())
+        .map(|l| l.width())
+        .max()
+        .map(|width| (width + 2).next_multiple_of(COMMENT_ALIGN))
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for line in lines.iter() {
+        if line.blank {
+            output.push('\n');
+            continue;
+        }
+
+        let indent = line.depth * INDENT;
+        output.extend(std::iter::repeat_n(' ', indent));
+        output.push_str(&line.code);
+        if let Some(comment) = line.comment {
+            if line.code.is_empty() {
+                output.push_str(comment);
+            } else {
+                let padding = column - line.width();
+                output.extend(std::iter::repeat_n(' ', padding));
+                output.push_str(comment);
+            }
+        }
+        output.push('\n');
+    }
+
+    // The formatter must not change the program
+    let kinds = |source: &str| -> Vec<TokenKind> { Lexer::new(source).map(|t| t.kind).collect() };
+    if kinds(source) != kinds(&output) {
+        error!("the formatted source does not hold the same tokens, please file an issue");
+        return None;
+    }
+
+    Some(output)
+}
+
+struct Line<'input> {
+    blank: bool,
+    depth: usize,
+    code: String,
+    comment: Option<&'input str>,
+}
+
+impl Line<'_> {
+    fn width(&self) -> usize {
+        // The last line of a block comment spanning several lines is not indented
+        match self.code.rsplit_once('\n') {
+            Some((_, last)) => last.chars().count(),
+            None => self.depth * INDENT + self.code.chars().count(),
+        }
+    }
+}
+
+/// Split the tokens of the tree into lines and compute their indentation
+fn layout<'input>(root: &Node<'input>, style: &Style) -> Vec<Line<'input>> {
+    let mut layout = Layout {
+        lines: Vec::new(),
+        style,
+        declared: false,
+        previous: None,
+    };
+    layout.node(root, 0);
+    layout.lines
+}
+
+struct Layout<'input, 'style> {
+    lines: Vec<Line<'input>>,
+    style: &'style Style,
+    // The next `Assign` is the initialiser of a declaration
+    declared: bool,
+    previous: Option<TokenKind>,
+}
+
+impl<'input> Layout<'input, '_> {
+    /// Lay out the tokens of `node`, the delimiters of a block are one level above its content
+    fn node(&mut self, node: &Node<'input>, depth: usize) {
+        for (index, child) in node.children.iter().enumerate() {
+            match child {
+                Element::Node(block) => self.node(block, depth + 1),
+                Element::Token(token) if node.is_delimiter(token) => {
+                    // A comment line before the end of a block belongs to the block
+                    let comment_depth = if index == 0 { depth - 1 } else { depth };
+                    self.token(token, depth - 1, comment_depth)
+                }
+                Element::Token(token) => self.token(token, depth, depth),
+            }
+        }
+    }
+
+    fn token(&mut self, token: &TriviaToken<'input>, depth: usize, comment_depth: usize) {
+        let mut newlines = 0;
+        // Whether the current line starts with a block comment
+        let mut block_line = false;
+        for trivia in &token.leading {
+            match trivia.kind {
+                TriviaKind::Newline => newlines += 1,
+                TriviaKind::Comment | TriviaKind::DocComment => {
+                    self.new_line(newlines, comment_depth);
+                    self.set_comment(trivia.text);
+                    newlines = 0;
+                    block_line = false;
+                }
+                TriviaKind::BlockComment => {
+                    if newlines > 0 || self.lines.is_empty() {
+                        self.new_line(newlines, comment_depth);
+                        block_line = true;
+                    }
+                    self.push_code(trivia.text, None);
+                    newlines = 0;
+                }
+                _ => (),
+            }
+        }
+
+        if token.token.kind == T![EOF] {
+            return;
+        }
+
+        if newlines > 0 || self.lines.is_empty() {
+            self.new_line(newlines, depth);
+        } else if block_line {
+            // SAFETY: the line of the block comment has been pushed above
+            self.lines.last_mut().unwrap().depth = depth;
+        }
+
+        let text = match token.token.kind {
+            T![Let] => {
+                self.declared = true;
+                if depth == 0 {
+                    self.style.global
+                } else {
+                    self.style.local
+                }
+            }
+            T![Assign] if self.declared => {
+                self.declared = false;
+                self.style.init
+            }
+            T![Assign] => self.style.assign,
+            _ => token.text,
+        };
+
+        self.push_code(text, Some(token.token.kind));
+
+        for trivia in &token.trailing {
+            match trivia.kind {
+                TriviaKind::Comment | TriviaKind::DocComment => self.set_comment(trivia.text),
+                TriviaKind::BlockComment => self.push_code(trivia.text, None),
+                _ => (),
+            }
+        }
+    }
+
+    /// Append `text` to the code of the current line, `kind` is the token it spells if any
+    fn push_code(&mut self, text: &str, kind: Option<TokenKind>) {
+        // SAFETY: a line is pushed before any code
+        let current = self.lines.last_mut().unwrap();
+        // The minus sign of a negative literal sticks to it
+        if !current.code.is_empty() && self.previous != Some(T![Not]) {
+            current.code.push(' ');
+        }
+        current.code.push_str(text);
+        self.previous = kind;
+    }
+
+    /// Start a line, a blank line is kept when it follows several newlines but none is kept at
+    /// the beginning of the file
+    fn new_line(&mut self, newlines: usize, depth: usize) {
+        if newlines > 1 && !self.lines.is_empty() {
+            self.lines.push(Line {
+                blank: true,
+                depth: 0,
+                code: String::new(),
+                comment: None,
+            });
+        }
+
+        self.lines.push(Line {
+            blank: false,
+            depth,
+            code: String::new(),
+            comment: None,
+        });
+    }
+
+    fn set_comment(&mut self, comment: &'input str) {
+        if let Some(line) = self.lines.last_mut() {
+            line.comment = Some(comment.trim_end());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indentation_and_spacing() {
+        let input = r#"
+
+
+A long time ago in a   galaxy far, far away...
+I am a big deal in the resistance.   finn
+        The force is strong with this one. rey
+  Judge me by my size, do you ? -10
+Do, or do not. There is no try. finn
+Execute order printf
+"%d\n"
+                  finn
+Order executed
+These aren't the droids you're looking for.
+
+
+
+  What a piece of junk! finn
+      Who, mesa ? Lock S-foils in attack position. 1 2 Stay on target.
+  The garbage will do.
+ You have failed me for the last time.
+May the force be with you.
+"#;
+
+        let expected = r#"A long time ago in a galaxy far, far away...
+    I am a big deal in the resistance. finn
+    I am a big deal in the resistance. rey
+    Who, mesa ? -10
+    Do, or do not. There is no try. finn
+        Execute order printf
+            "%d\n"
+            finn
+        Order executed
+    These aren't the droids you're looking for.
+
+        What a piece of junk! finn
+            I am your father. Lock S-foils in attack position. 1 2 Stay on target.
+        The garbage will do.
+    You have failed me for the last time.
+May the force be with you.
+"#;
+
+        let output = format(input, &Style::default()).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(format(&output, &Style::default()).unwrap(), output);
+    }
+
+    #[test]
+    fn comments() {
+        let input = "<(-.-)> Globals\nThe force is strong with this one. crew   <(-.-)> The crew\nWho, mesa ? 12 <(-.-)> Twelve   \n\nA long time ago in a galaxy far, far away...\n<(-.-)> Nothing to do\nMay the force be with you.\n";
+
+        let expected = "<(-.-)> Globals\nThat's one hell of a pilot. crew    <(-.-)> The crew\nWho, mesa ? 12                      <(-.-)> Twelve\n\nA long time ago in a galaxy far, far away...\n    <(-.-)> Nothing to do\nMay the force be with you.\n";
+
+        assert_eq!(format(input, &Style::default()).unwrap(), expected);
+    }
+
+    #[test]
+    fn block_comments() {
+        let input = "A long time ago in a galaxy far, far away...\n<(o.o) Multi\n   line (o.o)>\n  Execute order f <(o.o) args (o.o)>   Order executed <(-.-)> Call\n<(o.o) end (o.o)> May the force be with you.\n";
+
+        let expected = "A long time ago in a galaxy far, far away...\n    <(o.o) Multi\n   line (o.o)>\n    Execute order f <(o.o) args (o.o)> Order executed   <(-.-)> Call\n<(o.o) end (o.o)> May the force be with you.\n";
+
+        let output = format(input, &Style::default()).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(format(&output, &Style::default()).unwrap(), output);
+        assert!(format("<(o.o) <(o.o) (o.o)>", &Style::default()).is_none());
+    }
+
+    #[test]
+    fn custom_style() {
+        let mut style = Style::default();
+        style.set("init", "I am your father.").unwrap();
+        style
+            .set("assign", "Judge me by my size, do you ?")
+            .unwrap();
+        assert!(style.set("local", "Who, mesa ?").is_err());
+        assert!(style.set("speed", "Punch it!").is_err());
+
+        let input = "A long time ago in a galaxy far, far away...\nI am a big deal in the resistance. x\nWho, mesa ? 1\nWhat a piece of junk! x\nI am your father. 2\nThe garbage will do.\nMay the force be with you.\n";
+        let expected = "A long time ago in a galaxy far, far away...\n    I am a big deal in the resistance. x\n    I am your father. 1\n    What a piece of junk! x\n        Judge me by my size, do you ? 2\n    The garbage will do.\nMay the force be with you.\n";
+
+        assert_eq!(format(input, &style).unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        assert!(format("A long time ago in a galaxy $", &Style::default()).is_none());
+    }
+
+    #[test]
+    fn stdlib_is_stable() {
+        let output = format(include_str!("stdlib/math.hsl"), &Style::default()).unwrap();
+        assert_eq!(format(&output, &Style::default()).unwrap(), output);
+    }
+
+    #[test]
+    fn examples_are_formatted() {
+        let examples = concat!(env!("CARGO_MANIFEST_DIR"), "/examples");
+        let mut dirs = vec![std::path::PathBuf::from(examples)];
+        let mut count = 0;
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                if path.extension().is_none_or(|ext| ext != "hsl") {
+                    continue;
+                }
+
+                // `hsl fmt --check` must accept every example
+                let source = std::fs::read_to_string(&path).unwrap();
+                let output = format(&source, &Style::default());
+                assert_eq!(
+                    output.as_deref(),
+                    Some(source.as_str()),
+                    "{}",
+                    path.display()
+                );
+                count += 1;
+            }
+        }
+        assert!(count > 0);
+    }
+}
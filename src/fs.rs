@@ -4,10 +4,14 @@ pub struct Files {
 }
 
 impl Files {
-    pub fn new(output_file: &str) -> Option<Self> {
+    /// Paths of the intermediate files of `output_file`, their directory is created if
+    /// `create_dir` is set
+    pub fn new(output_file: &str, create_dir: bool) -> Option<Self> {
         let garbage_path = get_garbage_base(output_file)?;
 
-        create_garbage_base(&garbage_path)?;
+        if create_dir {
+            create_garbage_base(&garbage_path)?;
+        }
 
         let output_stem = get_file_stem(output_file)?;
         let g_path = std::path::Path::new(&garbage_path);
@@ -3,45 +3,152 @@
 
 use core::fmt;
 
-use crate::lexer::token::TokenKind;
+use crate::lexer::token::{Span, TokenKind};
+use crate::parser::slt::SymbolId;
 
 pub struct Program<'prog> {
+    pub modules: Vec<Module<'prog>>,
     pub func: Vec<Fn<'prog>>,
     pub extrn: Vec<Extrn<'prog>>,
+    pub globals: Vec<Global<'prog>>,
+    pub records: Vec<Record<'prog>>,
+}
+
+/// A source file, its functions can only be called from the modules importing it when they are
+/// public
+pub struct Module<'prog> {
+    pub path: &'prog str,
+    // Unique name of the module, the symbols of its functions are prefixed with it
+    pub name: &'prog str,
+    pub imports: Vec<Import<'prog>>,
+
+    // Source embedded in the compiler for the modules of the standard library, the other
+    // modules are read from `path`
+    pub source: Option<&'static str>,
+}
+
+pub struct Import<'prog> {
+    // Path of the imported file relative to the importing one, as written in the source
+    pub path: &'prog str,
+    pub span: Span,
+
+    // Index of the imported module, it is set by the compiler once the file is found
+    pub module: Option<usize>,
+}
+
+/// A record type declared at the top level, its layout is computed by the resolver
+pub struct Record<'prog> {
+    pub id: &'prog str,
+    pub span: Span,
+    pub fields: Vec<(&'prog str, Type<'prog>, Span)>,
+
+    // Index of the module declaring the record, the record types refer to it by symbol
+    pub module: usize,
+    pub symbol: &'prog str,
+}
+
+/// A module level variable, its value is evaluated at compile time and it is a constant
+/// unless it is assigned in a function
+pub struct Global<'prog> {
+    pub id: &'prog str,
+    pub span: Span,
+    pub value: Arg<'prog>,
+
+    // Set by the resolver if the global is assigned somewhere
+    pub mutable: bool,
+
+    // Index of the module declaring the global and its name in the generated code
+    pub module: usize,
+    pub symbol: &'prog str,
 }
 
 pub struct Extrn<'prog> {
     pub id: &'prog str,
+    pub span: Span,
 
     // Tell if the function has a variadic parameter and if so the value of variadic is
     // the number of fixed parameters
     pub variadic: Option<usize>,
-    pub args: Vec<Type>,
+    pub args: Vec<Type<'prog>>,
+
+    // Index of the module declaring the extern
+    pub module: usize,
+    // Documentation given by the doc comments above the declaration
+    pub doc: Option<&'prog str>,
 }
 
 pub struct Fn<'prog> {
     pub id: &'prog str,
+    pub span: Span,
     pub body: Vec<Expr<'prog>>,
 
     // Tell if the function has a variadic parameter and if so the value of variadic is
     // the number of fixed parameters
     pub variadic: Option<usize>,
-    pub args: Vec<(&'prog str, Type)>,
+    pub args: Vec<(&'prog str, Type<'prog>)>,
+
+    // Path of the source file declaring the function
+    pub file: &'prog str,
+    // Index of the module declaring the function and whether other modules can call it
+    pub module: usize,
+    pub public: bool,
+    // Name of the function in the generated code, see `mangle`
+    pub symbol: &'prog str,
+    // Documentation given by the doc comments above the declaration
+    pub doc: Option<&'prog str>,
+
+    // The slt region holding the function variables, it is set by the resolver
+    pub region: Option<u32>,
 }
 
 pub enum Op {
+    Assign,
     Eq,
     Add,
     Sub,
     Mul,
     Div,
     Mod,
+    // Append the value to a growable array or to a dynamic string
+    Push,
+    // Logical operations on Signals, the value is not evaluated when the result is known
+    And,
+    Or,
+}
+
+/// Comparison of two values, the result is a Signal
+pub enum Cmp {
+    Eq,
+    Lt,
+    Gt,
+}
+
+/// Short-circuiting logical operation on two Signals
+pub enum Logic {
+    And,
+    Or,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Type {
+pub enum Type<'prog> {
     Ptr(InnerType),
     Val(InnerType),
+    // A fixed size array living in the stack frame of its function
+    Array(InnerType, usize),
+    // A reference to an array, used to pass arrays to functions
+    Slice(InnerType),
+    // A record living in the stack frame of its function
+    Record(&'prog str),
+    // A reference to a record, used to pass records to functions
+    RecordRef(&'prog str),
+    // A growable array allocated on the heap, the variable holds a pointer to its first
+    // element and the capacity and the length are stored in front of it
+    Heap(InnerType),
+    // A string allocated on the heap that can grow, it is laid out like a growable array
+    // of bytes and is always null terminated so it can be given where a string is expected
+    Text,
+    // The type of the null pointer, it can be given where any pointer is expected
+    Null,
     Void,
 }
 
@@ -50,45 +157,286 @@ pub enum InnerType {
     Int,
     Str,
     Bool,
+    Char,
 }
 
 pub enum Expr<'prog> {
     Let {
         id: &'prog str,
+        span: Span,
         value: Arg<'prog>,
+        sym: Option<SymbolId<'prog>>,
     },
     FnCall {
         id: &'prog str,
+        span: Span,
         args: Vec<Arg<'prog>>,
+        func: Option<SymbolId<'prog>>,
+    },
+    // Apply each operation in order to the variable, starting from its current value.
+    // If `index` or `field` is set the operations apply to an element of the array or to a
+    // field of the record instead, if `deref` is set they apply to the pointed value
+    Assign {
+        id: &'prog str,
+        span: Span,
+        index: Option<Arg<'prog>>,
+        field: Option<&'prog str>,
+        deref: bool,
+        ops: Vec<(Op, Arg<'prog>)>,
+        sym: Option<SymbolId<'prog>>,
+    },
+    If {
+        span: Span,
+        cond: Arg<'prog>,
+        body: Vec<Expr<'prog>>,
+        else_body: Option<Vec<Expr<'prog>>>,
+    },
+    While {
+        span: Span,
+        cond: Arg<'prog>,
+        body: Vec<Expr<'prog>>,
+    },
+    // Print a value followed by a newline on the standard output
+    Print {
+        span: Span,
+        value: Arg<'prog>,
+    },
+    // Stop the program with `msg` when `cond` is false
+    Assert {
+        span: Span,
+        cond: Arg<'prog>,
+        msg: Option<&'prog str>,
+    },
+    // Stop the program with `msg`
+    Panic {
+        span: Span,
+        msg: Option<&'prog str>,
+    },
+    // Release the heap storage of a growable array or a dynamic string, the variable is
+    // reset to null
+    Free {
+        id: &'prog str,
+        span: Span,
+        sym: Option<SymbolId<'prog>>,
     },
 }
 
 pub enum Arg<'prog> {
-    Id(&'prog str),
+    Id {
+        id: &'prog str,
+        span: Span,
+        sym: Option<SymbolId<'prog>>,
+    },
     Lit(Lit<'prog>),
+    Index {
+        id: &'prog str,
+        span: Span,
+        index: Box<Arg<'prog>>,
+        sym: Option<SymbolId<'prog>>,
+    },
+    Len {
+        id: &'prog str,
+        span: Span,
+        sym: Option<SymbolId<'prog>>,
+    },
+    // An array literal, only valid as the value of a let statement
+    Array {
+        span: Span,
+        items: Vec<Lit<'prog>>,
+    },
+    Field {
+        id: &'prog str,
+        span: Span,
+        field: &'prog str,
+        sym: Option<SymbolId<'prog>>,
+    },
+    // A record construction, only valid as the value of a let statement
+    Record {
+        id: &'prog str,
+        span: Span,
+        fields: Vec<(&'prog str, Arg<'prog>)>,
+    },
+    // The address of a variable, an array element or a record field
+    AddrOf {
+        span: Span,
+        target: Box<Arg<'prog>>,
+    },
+    Deref {
+        id: &'prog str,
+        span: Span,
+        sym: Option<SymbolId<'prog>>,
+    },
+    Null {
+        span: Span,
+    },
+    // A growable array of `len` zeroed elements, only valid as the value of a let statement
+    Alloc {
+        span: Span,
+        ty: InnerType,
+        len: Box<Arg<'prog>>,
+    },
+    // A dynamic string holding a copy of `init`, only valid as the value of a let statement
+    Text {
+        span: Span,
+        init: Box<Arg<'prog>>,
+    },
+    // A value of type `ty` read from a line of the standard input, only valid as the value
+    // of a let statement
+    Read {
+        span: Span,
+        ty: InnerType,
+    },
+    Cmp {
+        span: Span,
+        op: Cmp,
+        lhs: Box<Arg<'prog>>,
+        rhs: Box<Arg<'prog>>,
+    },
+    // `rhs` is only evaluated when `lhs` does not decide the result
+    Logic {
+        span: Span,
+        op: Logic,
+        lhs: Box<Arg<'prog>>,
+        rhs: Box<Arg<'prog>>,
+    },
+    Not {
+        span: Span,
+        arg: Box<Arg<'prog>>,
+    },
+    // A new dynamic string holding `lhs` followed by `rhs`, only valid as the value of a let
+    // statement
+    Concat {
+        span: Span,
+        lhs: Box<Arg<'prog>>,
+        rhs: Box<Arg<'prog>>,
+    },
+    // A new dynamic string holding the `len` bytes of `text` starting at `start`, only valid
+    // as the value of a let statement
+    Substr {
+        span: Span,
+        text: Box<Arg<'prog>>,
+        start: Box<Arg<'prog>>,
+        len: Box<Arg<'prog>>,
+    },
+    // A new dynamic string built from an interpolated string literal, only valid as the
+    // value of a let statement
+    Interp {
+        span: Span,
+        parts: Vec<Part<'prog>>,
+    },
+}
+
+/// A piece of an interpolated string literal, the variables are written between braces
+pub enum Part<'prog> {
+    Str(&'prog str),
+    Arg(Arg<'prog>),
 }
 
 pub enum Lit<'prog> {
     Int(i64),
     Str(&'prog str),
     Bool(bool),
+    Char(char),
+}
+
+/// Symbol of the item `id` of a module, the module name is separated by a dot which cannot be
+/// part of a name
+pub fn mangle(module: &str, id: &str) -> String {
+    if module.is_empty() {
+        id.to_string()
+    } else {
+        format!("{module}.{id}")
+    }
+}
+
+/// Name of an item from its symbol
+pub fn demangle(symbol: &str) -> &str {
+    symbol.rsplit('.').next().unwrap_or(symbol)
 }
 
 impl<'prog> Program<'prog> {
     pub fn new() -> Self {
         Self {
+            modules: Vec::new(),
             func: Vec::new(),
             extrn: Vec::new(),
+            globals: Vec::new(),
+            records: Vec::new(),
+        }
+    }
+}
+
+impl Type<'_> {
+    /// Number of 8 bytes stack slots used by a variable of this type, arrays store their
+    /// length in front of their elements
+    ///
+    /// # Panics
+    /// Records are sized from their layout, this panics for `Type::Record`
+    pub fn slots(&self) -> usize {
+        match self {
+            Self::Array(_, len) => len + 1,
+            Self::Record(id) => unreachable!("record {id} is sized from its layout"),
+            _ => 1,
+        }
+    }
+
+    /// Name of the record type of a record or a record reference
+    pub fn record(&self) -> Option<&str> {
+        match self {
+            Self::Record(id) | Self::RecordRef(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Tell if the value is a string, dynamic strings can be given where a string is expected
+    pub fn is_str(&self) -> bool {
+        matches!(self, Self::Val(InnerType::Str) | Self::Text)
+    }
+
+    /// Tell if the value lives on the heap and can be grown and freed
+    pub fn is_heap(&self) -> bool {
+        matches!(self, Self::Heap(_) | Self::Text)
+    }
+
+    /// Type of the elements of an array, a slice or a growable array
+    pub fn elem(&self) -> Option<InnerType> {
+        match self {
+            Self::Array(inner, _) | Self::Slice(inner) | Self::Heap(inner) => Some(*inner),
+            _ => None,
         }
     }
 
-    pub fn get_fn_variadic(&self, id: &'prog str) -> Option<usize> {
-        if let Some(func) = self.func.iter().find(|f| f.id == id) {
-            func.variadic
-        } else if let Some(func) = self.extrn.iter().find(|f| f.id == id) {
-            func.variadic
-        } else {
-            None
+    /// Tell if a value of type `other` can be given where a value of type `self` is expected
+    pub fn accepts(&self, other: &Type<'_>) -> bool {
+        match (self, other) {
+            (Type::Slice(inner), Type::Array(other, _)) => inner == other,
+            (Type::Slice(inner), Type::Heap(other)) => inner == other,
+            (Type::RecordRef(id), Type::Record(other)) => id == other,
+            (Type::Val(InnerType::Str), Type::Text) => true,
+            (Type::Ptr(_), Type::Null) => true,
+            _ => self == other,
+        }
+    }
+}
+
+impl InnerType {
+    /// Size in bytes of a record field of this type
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Int | Self::Str => 8,
+            Self::Char => 4,
+            Self::Bool => 1,
+        }
+    }
+}
+
+impl Lit<'_> {
+    pub fn ty(&self) -> InnerType {
+        match self {
+            Self::Int(_) => InnerType::Int,
+            Self::Str(_) => InnerType::Str,
+            Self::Bool(_) => InnerType::Bool,
+            Self::Char(_) => InnerType::Char,
         }
     }
 }
@@ -98,22 +446,69 @@ impl TryFrom<TokenKind> for Op {
 
     fn try_from(value: TokenKind) -> Result<Self, Self::Error> {
         match value {
+            T![Assign] => Ok(Self::Assign),
             T![Plus] => Ok(Self::Add),
             T![Eq] => Ok(Self::Eq),
             T![Minus] => Ok(Self::Sub),
             T![Div] => Ok(Self::Div),
             T![Mul] => Ok(Self::Mul),
             T![Mod] => Ok(Self::Mod),
+            T![Push] => Ok(Self::Push),
+            T![And] => Ok(Self::And),
+            T![Or] => Ok(Self::Or),
             _ => Err(()),
         }
     }
 }
 
-impl fmt::Display for Type {
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Assign => write!(f, "assign"),
+            Self::Eq => write!(f, "eq"),
+            Self::Add => write!(f, "add"),
+            Self::Sub => write!(f, "sub"),
+            Self::Mul => write!(f, "mul"),
+            Self::Div => write!(f, "div"),
+            Self::Mod => write!(f, "mod"),
+            Self::Push => write!(f, "push"),
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
+        }
+    }
+}
+
+impl fmt::Display for Logic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
+        }
+    }
+}
+
+impl fmt::Display for Cmp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eq => write!(f, "eq"),
+            Self::Lt => write!(f, "lt"),
+            Self::Gt => write!(f, "gt"),
+        }
+    }
+}
+
+impl fmt::Display for Type<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Ptr(inner) => write!(f, "pointer({inner})"),
             Self::Val(inner) => write!(f, "value({inner})"),
+            Self::Array(inner, len) => write!(f, "array({inner}; {len})"),
+            Self::Slice(inner) => write!(f, "slice({inner})"),
+            Self::Record(id) => write!(f, "record({})", demangle(id)),
+            Self::RecordRef(id) => write!(f, "reference({})", demangle(id)),
+            Self::Heap(inner) => write!(f, "heap({inner})"),
+            Self::Text => write!(f, "text"),
+            Self::Null => write!(f, "null"),
             Self::Void => write!(f, "void"),
         }
     }
@@ -125,6 +520,7 @@ impl fmt::Display for InnerType {
             Self::Int => write!(f, "Credit"),
             Self::Str => write!(f, "Holotext"),
             Self::Bool => write!(f, "Signal"),
+            Self::Char => write!(f, "Aurebesh"),
         }
     }
 }
@@ -6,7 +6,20 @@ use token::{Span, Token};
 #[macro_use]
 pub(crate) mod token;
 
-mod rules;
+pub mod rules;
+pub mod trivia;
+
+/// Prefix of the comments documenting the function or the extern declared below them
+pub const DOC_COMMENT: &str = "<(^.^)>";
+/// The text of a line of documentation, without the prefix of its doc comment
+pub fn doc_line(comment: &str) -> &str {
+    let line = comment.strip_prefix(DOC_COMMENT).unwrap_or(comment);
+    line.strip_prefix(' ').unwrap_or(line).trim_end()
+}
+
+/// Delimiters of the block comments, they can span several lines and nest
+pub const BLOCK_COMMENT_OPEN: &str = "<(o.o)";
+pub const BLOCK_COMMENT_CLOSE: &str = "(o.o)>";
 
 #[derive(Debug, Clone, Copy)]
 pub struct ParsePoint {
@@ -20,6 +33,8 @@ pub struct Lexer<'input> {
     input: &'input [u8],
     parse_point: ParsePoint,
     has_eof: bool,
+    // Comments are returned as tokens instead of being skipped, for the formatter
+    comments: bool,
 
     int_number: u64,
     char_lit: char,
@@ -36,6 +51,7 @@ impl<'input> Lexer<'input> {
                 line_number: 0,
             },
             has_eof: false,
+            comments: false,
 
             int_number: 0,
             char_lit: ' ',
@@ -43,6 +59,15 @@ impl<'input> Lexer<'input> {
         }
     }
 
+    /// A lexer returning the comments as `T![Comment]` tokens, a line comment spans until the
+    /// end of its line and a block comment until its closing delimiter
+    pub fn with_comments(input: &'input str) -> Lexer<'input> {
+        Lexer {
+            comments: true,
+            ..Self::new(input)
+        }
+    }
+
     pub fn is_eof(&self) -> bool {
         self.parse_point.position >= self.input.len()
     }
@@ -62,7 +87,7 @@ impl<'input> Lexer<'input> {
         self.parse_point.position += 1;
         if self.input[x] as char == '\n' {
             self.parse_point.line_number += 1;
-            self.parse_point.line_start = x;
+            self.parse_point.line_start = x + 1;
         }
     }
 
@@ -105,8 +130,40 @@ impl<'input> Lexer<'input> {
         }
     }
 
+    /// Skip a block comment whose opening delimiter has been skipped, along with the comments
+    /// nested in it. Returns false if the input ends before the comment is closed
+    fn skip_block_comment(&mut self) -> bool {
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_eof() {
+                return false;
+            }
+
+            if self.skip_prefix(BLOCK_COMMENT_OPEN) {
+                depth += 1;
+            } else if self.skip_prefix(BLOCK_COMMENT_CLOSE) {
+                depth -= 1;
+            } else {
+                self.skip_char();
+            }
+        }
+
+        true
+    }
+
+    /// Parse the digits of a number in the given radix, digits can be separated by `_`
     fn parse_number(&mut self, radix: Radix) -> Option<()> {
+        let mut has_digits = false;
+        let mut separated = false;
+
         while let Some(x) = self.peek_char() {
+            // Separators go between digits, not right after the radix prefix
+            if x == '_' && has_digits {
+                separated = true;
+                self.skip_char();
+                continue;
+            }
+
             let Some(d) = x.to_digit(radix as u32) else {
                 break;
             };
@@ -125,9 +182,17 @@ impl<'input> Lexer<'input> {
                 return None;
             };
             self.int_number = r;
+            has_digits = true;
+            separated = false;
             self.skip_char();
         }
 
+        // A number must have digits, can't end with a separator (e.g. `1_000_`) and can't be
+        // directly followed by an identifier (e.g. `0x` or `0b12`)
+        if !has_digits || separated || self.peek_char().is_some_and(is_identifier) {
+            return None;
+        }
+
         Some(())
     }
 
@@ -136,22 +201,8 @@ impl<'input> Lexer<'input> {
             match x {
                 '\\' => {
                     self.skip_char();
-                    let x = self.peek_char()?;
-                    let x = match x {
-                        '0' => '\0',
-                        'n' => '\n',
-                        't' => '\t',
-                        'r' => '\r',
-                        x if x == delim => delim,
-                        '\\' => '\\',
-                        _ => {
-                            error!("invalid escaped character `{x}`");
-                            return None;
-                        }
-                    };
-
+                    let x = self.parse_escape(delim)?;
                     self.string.push(x);
-                    self.skip_char();
                 }
                 x if x == delim => break,
                 _ => {
@@ -164,16 +215,123 @@ impl<'input> Lexer<'input> {
         Some(())
     }
 
+    /// Report a literal opened on `line` which is not closed at the end of the input, an invalid
+    /// escape sequence has already been reported
+    fn unterminated(&self, literal: &str, line: usize) {
+        if self.is_eof() {
+            error!("line {}: unterminated {literal} literal", line + 1);
+        }
+    }
+
+    /// Parse an escape sequence, the leading backslash must have already been skipped
+    fn parse_escape(&mut self, delim: char) -> Option<char> {
+        let x = self.peek_char()?;
+        let x = match x {
+            'x' => {
+                self.skip_char();
+                return self.parse_escaped_number(Radix::Hex, 2, 2);
+            }
+            '0'..='7' => return self.parse_escaped_number(Radix::Oct, 1, 3),
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0c',
+            'v' => '\x0b',
+            '?' => '?',
+            '\'' => '\'',
+            '"' => '"',
+            x if x == delim => delim,
+            '\\' => '\\',
+            _ => {
+                error!(
+                    "line {}: invalid escaped character `{x}`",
+                    self.parse_point.line_number + 1
+                );
+                return None;
+            }
+        };
+
+        self.skip_char();
+        Some(x)
+    }
+
+    fn parse_escaped_number(&mut self, radix: Radix, min: usize, max: usize) -> Option<char> {
+        let mut value = 0;
+        let mut len = 0;
+
+        while len < max {
+            let Some(d) = self.peek_char().and_then(|x| x.to_digit(radix as u32)) else {
+                break;
+            };
+
+            value = value * radix as u32 + d;
+            len += 1;
+            self.skip_char();
+        }
+
+        if len < min {
+            error!(
+                "line {}: invalid escape sequence, expected at least {min} digits in radix {radix}",
+                self.parse_point.line_number + 1
+            );
+            return None;
+        }
+
+        char::from_u32(value)
+    }
+
     pub fn next_token(&mut self) -> Token {
         'comment: loop {
             self.skip_whitespaces();
 
             // This is the comment prefix so we skip everything starting at this point
-            if self.skip_prefix("<(-.-)>") {
+            let saved_position = self.parse_point.position;
+            if self.skip_prefix("<(-.-)>") || self.skip_prefix(DOC_COMMENT) {
+                if self.comments {
+                    while self.peek_char().is_some_and(|c| c != '\n') {
+                        self.skip_char();
+                    }
+
+                    return Token::new(
+                        T![Comment],
+                        Span {
+                            start: saved_position,
+                            end: self.parse_point.position,
+                            line: self.parse_point.line_number,
+                        },
+                    );
+                }
+
                 self.skip_until("\n");
                 continue 'comment;
             }
 
+            // A block comment is reported on the line of its opening delimiter
+            let saved_point = self.parse_point;
+            if self.skip_prefix(BLOCK_COMMENT_OPEN) {
+                let span = |lexer: &Self| Span {
+                    start: saved_point.position,
+                    end: lexer.parse_point.position,
+                    line: saved_point.line_number,
+                };
+
+                if !self.skip_block_comment() {
+                    error!(
+                        "line {}: unterminated block comment opened at position {}, it is closed by `{BLOCK_COMMENT_CLOSE}`",
+                        saved_point.line_number + 1,
+                        saved_point.position - saved_point.line_start,
+                    );
+                    return Token::new(T![ParseError], span(self));
+                }
+
+                if self.comments {
+                    return Token::new(T![Comment], span(self));
+                }
+                continue 'comment;
+            }
+
             break 'comment;
         }
 
@@ -268,102 +426,61 @@ impl<'input> Lexer<'input> {
         }
 
         // Check if we have a number
-        {
+        if x.is_ascii_digit() {
             let saved_position = self.parse_point.position;
-            if self.skip_prefix("0x") {
-                let Some(_) = self.parse_number(Radix::Hex) else {
-                    error!(
-                        "line {} position {}: invalid hexadecimal number",
-                        self.parse_point.line_number,
-                        self.parse_point.position - self.parse_point.line_start
-                    );
-
-                    return Token::new(
-                        T![ParseError],
-                        Span {
-                            line: self.parse_point.line_number,
-                            start: self.parse_point.position,
-                            end: self.parse_point.position,
-                        },
-                    );
-                };
+            let radix = if self.skip_prefix("0x") || self.skip_prefix("0X") {
+                Radix::Hex
+            } else if self.skip_prefix("0o") || self.skip_prefix("0O") {
+                Radix::Oct
+            } else if self.skip_prefix("0b") || self.skip_prefix("0B") {
+                Radix::Bin
+            } else {
+                Radix::Dec
+            };
 
-                self.int_number = 0;
-                return Token::new(
-                    T![IntLit],
-                    Span {
-                        line: self.parse_point.line_number,
-                        start: saved_position,
-                        end: self.parse_point.position,
-                    },
+            self.int_number = 0;
+            let Some(_) = self.parse_number(radix) else {
+                error!(
+                    "line {} position {}: invalid {} number",
+                    self.parse_point.line_number + 1,
+                    self.parse_point.position - self.parse_point.line_start,
+                    radix.name()
                 );
-            }
-
-            if self.skip_prefix("0") {
-                let Some(_) = self.parse_number(Radix::Oct) else {
-                    error!(
-                        "line {} position {}: invalid octal number",
-                        self.parse_point.line_number,
-                        self.parse_point.position - self.parse_point.line_start
-                    );
 
-                    return Token::new(
-                        T![ParseError],
-                        Span {
-                            line: self.parse_point.line_number,
-                            start: self.parse_point.position,
-                            end: self.parse_point.position,
-                        },
-                    );
-                };
+                // Skip the remaining of the invalid number so we don't produce garbage tokens
+                while self.peek_char().is_some_and(is_identifier) {
+                    self.skip_char();
+                }
 
-                self.int_number = 0;
                 return Token::new(
-                    T![IntLit],
+                    T![ParseError],
                     Span {
                         line: self.parse_point.line_number,
                         start: saved_position,
                         end: self.parse_point.position,
                     },
                 );
-            }
-
-            if x.is_digit(Radix::Dec as u32) {
-                let Some(_) = self.parse_number(Radix::Dec) else {
-                    error!(
-                        "line {} position {}: invalid decimal number",
-                        self.parse_point.line_number,
-                        self.parse_point.position - self.parse_point.line_start
-                    );
-
-                    return Token::new(
-                        T![ParseError],
-                        Span {
-                            line: self.parse_point.line_number,
-                            start: self.parse_point.position,
-                            end: self.parse_point.position,
-                        },
-                    );
-                };
+            };
 
-                self.int_number = 0;
-                return Token::new(
-                    T![IntLit],
-                    Span {
-                        line: self.parse_point.line_number,
-                        start: saved_position,
-                        end: self.parse_point.position,
-                    },
-                );
-            }
+            return Token::with_value(
+                T![IntLit],
+                Span {
+                    line: self.parse_point.line_number,
+                    start: saved_position,
+                    end: self.parse_point.position,
+                },
+                self.int_number,
+            );
         }
 
         // Check if we have a string
         if x == '"' {
-            let saved_position = self.parse_point.position;
+            let saved_point = self.parse_point;
+            let saved_position = saved_point.position;
             self.skip_char();
             self.string.clear();
             if self.parse_string('"').is_none() || self.is_eof() {
+                self.unterminated("string", saved_point.line_number);
                 return Token::new(
                     T![ParseError],
                     Span {
@@ -385,10 +502,12 @@ impl<'input> Lexer<'input> {
         }
 
         if x == '\'' {
-            let saved_position = self.parse_point.position;
+            let saved_point = self.parse_point;
+            let saved_position = saved_point.position;
             self.skip_char();
             self.string.clear();
             if self.parse_string('\'').is_none() || self.is_eof() {
+                self.unterminated("character", saved_point.line_number);
                 return Token::new(
                     T![ParseError],
                     Span {
@@ -402,8 +521,12 @@ impl<'input> Lexer<'input> {
 
             let chars: Vec<char> = self.string.chars().collect();
 
-            // TODO: add the error diagnostic and handling
             if chars.len() != 1 {
+                error!(
+                    "line {}: a character literal holds exactly one character, found {}",
+                    self.parse_point.line_number + 1,
+                    chars.len()
+                );
                 return Token::new(
                     T![ParseError],
                     Span {
@@ -416,16 +539,21 @@ impl<'input> Lexer<'input> {
 
             self.char_lit = chars[0];
 
-            return Token::new(
+            return Token::with_value(
                 T![CharLit],
                 Span {
                     line: self.parse_point.line_number,
                     start: saved_position,
                     end: self.parse_point.position,
                 },
+                self.char_lit as u64,
             );
         }
 
+        error!(
+            "line {}: unknown character `{x}`",
+            self.parse_point.line_number + 1
+        );
         self.skip_char();
         Token::new(
             T![ParseError],
@@ -455,22 +583,45 @@ impl<'input> Iterator for Lexer<'input> {
     }
 }
 
-fn is_identifier(c: char) -> bool {
+/// Spellings of the tokens of `kind`, some tokens have synonymous spellings
+pub fn synonyms(kind: token::TokenKind) -> Vec<&'static str> {
+    PUNCTS
+        .iter()
+        .chain(KEYWORDS)
+        .chain(TYPES)
+        .filter(|(_, k)| *k == kind)
+        .map(|(spelling, _)| *spelling)
+        .collect()
+}
+
+pub(crate) fn is_identifier(c: char) -> bool {
     c.is_alphanumeric() || c == '_'
 }
 
-fn is_identifier_start(c: char) -> bool {
+pub(crate) fn is_identifier_start(c: char) -> bool {
     c.is_alphabetic() || c == '_'
 }
 
 #[repr(u8)]
 #[derive(Clone, Copy)]
 enum Radix {
+    Bin = 2,
     Oct = 8,
     Dec = 10,
     Hex = 16,
 }
 
+impl Radix {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bin => "binary",
+            Self::Oct => "octal",
+            Self::Dec => "decimal",
+            Self::Hex => "hexadecimal",
+        }
+    }
+}
+
 impl std::fmt::Display for Radix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", *self as u8)
@@ -526,7 +677,7 @@ mod tests {
                 Who, mesa ? 10
             May the force be with you.
         "#;
-        let mut lexer = Lexer::new(&input);
+        let mut lexer = Lexer::new(input);
         let tokens: Vec<_> = lexer.tokenize();
         assert_tokens!(
             tokens,
@@ -556,7 +707,7 @@ mod tests {
                 Order executed
             May the force be with you.
         "#;
-        let mut lexer = Lexer::new(&input);
+        let mut lexer = Lexer::new(input);
         let tokens: Vec<_> = lexer.tokenize();
         assert_tokens!(
             tokens,
@@ -578,6 +729,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn builtin_print() {
+        let input = r#"
+            You'll find I'm full of surprises. finn
+            You'll find I'm full of surprises. That's impossible!
+        "#;
+        let mut lexer = Lexer::new(input);
+        let tokens: Vec<_> = lexer.tokenize();
+        assert_tokens!(tokens, [T![Print], T![ID], T![Print], T![False], T![EOF]]);
+    }
+
+    #[test]
+    fn assert_and_panic() {
+        let input = r#"
+            I have a bad feeling about this. shields "shields are down"
+            It's a trap!
+        "#;
+        let mut lexer = Lexer::new(input);
+        let tokens: Vec<_> = lexer.tokenize();
+        assert_tokens!(tokens, [T![Assert], T![ID], T![String], T![Panic], T![EOF]]);
+    }
+
+    #[test]
+    fn logical_operators() {
+        let input = r#"
+            You and what army? I find your lack of faith disturbing. x
+            These are the droids you're looking for. x 1
+            These aren't the droids you're looking for.
+            One way or another. Smaller than a womp rat. x -1
+        "#;
+        let mut lexer = Lexer::new(input);
+        let tokens: Vec<_> = lexer.tokenize();
+        assert_tokens!(
+            tokens,
+            [
+                T![And],
+                T![LNot],
+                T![ID],
+                T![Eq],
+                T![ID],
+                T![IntLit],
+                T![Else],
+                T![Or],
+                T![Lt],
+                T![ID],
+                T![Not],
+                T![IntLit],
+                T![EOF]
+            ]
+        );
+    }
+
     #[test]
     fn keywords_with_print_call_string() {
         let input = r#"
@@ -590,7 +793,7 @@ mod tests {
                 Order executed
             May the force be with you.
         "#;
-        let mut lexer = Lexer::new(&input);
+        let mut lexer = Lexer::new(input);
         let tokens: Vec<_> = lexer.tokenize();
         assert_tokens!(
             tokens,
@@ -615,10 +818,123 @@ mod tests {
     #[test]
     fn types() {
         let input = r#"
-            Credit Holotext Signal
+            Credit Holotext Signal Aurebesh
         "#;
-        let mut lexer = Lexer::new(&input);
+        let mut lexer = Lexer::new(input);
         let tokens: Vec<_> = lexer.tokenize();
-        assert_tokens!(tokens, [T![TyInt], T![TyString], T![TyBool], T![EOF]]);
+        assert_tokens!(
+            tokens,
+            [T![TyInt], T![TyString], T![TyBool], T![TyChar], T![EOF]]
+        );
+    }
+
+    #[test]
+    fn int_literals() {
+        let input = "10 0x1F 0X1f 0o17 0O17 0b101 0B1_01 017 1_000_000";
+        let mut lexer = Lexer::new(input);
+        let values: Vec<_> = lexer
+            .tokenize()
+            .iter()
+            .filter(|t| t.kind == T![IntLit])
+            .map(|t| t.value)
+            .collect();
+        assert_eq!(values, [10, 31, 31, 15, 15, 5, 5, 17, 1_000_000]);
+    }
+
+    #[test]
+    fn invalid_int_literals() {
+        let input = "0x 0b12 0o8 12ab 99999999999999999999";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_tokens!(
+            tokens,
+            [
+                T![ParseError],
+                T![ParseError],
+                T![ParseError],
+                T![ParseError],
+                T![ParseError],
+                T![EOF]
+            ]
+        );
+    }
+
+    #[test]
+    fn misplaced_int_separators() {
+        let input = "1_000_ 0x_ff 0b_1 0o17_ 1__0";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_tokens!(
+            tokens,
+            [
+                T![ParseError],
+                T![ParseError],
+                T![ParseError],
+                T![ParseError],
+                T![IntLit],
+                T![EOF]
+            ]
+        );
+    }
+
+    #[test]
+    fn char_literals() {
+        let input = r#"'a' '\n' '\x41' '\'' '\101' '\0'"#;
+        let mut lexer = Lexer::new(input);
+        let values: Vec<_> = lexer
+            .tokenize()
+            .iter()
+            .filter(|t| t.kind == T![CharLit])
+            .map(|t| t.value)
+            .collect();
+        assert_eq!(values, [97, 10, 65, 39, 65, 0]);
+    }
+
+    #[test]
+    fn block_comments() {
+        let input = "<(o.o) one\n <(o.o) nested (o.o)> (o.o)>finn <(o.o)(o.o)>\n<(o.o) two\nlines (o.o)> 10";
+        let tokens = Lexer::new(input).tokenize();
+        assert_tokens!(tokens, [T![ID], T![IntLit], T![EOF]]);
+        assert_eq!((tokens[0].span.line, tokens[1].span.line), (1, 3));
+
+        let tokens = Lexer::with_comments(input).tokenize();
+        assert_tokens!(
+            tokens,
+            [
+                T![Comment],
+                T![ID],
+                T![Comment],
+                T![Comment],
+                T![IntLit],
+                T![EOF]
+            ]
+        );
+        assert_eq!(tokens[0].span.line, 0);
+        assert_eq!(tokens[3].span.line, 2);
+        assert_eq!(tokens[3].text(input), "<(o.o) two\nlines (o.o)>");
+    }
+
+    #[test]
+    fn unterminated_block_comment() {
+        let input = "finn\n  <(o.o) <(o.o) nested (o.o)>\n";
+        let (tokens, records) = crate::log::capture(|| Lexer::new(input).tokenize());
+        assert_tokens!(tokens, [T![ID], T![ParseError], T![EOF]]);
+        assert_eq!(tokens[1].span.line, 1);
+        assert_eq!(tokens[1].span.start, 7);
+
+        // The error is reported on the line of the opening delimiter
+        assert_eq!(records.len(), 1);
+        assert!(records[0]
+            .message
+            .starts_with("line 2: unterminated block comment"));
+    }
+
+    #[test]
+    fn invalid_char_literals() {
+        let input = r#"'ab' '\xZ'"#;
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_tokens!(tokens, [T![ParseError]]);
+        assert!(tokens.iter().all(|t| t.kind != T![CharLit]));
     }
 }
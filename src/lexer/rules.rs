@@ -7,6 +7,13 @@ pub const PUNCTS: &[(&str, super::token::TokenKind)] = &[
         "Your lightsabers will make a fine addition to my collection.",
         T![Plus],
     ),
+    ("We need reinforcements!", T![Push]),
+    ("These are the droids you're looking for.", T![Eq]),
+    ("Smaller than a womp rat.", T![Lt]),
+    ("Bigger than a Star Destroyer.", T![Gt]),
+    ("You and what army?", T![And]),
+    ("One way or another.", T![Or]),
+    ("I find your lack of faith disturbing.", T![LNot]),
     ("-", T![Not]),
 ];
 
@@ -19,6 +26,30 @@ pub const KEYWORDS: &[(&str, super::token::TokenKind)] = &[
     ("Do, or do not. There is no try.", T![If]),
     ("These aren't the droids you're looking for.", T![Else]),
     ("You have failed me for the last time.", T![IfEnd]),
+    ("Here we go again.", T![OWhile]),
+    ("Great, kid. Don't get cocky.", T![CWhile]),
+    ("Lock S-foils in attack position.", T![OArray]),
+    ("Stay on target.", T![CArray]),
+    ("at sector", T![Index]),
+    ("Size matters not.", T![Len]),
+    ("Help me, Obi-Wan Kenobi.", T![ORecord]),
+    ("You're my only hope.", T![CRecord]),
+    ("Prepare my ship.", T![ONew]),
+    ("Punch it!", T![CNew]),
+    ("of the", T![Field]),
+    ("Coordinates of", T![AddrOf]),
+    ("Jump to", T![Deref]),
+    ("Lost in hyperspace.", T![Null]),
+    ("Send in the clones.", T![Alloc]),
+    ("Begin recording.", T![Text]),
+    ("Let go, Luke.", T![Free]),
+    ("You'll find I'm full of surprises.", T![Print]),
+    ("What is thy bidding, my master?", T![Read]),
+    ("I have a bad feeling about this.", T![Assert]),
+    ("It's a trap!", T![Panic]),
+    ("Together we can rule the galaxy.", T![Concat]),
+    ("A piece of", T![Substr]),
+    ("Incoming transmission:", T![Interp]),
     ("I am your father.", T![Assign]),
     ("Judge me by my size, do you ?", T![Assign]),
     ("Who, mesa ?", T![Assign]),
@@ -29,6 +60,8 @@ pub const KEYWORDS: &[(&str, super::token::TokenKind)] = &[
     ("That's one hell of a pilot.", T![Let]),
     ("From a certain point of view.", T![True]),
     ("That's impossible!", T![False]),
+    ("You're coming with me.", T![Import]),
+    ("Hello there!", T![Pub]),
     ("Starfield", T![Variadic]),
     ("Hypersignal", T![OExtrnFn]),
     ("Jamsignal", T![CExtrnFn]),
@@ -40,4 +73,7 @@ pub const TYPES: &[(&str, super::token::TokenKind)] = &[
     ("Credit", T![TyInt]),
     ("Holotext", T![TyString]),
     ("Signal", T![TyBool]),
+    ("Aurebesh", T![TyChar]),
+    ("Squadron", T![TySlice]),
+    ("Tracker", T![TyPtr]),
 ];
@@ -6,6 +6,7 @@ use std::fmt;
 macro_rules! T {
     [EOF] => { $crate::lexer::token::TokenKind::EOF };
     [ParseError] => { $crate::lexer::token::TokenKind::ParseError };
+    [Comment] => { $crate::lexer::token::TokenKind::Comment };
     [ID] => { $crate::lexer::token::TokenKind::ID };
     [String] => { $crate::lexer::token::TokenKind::String };
     [CharLit] => { $crate::lexer::token::TokenKind::CharLit };
@@ -19,16 +20,51 @@ macro_rules! T {
     [Plus] => { $crate::lexer::token::TokenKind::Plus };
     [Minus] => { $crate::lexer::token::TokenKind::Minus };
     [Eq] => { $crate::lexer::token::TokenKind::Eq };
+    [Lt] => { $crate::lexer::token::TokenKind::Lt };
+    [Gt] => { $crate::lexer::token::TokenKind::Gt };
+    [And] => { $crate::lexer::token::TokenKind::And };
+    [Or] => { $crate::lexer::token::TokenKind::Or };
+    [LNot] => { $crate::lexer::token::TokenKind::LNot };
     [TyInt] => { $crate::lexer::token::TokenKind::TyInt };
     [TyString] => { $crate::lexer::token::TokenKind::TyString };
     [TyBool] => { $crate::lexer::token::TokenKind::TyBool };
+    [TyChar] => { $crate::lexer::token::TokenKind::TyChar };
+    [TySlice] => { $crate::lexer::token::TokenKind::TySlice };
+    [OArray] => { $crate::lexer::token::TokenKind::OArray };
+    [CArray] => { $crate::lexer::token::TokenKind::CArray };
+    [Index] => { $crate::lexer::token::TokenKind::Index };
+    [Len] => { $crate::lexer::token::TokenKind::Len };
+    [ORecord] => { $crate::lexer::token::TokenKind::ORecord };
+    [CRecord] => { $crate::lexer::token::TokenKind::CRecord };
+    [ONew] => { $crate::lexer::token::TokenKind::ONew };
+    [CNew] => { $crate::lexer::token::TokenKind::CNew };
+    [Field] => { $crate::lexer::token::TokenKind::Field };
+    [TyPtr] => { $crate::lexer::token::TokenKind::TyPtr };
+    [AddrOf] => { $crate::lexer::token::TokenKind::AddrOf };
+    [Deref] => { $crate::lexer::token::TokenKind::Deref };
+    [Null] => { $crate::lexer::token::TokenKind::Null };
+    [Push] => { $crate::lexer::token::TokenKind::Push };
+    [Alloc] => { $crate::lexer::token::TokenKind::Alloc };
+    [Text] => { $crate::lexer::token::TokenKind::Text };
+    [Free] => { $crate::lexer::token::TokenKind::Free };
+    [Print] => { $crate::lexer::token::TokenKind::Print };
+    [Read] => { $crate::lexer::token::TokenKind::Read };
+    [Assert] => { $crate::lexer::token::TokenKind::Assert };
+    [Panic] => { $crate::lexer::token::TokenKind::Panic };
+    [Concat] => { $crate::lexer::token::TokenKind::Concat };
+    [Substr] => { $crate::lexer::token::TokenKind::Substr };
+    [Interp] => { $crate::lexer::token::TokenKind::Interp };
     [OAssign] => { $crate::lexer::token::TokenKind::OAssign };
     [Assign] => { $crate::lexer::token::TokenKind::Assign };
     [CAssign] => { $crate::lexer::token::TokenKind::CAssign };
     [If] => { $crate::lexer::token::TokenKind::If };
     [IfEnd] => { $crate::lexer::token::TokenKind::IfEnd};
     [Else] => { $crate::lexer::token::TokenKind::Else };
+    [OWhile] => { $crate::lexer::token::TokenKind::OWhile };
+    [CWhile] => { $crate::lexer::token::TokenKind::CWhile };
     [Let] => { $crate::lexer::token::TokenKind::Let };
+    [Import] => { $crate::lexer::token::TokenKind::Import };
+    [Pub] => { $crate::lexer::token::TokenKind::Pub };
     [OFnDecl1] => { $crate::lexer::token::TokenKind::OFnDecl1 };
     [OFnDecl2] => { $crate::lexer::token::TokenKind::OFnDecl2 };
     [CFnDecl] => { $crate::lexer::token::TokenKind::CFnDecl };
@@ -45,11 +81,22 @@ macro_rules! T {
 pub struct Token {
     pub kind: TokenKind,
     pub span: Span,
+
+    // The value decoded by the lexer for literal tokens (the code point of char literals)
+    pub value: u64,
 }
 
 impl Token {
     pub fn new(kind: TokenKind, span: Span) -> Token {
-        Self { kind, span }
+        Self {
+            kind,
+            span,
+            value: 0,
+        }
+    }
+
+    pub fn with_value(kind: TokenKind, span: Span, value: u64) -> Token {
+        Self { kind, span, value }
     }
 
     pub fn text<'input>(&self, input: &'input str) -> &'input str {
@@ -78,6 +125,8 @@ pub enum TokenKind {
     // Terminal
     EOF,
     ParseError,
+    // Only produced by a lexer keeping the comments
+    Comment,
 
     // Values
     ID,
@@ -94,13 +143,21 @@ pub enum TokenKind {
     Mod,
     Plus,
     Minus,
-    #[allow(dead_code)]
+    Push,
     Eq,
+    Lt,
+    Gt,
+    And,
+    Or,
+    LNot,
 
     // Types
     TyInt,
     TyString,
     TyBool,
+    TyChar,
+    TySlice,
+    TyPtr,
 
     // Keywords
     OAssign,
@@ -109,7 +166,33 @@ pub enum TokenKind {
     If,
     IfEnd,
     Else,
+    OWhile,
+    CWhile,
+    OArray,
+    CArray,
+    Index,
+    Len,
+    ORecord,
+    CRecord,
+    ONew,
+    CNew,
+    Field,
+    AddrOf,
+    Deref,
+    Null,
+    Alloc,
+    Text,
+    Free,
+    Print,
+    Read,
+    Assert,
+    Panic,
+    Concat,
+    Substr,
+    Interp,
     Let,
+    Import,
+    Pub,
     OFnDecl1,
     OFnDecl2,
     CFnDecl,
@@ -130,6 +213,7 @@ impl fmt::Display for TokenKind {
             match self {
                 T![EOF] => "<eof>",
                 T![ParseError] => "<?>",
+                T![Comment] => "<(-.-)>",
                 T![ID] => "Identifier",
                 T![String] => "String",
                 T![CharLit] => "Char literal",
@@ -142,17 +226,52 @@ impl fmt::Display for TokenKind {
                 T![Mod] => "Mod",
                 T![Plus] => "Plus",
                 T![Minus] => "Minus",
+                T![Push] => "Push",
                 T![Eq] => "Eq",
+                T![Lt] => "Lower than",
+                T![Gt] => "Greater than",
+                T![And] => "And",
+                T![Or] => "Or",
+                T![LNot] => "Logical not",
                 T![TyInt] => "Type int",
                 T![TyString] => "Type string",
                 T![TyBool] => "Type bool",
+                T![TyChar] => "Type char",
+                T![TySlice] => "Type slice",
+                T![TyPtr] => "Type pointer",
                 T![OAssign] => "Open assign",
                 T![Assign] => "Assign",
                 T![CAssign] => "Close assign",
                 T![If] => "If",
                 T![IfEnd] => "IfEnd",
                 T![Else] => "Else",
+                T![OWhile] => "Opening while loop",
+                T![CWhile] => "Closing while loop",
+                T![OArray] => "Opening array literal",
+                T![CArray] => "Closing array literal",
+                T![Index] => "Index",
+                T![Len] => "Length",
+                T![ORecord] => "Opening record declaration",
+                T![CRecord] => "Closing record declaration",
+                T![ONew] => "Opening record construction",
+                T![CNew] => "Closing record construction",
+                T![Field] => "Field",
+                T![AddrOf] => "Address of",
+                T![Deref] => "Dereference",
+                T![Null] => "Null",
+                T![Alloc] => "Allocation",
+                T![Text] => "Dynamic string",
+                T![Free] => "Free",
+                T![Print] => "Print",
+                T![Read] => "Read",
+                T![Assert] => "Assert",
+                T![Panic] => "Panic",
+                T![Concat] => "Concatenation",
+                T![Substr] => "Substring",
+                T![Interp] => "Interpolated string",
                 T![Let] => "Let",
+                T![Import] => "Import",
+                T![Pub] => "Public",
                 T![OFnDecl1] => "Opening function declaration 1",
                 T![OFnDecl2] => "Opening function declaration 2",
                 T![CFnDecl] => "Closing function declaration 1",
@@ -0,0 +1,226 @@
+//! Trivia-preserving lexing
+//!
+//! The lexer skips the whitespaces and the comments, here they are kept as the trivia of the
+//! tokens so that the source can be printed back byte-for-byte. The trailing trivia of a token
+//! holds what follows it on its line (whitespaces and a comment), the newline and everything
+//! after it are the leading trivia of the next token. The bytes the lexer does not recognise
+//! are kept as skipped trivia.
+
+use std::fmt;
+
+use super::token::{Span, Token};
+use super::Lexer;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TriviaKind {
+    // Spaces and tabs, a newline is its own trivia
+    Whitespace,
+    Newline,
+    Comment,
+    // Comment starting with `<(^.^)>`, it documents the declaration below it
+    DocComment,
+    // Comment between `<(o.o)` and `(o.o)>`, it may span several lines
+    BlockComment,
+    // Bytes the lexer does not recognise as a token
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Trivia<'input> {
+    pub kind: TriviaKind,
+    pub span: Span,
+    pub text: &'input str,
+}
+
+#[derive(Debug, Clone)]
+pub struct TriviaToken<'input> {
+    pub leading: Vec<Trivia<'input>>,
+    pub token: Token,
+    pub text: &'input str,
+    pub trailing: Vec<Trivia<'input>>,
+}
+
+impl fmt::Display for TriviaToken<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for trivia in &self.leading {
+            f.write_str(trivia.text)?;
+        }
+        f.write_str(self.text)?;
+        for trivia in &self.trailing {
+            f.write_str(trivia.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split the source into tokens holding their trivia, the last token is `T![EOF]` whose
+/// leading trivia ends the source
+pub fn tokenize(source: &str) -> Vec<TriviaToken<'_>> {
+    let mut tokens: Vec<TriviaToken> = Vec::new();
+    let mut pending = Vec::new();
+    let mut position = 0;
+    let mut line = 0;
+
+    // Invalid tokens are not kept, their bytes end up in the skipped trivia. The lexer reads
+    // bytes so a token can end inside a non-ASCII character
+    let valid = |t: &Token| {
+        t.kind != T![ParseError]
+            && source.is_char_boundary(t.span.start)
+            && source.is_char_boundary(t.span.end)
+    };
+    for token in Lexer::with_comments(source).filter(valid) {
+        split_gap(source, position..token.span.start, &mut line, &mut pending);
+        position = token.span.end;
+
+        if token.kind == T![Comment] {
+            let text = token.text(source);
+            let kind = if text.starts_with(super::DOC_COMMENT) {
+                TriviaKind::DocComment
+            } else if text.starts_with(super::BLOCK_COMMENT_OPEN) {
+                TriviaKind::BlockComment
+            } else {
+                TriviaKind::Comment
+            };
+            pending.push(Trivia {
+                kind,
+                span: Span { line, ..token.span },
+                text,
+            });
+            line += text.matches('\n').count();
+            continue;
+        }
+
+        // What precedes the first newline stays on the line of the previous token
+        if let Some(previous) = tokens.last_mut() {
+            let end = pending
+                .iter()
+                .position(|t| t.kind == TriviaKind::Newline)
+                .unwrap_or(pending.len());
+            previous.trailing.extend(pending.drain(..end));
+        }
+
+        line += source[token.span].matches('\n').count();
+        tokens.push(TriviaToken {
+            leading: std::mem::take(&mut pending),
+            token,
+            text: token.text(source),
+            trailing: Vec::new(),
+        });
+    }
+
+    tokens
+}
+
+/// Push the trivia of the bytes between two tokens, `line` is updated with their newlines
+fn split_gap<'input>(
+    source: &'input str,
+    gap: std::ops::Range<usize>,
+    line: &mut usize,
+    trivia: &mut Vec<Trivia<'input>>,
+) {
+    let bytes = source.as_bytes();
+    let mut start = gap.start;
+
+    while start < gap.end {
+        let is_space = |b: u8| b.is_ascii_whitespace() && b != b'\n';
+        let (kind, end) = match bytes[start] {
+            b'\n' => (TriviaKind::Newline, start + 1),
+            b'\r' if bytes.get(start + 1) == Some(&b'\n') => (TriviaKind::Newline, start + 2),
+            b if is_space(b) => (
+                TriviaKind::Whitespace,
+                (start..gap.end)
+                    .find(|i| !is_space(bytes[*i]) || source[*i..].starts_with("\r\n"))
+                    .unwrap_or(gap.end),
+            ),
+            _ => (
+                TriviaKind::Skipped,
+                (start..gap.end)
+                    .find(|i| bytes[*i].is_ascii_whitespace())
+                    .unwrap_or(gap.end),
+            ),
+        };
+
+        trivia.push(Trivia {
+            kind,
+            span: Span {
+                start,
+                end,
+                line: *line,
+            },
+            text: &source[start..end],
+        });
+        if kind == TriviaKind::Newline {
+            *line += 1;
+        }
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doc_comments() {
+        let source = "<(^.^)> Ignored\n\n<(^.^)> Launch\n  <(^.^)>   the  ship \n<(-.-)> Note\n<(^.^)>\n<(^.^)>It flies\nHello there!\n<(^.^)> Too far\n\nHello there!";
+        let tokens = tokenize(source);
+
+        assert_eq!(tokens[0].leading[3].kind, TriviaKind::DocComment,);
+        assert_eq!(tokens[1].leading[0].kind, TriviaKind::Newline);
+    }
+
+    #[test]
+    fn block_comments() {
+        use TriviaKind::*;
+
+        let source = "<(o.o) a\n<(o.o) b (o.o)>\n(o.o)> x <(o.o) c (o.o)>\ny";
+        let tokens = tokenize(source);
+
+        assert_eq!(kinds(&tokens[0].leading), [BlockComment, Whitespace]);
+        assert_eq!(kinds(&tokens[0].trailing), [Whitespace, BlockComment]);
+        assert_eq!(tokens[1].leading[0].span.line, 2);
+        assert_eq!(tokens[1].token.span.line, 3);
+        assert_eq!(
+            tokens.iter().map(|t| t.to_string()).collect::<String>(),
+            source
+        );
+    }
+
+    fn kinds(trivia: &[Trivia]) -> Vec<TriviaKind> {
+        trivia.iter().map(|t| t.kind).collect()
+    }
+
+    #[test]
+    fn leading_and_trailing() {
+        use TriviaKind::*;
+
+        let source = "<(-.-)> Head\r\n  Hello there! \t<(-.-)> Tail\n\n  x $ y\n";
+        let tokens = tokenize(source);
+
+        let kinds_of = |t: &TriviaToken| (t.token.kind, kinds(&t.leading), kinds(&t.trailing));
+        assert_eq!(
+            tokens.iter().map(kinds_of).collect::<Vec<_>>(),
+            [
+                (
+                    T![Pub],
+                    vec![Comment, Newline, Whitespace],
+                    vec![Whitespace, Comment]
+                ),
+                (
+                    T![ID],
+                    vec![Newline, Newline, Whitespace],
+                    vec![Whitespace, Skipped, Whitespace]
+                ),
+                (T![ID], vec![], vec![]),
+                (T![EOF], vec![Newline], vec![]),
+            ]
+        );
+
+        assert_eq!(tokens[1].trailing[1].text, "$");
+        assert_eq!(tokens[1].leading[2].span.line, 3);
+        assert_eq!(
+            tokens.iter().map(|t| t.to_string()).collect::<String>(),
+            source
+        );
+    }
+}
@@ -2,10 +2,11 @@
 #![allow(dead_code)]
 
 use core::fmt;
+use std::cell::RefCell;
 
 pub static mut LOGGER: Logger = Logger::new(LogLevel::Info);
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogLevel {
     Info,
     Warning,
@@ -36,14 +37,74 @@ impl Logger {
     }
 
     pub fn log(&self, level: LogLevel, args: fmt::Arguments<'_>) {
-        if self.threshold > level {
+        let captured = CAPTURE.with_borrow_mut(|capture| {
+            let Some(records) = capture else {
+                return false;
+            };
+
+            let (file, line) = CONTEXT.with_borrow(|context| context.clone());
+            records.push(Record {
+                level,
+                file,
+                line,
+                message: args.to_string(),
+            });
+            true
+        });
+        if captured || self.threshold > level {
             return;
         }
 
-        eprintln!("[{level}] {args}");
+        // The messages logged while compiling a file are prefixed with its path
+        match CONTEXT.with_borrow(|(file, _)| file.clone()) {
+            Some(file) => eprintln!("[{level}] {file}: {args}"),
+            None => eprintln!("[{level}] {args}"),
+        }
     }
 }
 
+/// A message logged while the messages are captured
+#[derive(Debug)]
+pub struct Record {
+    pub level: LogLevel,
+    // Source file and line of the item being compiled when the message was logged
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Vec<Record>>> = const { RefCell::new(None) };
+    static CONTEXT: RefCell<(Option<String>, Option<usize>)> = const { RefCell::new((None, None)) };
+}
+
+/// Run `f` and return the messages it logs instead of printing them, the language server
+/// turns them into diagnostics
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Record>) {
+    // The previous capture is restored even if `f` panics
+    struct Restore(Option<Option<Vec<Record>>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(previous) = self.0.take() {
+                CAPTURE.set(previous);
+            }
+        }
+    }
+
+    let mut restore = Restore(Some(CAPTURE.replace(Some(Vec::new()))));
+    let result = f();
+    // SAFETY: the previous capture is only taken here and on drop
+    let previous = restore.0.take().unwrap();
+    let records = CAPTURE.replace(previous).unwrap_or_default();
+    (result, records)
+}
+
+/// Set the file and the line of the item being compiled, they locate the captured messages
+/// which don't tell their line
+pub fn set_context(file: Option<&str>, line: Option<usize>) {
+    CONTEXT.set((file.map(String::from), line));
+}
+
 macro_rules! log {
     ($level:ident, $($args:tt)*) => {
         // SAFETY: this is safe cause we know we're not mutating LOGGER
@@ -0,0 +1,533 @@
+//! Analysis of the documents edited in the language server
+//!
+//! A document is checked like `hsl check` does, with the files of its project when it is one of
+//! them and alone otherwise. The messages logged by the compiler become the diagnostics of the
+//! document and the resolved program answers the hover, definition and completion requests.
+//! Positions are byte offsets in the document, the server converts them.
+
+use std::path::{Path, PathBuf};
+
+use crate::cst::{Element, Node, NodeKind};
+use crate::ir::{Program, Type};
+use crate::lexer::token::Span;
+use crate::lexer::trivia::TriviaToken;
+use crate::log::LogLevel;
+use crate::parser::slt::SymbolLookupTable;
+
+pub struct Diagnostic {
+    pub line: usize,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// A place in a source file, the span holds byte offsets in the file
+pub struct Location {
+    pub path: PathBuf,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompletionKind {
+    Keyword,
+    Function,
+    Variable,
+    Record,
+}
+
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: Option<String>,
+}
+
+/// A function or an extern declared in a document
+pub struct Symbol<'input> {
+    pub name: &'input str,
+    pub external: bool,
+    // The whole declaration and its name
+    pub span: Span,
+    pub name_span: Span,
+}
+
+/// The program of a document once its names are resolved
+pub struct Checked<'a, 'prog> {
+    pub program: &'a Program<'prog>,
+    pub slt: &'a SymbolLookupTable<'prog>,
+    pub path: &'a Path,
+    // Module of the document
+    pub module: usize,
+}
+
+/// Check the document at `path` whose content is `text`, `f` is given the program if the
+/// names of the document could be resolved. Returns the diagnostics of the document
+pub fn check<R>(
+    path: &Path,
+    text: &str,
+    f: impl FnOnce(Option<&Checked<'_, '_>>) -> R,
+) -> (Vec<Diagnostic>, R) {
+    let Some(file) = path.to_str() else {
+        return (Vec::new(), f(None));
+    };
+    // A new document is not on disk yet, its unsaved content is compiled under its path
+    let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+    let (project, _) =
+        crate::log::capture(|| path.parent().and_then(crate::project::Project::find));
+    let project = project.filter(|p| p.files.iter().any(|f| same_file(f, &canonical)));
+    let files = match &project {
+        Some(project) => project.files.iter().map(String::as_str).collect(),
+        None => vec![file],
+    };
+    let flags = crate::flags::Flags::check("hsl", files, project.as_ref());
+
+    let (result, records) = crate::log::capture(|| {
+        let arena = crate::arena::Arena::new();
+        let Some(mut c) = crate::compiler::Compiler::new(&arena, flags) else {
+            return f(None);
+        };
+        c.unsaved.push((canonical.clone(), text.to_string()));
+
+        if c.parse_modules().is_err() {
+            return f(None);
+        }
+
+        let mut slt_builder = crate::parser::slt::Builder::new();
+        let mut slt = slt_builder.region();
+        if crate::resolver::resolve(&mut c.program, &mut slt_builder, &mut slt, false) == 0 {
+            crate::semantic::validate(&c.program, &(&slt).into());
+        }
+
+        let module = c
+            .program
+            .modules
+            .iter()
+            .position(|m| m.source.is_none() && same_file(m.path, &canonical));
+        match module {
+            Some(module) => f(Some(&Checked {
+                program: &c.program,
+                slt: &slt,
+                path,
+                module,
+            })),
+            None => f(None),
+        }
+    });
+
+    // The messages which don't tell their file are about the whole program
+    let diagnostics = records
+        .into_iter()
+        .filter(|r| r.level >= LogLevel::Warning)
+        .filter(|r| r.file.as_deref().is_none_or(|f| same_file(f, &canonical)))
+        .map(|r| match split_line(&r.message) {
+            Some((line, message)) => Diagnostic {
+                line,
+                level: r.level,
+                message: message.to_string(),
+            },
+            None => Diagnostic {
+                line: r.line.unwrap_or(0),
+                level: r.level,
+                message: r.message,
+            },
+        })
+        .collect();
+
+    (diagnostics, result)
+}
+
+/// The keywords and the types of the language
+pub fn keywords() -> Vec<Completion> {
+    use crate::lexer::rules::{KEYWORDS, TYPES};
+
+    KEYWORDS
+        .iter()
+        .chain(TYPES)
+        .map(|(spelling, _)| Completion {
+            label: spelling.to_string(),
+            kind: CompletionKind::Keyword,
+            detail: None,
+        })
+        .collect()
+}
+
+/// The functions and the externs declared in the document
+pub fn symbols<'input>(root: &Node<'input>) -> Vec<Symbol<'input>> {
+    root.children
+        .iter()
+        .filter_map(|child| match child {
+            Element::Node(node) if matches!(node.kind, NodeKind::Function | NodeKind::Extern) => {
+                let tokens = node.tokens();
+                let name = tokens.iter().find(|t| t.token.kind == T![ID])?;
+                Some(Symbol {
+                    name: name.text,
+                    external: node.kind == NodeKind::Extern,
+                    span: Span {
+                        start: tokens.first()?.token.span.start,
+                        end: tokens.last()?.token.span.end,
+                        line: name.token.span.line,
+                    },
+                    name_span: name.token.span,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The identifier at `offset` and whether it names a function
+fn identifier_at<'input>(root: &Node<'input>, offset: usize) -> Option<(&'input str, bool)> {
+    let tokens = root.tokens();
+    let index = tokens.iter().position(|t| {
+        t.token.kind == T![ID] && (t.token.span.start..=t.token.span.end).contains(&offset)
+    })?;
+
+    let function = index > 0
+        && matches!(
+            tokens[index - 1].token.kind,
+            T![OFnCall] | T![OFnDecl1] | T![OExtrnFn]
+        );
+    Some((tokens[index].text, function))
+}
+
+/// The function of the document holding `offset`
+fn enclosing_function<'a, 'input>(
+    root: &'a Node<'input>,
+    offset: usize,
+) -> Option<&'a Node<'input>> {
+    root.children.iter().find_map(|child| match child {
+        Element::Node(node) if node.kind == NodeKind::Function => {
+            let tokens = node.tokens();
+            let start = tokens.first()?.token.span.start;
+            let end = tokens.last()?.token.span.end;
+            (start..=end).contains(&offset).then_some(node)
+        }
+        _ => None,
+    })
+}
+
+fn name_of<'input>(function: &Node<'input>) -> Option<&'input str> {
+    function
+        .tokens()
+        .into_iter()
+        .find(|t| t.token.kind == T![ID])
+        .map(|t| t.text)
+}
+
+/// The token naming the parameter `name` of the function
+fn parameter<'a, 'input>(
+    function: &'a Node<'input>,
+    name: &str,
+) -> Option<&'a TriviaToken<'input>> {
+    function.children.iter().find_map(|child| match child {
+        Element::Node(params) if params.kind == NodeKind::Params => params
+            .tokens()
+            .into_iter()
+            .find(|t| t.token.kind == T![ID] && t.text == name),
+        _ => None,
+    })
+}
+
+impl<'prog> Checked<'_, 'prog> {
+    /// The type of the variable or the signature of the function at `offset`
+    pub fn hover(&self, root: &Node, offset: usize) -> Option<String> {
+        let (name, function) = identifier_at(root, offset)?;
+
+        let description = match self.lookup(root, name, function, offset)? {
+            Found::Variable(ty, _) => format!("{name}: {ty}"),
+            Found::Function(args, variadic, _) => signature(name, &args, variadic),
+            Found::Record(_) => format!("record {name}"),
+        };
+        Some(format!("```hsl\n{description}\n```"))
+    }
+
+    /// Where the variable, the function or the record at `offset` is declared
+    pub fn definition(&self, root: &Node, text: &str, offset: usize) -> Option<Location> {
+        let (name, function) = identifier_at(root, offset)?;
+
+        let location = match self.lookup(root, name, function, offset)? {
+            Found::Variable(_, location)
+            | Found::Function(_, _, location)
+            | Found::Record(location) => location?,
+        };
+
+        // Some declarations don't tell their file, they are only found in the document
+        let found = match std::fs::canonicalize(self.path) {
+            Ok(document) if same_file(&location.path.to_string_lossy(), &document) => {
+                text.get(location.span.start..location.span.end) == Some(name)
+            }
+            _ => true,
+        };
+        found.then_some(location)
+    }
+
+    /// The names which can be used at `offset`, the keywords are not included
+    pub fn completions(&self, root: &Node, offset: usize) -> Vec<Completion> {
+        let visible = self.visible();
+        let mut completions = Vec::new();
+        let mut push = |label: &str, kind, detail: String| {
+            completions.push(Completion {
+                label: label.to_string(),
+                kind,
+                detail: Some(detail),
+            })
+        };
+
+        for func in self.callable() {
+            let args: Vec<_> = func.args.iter().map(|(_, ty)| *ty).collect();
+            push(
+                func.id,
+                CompletionKind::Function,
+                signature(func.id, &args, func.variadic),
+            );
+        }
+        for extrn in &self.program.extrn {
+            push(
+                extrn.id,
+                CompletionKind::Function,
+                signature(extrn.id, &extrn.args, extrn.variadic),
+            );
+        }
+        for record in self
+            .program
+            .records
+            .iter()
+            .filter(|r| visible.contains(&r.module))
+        {
+            push(
+                record.id,
+                CompletionKind::Record,
+                format!("record {}", record.id),
+            );
+        }
+        for global in self
+            .program
+            .globals
+            .iter()
+            .filter(|g| visible.contains(&g.module))
+        {
+            if let Some(var) = self.slt.get_variable(global.symbol) {
+                push(global.id, CompletionKind::Variable, var.ty.to_string());
+            }
+        }
+
+        for region in self.scopes(root, offset) {
+            for (var, span) in region.variables.values() {
+                if span.start <= offset {
+                    push(var.id, CompletionKind::Variable, var.ty.to_string());
+                }
+            }
+        }
+
+        completions.sort_by(|a, b| a.label.cmp(&b.label));
+        completions.dedup_by(|a, b| a.label == b.label);
+        completions
+    }
+
+    /// The functions of the document module and the public ones of the other modules
+    fn callable(&self) -> impl Iterator<Item = &crate::ir::Fn<'prog>> {
+        self.program
+            .func
+            .iter()
+            .filter(|f| f.module == self.module || f.public)
+    }
+
+    /// The modules whose globals and records can be used in the document, the document module
+    /// comes before the modules it imports
+    fn visible(&self) -> Vec<usize> {
+        let imports = self.program.modules[self.module].imports.iter();
+        std::iter::once(self.module)
+            .chain(imports.filter_map(|i| i.module))
+            .collect()
+    }
+
+    /// Where an item of a module is declared, the modules of the standard library are not files
+    fn location(&self, module: usize, span: Span) -> Option<Location> {
+        let module = &self.program.modules[module];
+        module.source.is_none().then(|| Location {
+            path: PathBuf::from(module.path),
+            span,
+        })
+    }
+
+    fn function(&self, name: &str) -> Option<&crate::ir::Fn<'prog>> {
+        self.callable()
+            .filter(|f| f.id == name)
+            .min_by_key(|f| f.module != self.module)
+    }
+
+    /// The regions of the function and of the blocks holding `offset`, from the innermost.
+    /// The blocks of a region are its children in source order, the body of an if comes
+    /// before its else
+    fn scopes(&self, root: &Node, offset: usize) -> Vec<&SymbolLookupTable<'prog>> {
+        let Some(enclosing) = enclosing_function(root, offset) else {
+            return Vec::new();
+        };
+        let region = name_of(enclosing)
+            .and_then(|name| self.function(name))
+            .and_then(|func| func.region)
+            .and_then(|region| self.slt.find_region(region));
+        let Some(region) = region else {
+            return Vec::new();
+        };
+
+        let mut scopes = vec![region];
+        let mut elements = &enclosing.children[..];
+        'descend: loop {
+            let mut blocks = scopes[scopes.len() - 1].children.iter();
+            for element in elements {
+                let Element::Node(node) = element else {
+                    continue;
+                };
+                if !matches!(node.kind, NodeKind::If | NodeKind::While) {
+                    continue;
+                }
+
+                let children = &node.children[..];
+                let parts = match children.iter().position(is_else) {
+                    Some(i) => vec![&children[..i], &children[i + 1..]],
+                    None => vec![children],
+                };
+                for part in parts {
+                    let Some(block) = blocks.next() else {
+                        break 'descend;
+                    };
+                    if contains(part, offset) {
+                        scopes.push(block);
+                        elements = part;
+                        continue 'descend;
+                    }
+                }
+            }
+            break;
+        }
+
+        scopes.reverse();
+        scopes
+    }
+
+    fn lookup(
+        &self,
+        root: &Node,
+        name: &str,
+        function: bool,
+        offset: usize,
+    ) -> Option<Found<'prog>> {
+        let here = |span| {
+            Some(Location {
+                path: self.path.to_path_buf(),
+                span,
+            })
+        };
+
+        if !function {
+            let enclosing = enclosing_function(root, offset);
+            let func = enclosing.and_then(name_of).and_then(|n| self.function(n));
+            if let (Some(enclosing), Some(func)) = (enclosing, func) {
+                // The innermost declaration before the identifier, blocks may shadow variables
+                let local = self.scopes(root, offset).into_iter().find_map(|region| {
+                    let (var, span) = region.variables.get(name)?;
+                    (span.start <= offset).then_some((region, var, span))
+                });
+                if let Some((region, var, span)) = local {
+                    // The parameters are declared in the function region at the function
+                    if Some(region.region) == func.region
+                        && func.args.iter().any(|(id, _)| *id == name)
+                    {
+                        let token = parameter(enclosing, name);
+                        return Some(Found::Variable(
+                            var.ty,
+                            token.and_then(|t| here(t.token.span)),
+                        ));
+                    }
+                    return Some(Found::Variable(var.ty, here(*span)));
+                }
+            }
+
+            let visible = self.visible();
+            let global = visible.iter().find_map(|m| {
+                let globals = &self.program.globals;
+                globals.iter().find(|g| g.module == *m && g.id == name)
+            });
+            if let Some(global) = global {
+                let var = self.slt.get_variable(global.symbol)?;
+                return Some(Found::Variable(
+                    var.ty,
+                    self.location(global.module, global.span),
+                ));
+            }
+
+            let record = visible.iter().find_map(|m| {
+                let records = &self.program.records;
+                records.iter().find(|r| r.module == *m && r.id == name)
+            });
+            if let Some(record) = record {
+                return Some(Found::Record(self.location(record.module, record.span)));
+            }
+        }
+
+        if let Some(func) = self.function(name) {
+            let args = func.args.iter().map(|(_, ty)| *ty).collect();
+            let location = self.location(func.module, func.span);
+            return Some(Found::Function(args, func.variadic, location));
+        }
+
+        let extrn = self.program.extrn.iter().find(|e| e.id == name)?;
+        Some(Found::Function(
+            extrn.args.clone(),
+            extrn.variadic,
+            here(extrn.span),
+        ))
+    }
+}
+
+enum Found<'prog> {
+    Variable(Type<'prog>, Option<Location>),
+    Function(Vec<Type<'prog>>, Option<usize>, Option<Location>),
+    Record(Option<Location>),
+}
+
+fn is_else(element: &Element) -> bool {
+    matches!(element, Element::Token(t) if t.token.kind == T![Else])
+}
+
+/// Whether `offset` is between the first and the last token of the elements
+fn contains(elements: &[Element], offset: usize) -> bool {
+    let tokens: Vec<_> = elements
+        .iter()
+        .flat_map(|element| match element {
+            Element::Node(node) => node.tokens(),
+            Element::Token(token) => vec![token],
+        })
+        .collect();
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => {
+            (first.token.span.start..=last.token.span.end).contains(&offset)
+        }
+        _ => false,
+    }
+}
+
+fn signature(name: &str, args: &[Type], variadic: Option<usize>) -> String {
+    let mut args: Vec<_> = args.iter().map(Type::to_string).collect();
+    if let Some(fixed) = variadic {
+        args.truncate(fixed);
+        args.push("...".to_string());
+    }
+    format!("{name}({})", args.join(", "))
+}
+
+/// Split the `line N: ` prefix of a message, the reported lines start at 1 and the ones of the
+/// diagnostics at 0
+fn split_line(message: &str) -> Option<(usize, &str)> {
+    let (line, message) = message.strip_prefix("line ")?.split_once(": ")?;
+    Some((line.parse::<usize>().ok()?.saturating_sub(1), message))
+}
+
+/// Whether `file` is the file at the canonical path, the paths of the documents which are not
+/// on disk are compared as they are
+fn same_file(file: &str, canonical: &Path) -> bool {
+    match std::fs::canonicalize(file) {
+        Ok(file) => file == canonical,
+        Err(_) => Path::new(file) == canonical,
+    }
+}
@@ -0,0 +1,321 @@
+//! JSON values of the messages of the language server
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    // The order of the members is kept
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Build an object from its members
+    pub fn object<const N: usize>(members: [(&str, Value); N]) -> Self {
+        Self::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// The member `key` of an object, `Null` if there is none
+    pub fn get(&self, key: &str) -> &Value {
+        match self {
+            Self::Object(members) => members
+                .iter()
+                .find(|(k, _)| k == key)
+                .map_or(&Value::Null, |(_, v)| v),
+            _ => &Value::Null,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Self::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Parse a JSON document, `None` is returned if it is not valid
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut parser = Parser {
+            input: input.as_bytes(),
+            position: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespaces();
+        (parser.position == input.len()).then_some(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Self::Number(n as f64)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(values: Vec<Value>) -> Self {
+        Self::Array(values)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::String(s) => write_string(f, s),
+            Self::Array(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_str("]")
+            }
+            Self::Object(members) => {
+                f.write_str("{")?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+struct Parser<'input> {
+    input: &'input [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespaces(&mut self) {
+        while self
+            .input
+            .get(self.position)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+
+    fn skip_prefix(&mut self, prefix: &str) -> bool {
+        let found = self.input[self.position..].starts_with(prefix.as_bytes());
+        if found {
+            self.position += prefix.len();
+        }
+        found
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespaces();
+        match *self.input.get(self.position)? {
+            b'n' if self.skip_prefix("null") => Some(Value::Null),
+            b't' if self.skip_prefix("true") => Some(Value::Bool(true)),
+            b'f' if self.skip_prefix("false") => Some(Value::Bool(false)),
+            b'"' => self.string().map(Value::String),
+            b'[' => {
+                self.position += 1;
+                let mut values = Vec::new();
+                self.skip_whitespaces();
+                if self.skip_prefix("]") {
+                    return Some(Value::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespaces();
+                    if self.skip_prefix("]") {
+                        return Some(Value::Array(values));
+                    }
+                    if !self.skip_prefix(",") {
+                        return None;
+                    }
+                }
+            }
+            b'{' => {
+                self.position += 1;
+                let mut members = Vec::new();
+                self.skip_whitespaces();
+                if self.skip_prefix("}") {
+                    return Some(Value::Object(members));
+                }
+                loop {
+                    self.skip_whitespaces();
+                    let key = self.string()?;
+                    self.skip_whitespaces();
+                    if !self.skip_prefix(":") {
+                        return None;
+                    }
+                    members.push((key, self.value()?));
+                    self.skip_whitespaces();
+                    if self.skip_prefix("}") {
+                        return Some(Value::Object(members));
+                    }
+                    if !self.skip_prefix(",") {
+                        return None;
+                    }
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                let start = self.position;
+                while self
+                    .input
+                    .get(self.position)
+                    .is_some_and(|b| matches!(b, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+                {
+                    self.position += 1;
+                }
+                let number = std::str::from_utf8(&self.input[start..self.position]).ok()?;
+                number.parse().ok().map(Value::Number)
+            }
+            _ => None,
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if !self.skip_prefix("\"") {
+            return None;
+        }
+
+        let mut bytes = Vec::new();
+        loop {
+            let b = *self.input.get(self.position)?;
+            self.position += 1;
+            match b {
+                b'"' => return String::from_utf8(bytes).ok(),
+                b'\\' => {
+                    let escaped = *self.input.get(self.position)?;
+                    self.position += 1;
+                    let c = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\x08',
+                        b'f' => '\x0c',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return None,
+                    };
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                _ => bytes.push(b),
+            }
+        }
+    }
+
+    /// Decode the code point of a `\u` escape, surrogate pairs are made of two escapes
+    fn unicode_escape(&mut self) -> Option<char> {
+        let high = self.hex4()?;
+        if !(0xd800..0xdc00).contains(&high) {
+            return char::from_u32(high);
+        }
+
+        if !self.skip_prefix("\\u") {
+            return None;
+        }
+        let low = self.hex4()?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return None;
+        }
+        char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.input.get(self.position..self.position + 4)?;
+        self.position += 4;
+        u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = r#" {"id": 1, "params": {"text": "Hello \"there\"\n\u00e9\ud83d\ude80", "list": [true, false, null, -2.5e1]}} "#;
+        let value = Value::parse(input).unwrap();
+
+        assert_eq!(value.get("id").as_usize(), Some(1));
+        assert_eq!(
+            value.get("params").get("text").as_str(),
+            Some("Hello \"there\"\né🚀")
+        );
+        assert_eq!(value.get("params").get("list").as_array().unwrap().len(), 4);
+        assert_eq!(value.get("missing"), &Value::Null);
+
+        assert_eq!(Value::parse(&value.to_string()), Some(value));
+    }
+
+    #[test]
+    fn invalid_documents() {
+        for input in ["", "{", "[1,]", "{\"a\" 1}", "\"\\x\"", "tru", "{} {}"] {
+            assert_eq!(Value::parse(input), None, "{input}");
+        }
+    }
+}
@@ -0,0 +1,769 @@
+//! Language server of the HSL language
+//!
+//! `hsl lsp` speaks the Language Server Protocol over the standard input and output. The
+//! documents are synchronised in full and checked on each change: the errors of the compiler
+//! are published as diagnostics. The server answers hover, go to definition, completion and
+//! document symbol requests.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use json::Value;
+
+mod analysis;
+mod json;
+
+// Errors defined by JSON-RPC and the protocol
+const PARSE_ERROR: i32 = -32700;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const INVALID_REQUEST: i32 = -32600;
+
+// Kinds defined by the protocol
+const SEVERITY_ERROR: usize = 1;
+const SEVERITY_WARNING: usize = 2;
+const SYMBOL_FUNCTION: usize = 12;
+const COMPLETION_FUNCTION: usize = 3;
+const COMPLETION_VARIABLE: usize = 6;
+const COMPLETION_KEYWORD: usize = 14;
+const COMPLETION_STRUCT: usize = 22;
+// Full content of the documents is sent on each change
+const SYNC_FULL: usize = 1;
+
+/// Serve the requests read from `input` until the client asks to exit. Returns the exit code,
+/// it is 1 when the client exits without shutting down the server as the protocol requires
+pub fn serve(mut input: impl BufRead, mut output: impl Write) -> u8 {
+    let mut server = Server {
+        documents: HashMap::new(),
+        shutdown: false,
+    };
+
+    while let Some(message) = read_message(&mut input) {
+        let Some(message) = Value::parse(&message) else {
+            let error = error_response(Value::Null, PARSE_ERROR, "invalid JSON message");
+            if write_message(&mut output, &error).is_err() {
+                break;
+            }
+            continue;
+        };
+
+        if message.get("method").as_str() == Some("exit") {
+            return if server.shutdown { 0 } else { 1 };
+        }
+
+        for reply in server.handle(&message) {
+            if write_message(&mut output, &reply).is_err() {
+                error!("unable to write to the client of the language server");
+                return 1;
+            }
+        }
+    }
+
+    1
+}
+
+struct Server {
+    // Content of the open documents by URI
+    documents: HashMap<String, String>,
+    shutdown: bool,
+}
+
+impl Server {
+    /// Handle a request or a notification, the replies are the response of a request and the
+    /// notifications sent back to the client
+    fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let id = message.get("id").clone();
+        let params = message.get("params");
+        let Some(method) = message.get("method").as_str() else {
+            return vec![error_response(id, INVALID_REQUEST, "missing method")];
+        };
+
+        let result = match method {
+            "initialize" => Ok(Value::object([
+                (
+                    "capabilities",
+                    Value::object([
+                        ("textDocumentSync", SYNC_FULL.into()),
+                        ("hoverProvider", true.into()),
+                        ("definitionProvider", true.into()),
+                        ("completionProvider", Value::object([])),
+                        ("documentSymbolProvider", true.into()),
+                    ]),
+                ),
+                (
+                    "serverInfo",
+                    Value::object([
+                        ("name", "hsl".into()),
+                        ("version", env!("CARGO_PKG_VERSION").into()),
+                    ]),
+                ),
+            ])),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/didOpen" | "textDocument/didChange" => {
+                let document = params.get("textDocument");
+                let text = match method {
+                    "textDocument/didOpen" => document.get("text").as_str(),
+                    _ => params
+                        .get("contentChanges")
+                        .as_array()
+                        .and_then(|changes| changes.last())
+                        .and_then(|change| change.get("text").as_str()),
+                };
+
+                return match (document.get("uri").as_str(), text) {
+                    (Some(uri), Some(text)) => {
+                        self.documents.insert(uri.to_string(), text.to_string());
+                        vec![self.diagnostics(uri)]
+                    }
+                    _ => Vec::new(),
+                };
+            }
+            "textDocument/didClose" => {
+                let Some(uri) = params.get("textDocument").get("uri").as_str() else {
+                    return Vec::new();
+                };
+                self.documents.remove(uri);
+                return vec![publish_diagnostics(uri, Vec::new())];
+            }
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/completion" => self.completion(params),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            // The other notifications are ignored
+            _ if id == Value::Null => return Vec::new(),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{method}`"))),
+        };
+
+        // Notifications get no response
+        if id == Value::Null {
+            return Vec::new();
+        }
+
+        vec![match result {
+            Ok(result) => {
+                Value::object([("jsonrpc", "2.0".into()), ("id", id), ("result", result)])
+            }
+            Err((code, message)) => error_response(id, code, &message),
+        }]
+    }
+
+    /// The document and the byte offset of the position of a request
+    fn position<'a>(
+        &'a self,
+        params: &'a Value,
+    ) -> Result<(&'a str, &'a str, usize), (i32, String)> {
+        let uri = params.get("textDocument").get("uri").as_str();
+        let text = uri.and_then(|uri| self.documents.get(uri));
+        let (Some(uri), Some(text)) = (uri, text) else {
+            return Err((INVALID_PARAMS, "unknown document".to_string()));
+        };
+
+        let position = params.get("position");
+        let offset = match (
+            position.get("line").as_usize(),
+            position.get("character").as_usize(),
+        ) {
+            (Some(line), Some(character)) => offset(text, line, character),
+            _ => return Err((INVALID_PARAMS, "invalid position".to_string())),
+        };
+
+        Ok((uri, text, offset))
+    }
+
+    fn diagnostics(&self, uri: &str) -> Value {
+        let Some(text) = self.documents.get(uri) else {
+            return publish_diagnostics(uri, Vec::new());
+        };
+
+        let (diagnostics, ()) = check(&document_path(uri), text, |_| ());
+        let diagnostics = diagnostics
+            .into_iter()
+            .map(|d| {
+                let severity = match d.level {
+                    crate::log::LogLevel::Warning => SEVERITY_WARNING,
+                    _ => SEVERITY_ERROR,
+                };
+                Value::object([
+                    ("range", line_range(text, d.line)),
+                    ("severity", severity.into()),
+                    ("source", "hsl".into()),
+                    ("message", d.message.into()),
+                ])
+            })
+            .collect();
+
+        publish_diagnostics(uri, diagnostics)
+    }
+
+    fn hover(&self, params: &Value) -> Result<Value, (i32, String)> {
+        let (uri, text, offset) = self.position(params)?;
+        let root = crate::cst::parse(text);
+
+        let (_, hover) = check(&document_path(uri), text, |checked| {
+            checked.and_then(|c| c.hover(&root, offset))
+        });
+
+        Ok(match hover {
+            Some(hover) => Value::object([(
+                "contents",
+                Value::object([("kind", "markdown".into()), ("value", hover.into())]),
+            )]),
+            None => Value::Null,
+        })
+    }
+
+    fn definition(&self, params: &Value) -> Result<Value, (i32, String)> {
+        let (uri, text, offset) = self.position(params)?;
+        let root = crate::cst::parse(text);
+
+        let (_, location) = check(&document_path(uri), text, |checked| {
+            checked.and_then(|c| c.definition(&root, text, offset))
+        });
+        let Some(location) = location else {
+            return Ok(Value::Null);
+        };
+
+        // The declaration may be in another document, open or not
+        let canonical = std::fs::canonicalize(&location.path).ok();
+        let open = self.documents.iter().find(|(uri, _)| {
+            uri_to_path(uri).and_then(|p| std::fs::canonicalize(p).ok()) == canonical
+        });
+        let (target_uri, target) = match open {
+            Some((uri, target)) => (uri.clone(), target.clone()),
+            None => (
+                path_to_uri(&location.path),
+                std::fs::read_to_string(&location.path).unwrap_or_default(),
+            ),
+        };
+
+        Ok(Value::object([
+            ("uri", target_uri.into()),
+            (
+                "range",
+                range(&target, location.span.start, location.span.end),
+            ),
+        ]))
+    }
+
+    fn completion(&self, params: &Value) -> Result<Value, (i32, String)> {
+        let (uri, text, offset) = self.position(params)?;
+        let root = crate::cst::parse(text);
+
+        let (_, names) = check(&document_path(uri), text, |checked| {
+            checked
+                .map(|c| c.completions(&root, offset))
+                .unwrap_or_default()
+        });
+
+        let items = names
+            .into_iter()
+            .chain(analysis::keywords())
+            .map(|completion| {
+                let kind = match completion.kind {
+                    analysis::CompletionKind::Keyword => COMPLETION_KEYWORD,
+                    analysis::CompletionKind::Function => COMPLETION_FUNCTION,
+                    analysis::CompletionKind::Variable => COMPLETION_VARIABLE,
+                    analysis::CompletionKind::Record => COMPLETION_STRUCT,
+                };
+                let mut item = vec![
+                    ("label".to_string(), completion.label.into()),
+                    ("kind".to_string(), kind.into()),
+                ];
+                if let Some(detail) = completion.detail {
+                    item.push(("detail".to_string(), detail.into()));
+                }
+                Value::Object(item)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(items.into())
+    }
+
+    fn document_symbols(&self, params: &Value) -> Result<Value, (i32, String)> {
+        let uri = params.get("textDocument").get("uri").as_str();
+        let Some(text) = uri.and_then(|uri| self.documents.get(uri)) else {
+            return Err((INVALID_PARAMS, "unknown document".to_string()));
+        };
+
+        let root = crate::cst::parse(text);
+        let symbols = analysis::symbols(&root)
+            .into_iter()
+            .map(|symbol| {
+                Value::object([
+                    ("name", symbol.name.into()),
+                    (
+                        "detail",
+                        if symbol.external {
+                            "extern"
+                        } else {
+                            "function"
+                        }
+                        .into(),
+                    ),
+                    ("kind", SYMBOL_FUNCTION.into()),
+                    ("range", range(text, symbol.span.start, symbol.span.end)),
+                    (
+                        "selectionRange",
+                        range(text, symbol.name_span.start, symbol.name_span.end),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Ok(symbols.into())
+    }
+}
+
+/// Read the content of a message, `None` is returned at the end of the input
+fn read_message(input: &mut impl BufRead) -> Option<String> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+
+    let mut content = vec![0; length?];
+    input.read_exact(&mut content).ok()?;
+    String::from_utf8(content).ok()
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let content = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    output.flush()
+}
+
+fn error_response(id: Value, code: i32, message: &str) -> Value {
+    Value::object([
+        ("jsonrpc", "2.0".into()),
+        ("id", id),
+        (
+            "error",
+            Value::object([
+                ("code", Value::Number(code.into())),
+                ("message", message.into()),
+            ]),
+        ),
+    ])
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    Value::object([
+        ("jsonrpc", "2.0".into()),
+        ("method", "textDocument/publishDiagnostics".into()),
+        (
+            "params",
+            Value::object([("uri", uri.into()), ("diagnostics", diagnostics.into())]),
+        ),
+    ])
+}
+
+/// The position of a byte offset, the characters are counted in UTF-16 code units as the
+/// protocol requires
+fn position(text: &str, offset: usize) -> Value {
+    let offset = offset.min(text.len());
+    let before = text.get(..offset).unwrap_or(text);
+    let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+    Value::object([
+        ("line", before.matches('\n').count().into()),
+        (
+            "character",
+            before[line_start..].encode_utf16().count().into(),
+        ),
+    ])
+}
+
+fn range(text: &str, start: usize, end: usize) -> Value {
+    Value::object([
+        ("start", position(text, start)),
+        ("end", position(text, end)),
+    ])
+}
+
+/// The range of the whole line
+fn line_range(text: &str, line: usize) -> Value {
+    let content = text.lines().nth(line).unwrap_or("");
+    let end = content.encode_utf16().count();
+    Value::object([
+        (
+            "start",
+            Value::object([("line", line.into()), ("character", 0.into())]),
+        ),
+        (
+            "end",
+            Value::object([("line", line.into()), ("character", end.into())]),
+        ),
+    ])
+}
+
+/// The byte offset of a position, positions past the end of a line are at its end
+fn offset(text: &str, line: usize, character: usize) -> usize {
+    let line_start: usize = text.split_inclusive('\n').take(line).map(str::len).sum();
+    let content = text[line_start..].split('\n').next().unwrap_or("");
+
+    let mut units = 0;
+    for (index, c) in content.char_indices() {
+        if units >= character {
+            return line_start + index;
+        }
+        units += c.len_utf16();
+    }
+    line_start + content.len()
+}
+
+/// Check the document with `analysis::check`, a panic of the compiler becomes a diagnostic
+/// instead of stopping the server and `f` gives its default result
+fn check<R: Default>(
+    path: &Path,
+    text: &str,
+    f: impl FnOnce(Option<&analysis::Checked<'_, '_>>) -> R,
+) -> (Vec<analysis::Diagnostic>, R) {
+    let checked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        analysis::check(path, text, f)
+    }));
+    let payload = match checked {
+        Ok(checked) => return checked,
+        Err(payload) => payload,
+    };
+
+    crate::log::set_context(None, None);
+    let reason = match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(reason), _) => reason,
+        (_, Some(reason)) => reason.as_str(),
+        _ => "unknown reason",
+    };
+    let diagnostic = analysis::Diagnostic {
+        line: 0,
+        level: crate::log::LogLevel::Error,
+        message: format!("internal error of the compiler ({reason}), please file an issue"),
+    };
+    (vec![diagnostic], R::default())
+}
+
+/// Path of the document at `uri`, a document which is not a file like an untitled one is
+/// named by its URI
+fn document_path(uri: &str) -> PathBuf {
+    uri_to_path(uri).unwrap_or_else(|| PathBuf::from(uri))
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+
+    let mut bytes = Vec::new();
+    let mut chars = path.bytes();
+    while let Some(b) = chars.next() {
+        if b == b'%' {
+            let hex = [chars.next()?, chars.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut uri = String::from("file://");
+    for b in path.to_string_lossy().bytes() {
+        if b.is_ascii_alphanumeric() || b"/-._~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{content}", content.len())
+    }
+
+    /// Serve the requests, returns the exit code and the replies of the server
+    fn exchange<const N: usize>(requests: [(&str, Option<usize>, Value); N]) -> (u8, Vec<Value>) {
+        let input: String = requests
+            .into_iter()
+            .map(|(method, id, params)| {
+                let mut request = vec![
+                    ("jsonrpc".to_string(), "2.0".into()),
+                    ("method".to_string(), method.into()),
+                    ("params".to_string(), params),
+                ];
+                request.extend(id.map(|id| ("id".to_string(), Value::from(id))));
+                message(&Value::Object(request).to_string())
+            })
+            .collect();
+
+        let mut output = Vec::new();
+        let code = serve(input.as_bytes(), &mut output);
+
+        let mut output = std::io::Cursor::new(output);
+        let replies = std::iter::from_fn(|| read_message(&mut output))
+            .map(|reply| Value::parse(&reply).unwrap())
+            .collect();
+        (code, replies)
+    }
+
+    fn diagnostics(reply: &Value) -> &[Value] {
+        reply.get("params").get("diagnostics").as_array().unwrap()
+    }
+
+    #[test]
+    fn positions() {
+        let text = "ab\n\u{e9}\u{1f680}x\n";
+        assert_eq!(offset(text, 1, 3), "ab\n\u{e9}\u{1f680}".len());
+        assert_eq!(offset(text, 0, 10), 2);
+        assert_eq!(
+            position(text, text.len() - 1),
+            Value::object([("line", 1.into()), ("character", 4.into())])
+        );
+
+        let path = Path::new("/tmp/millennium falcon.hsl");
+        assert_eq!(path_to_uri(path), "file:///tmp/millennium%20falcon.hsl");
+        assert_eq!(uri_to_path(&path_to_uri(path)).as_deref(), Some(path));
+    }
+
+    #[test]
+    fn session() {
+        let dir = std::env::temp_dir().join("hsl_lsp");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.hsl");
+        let text = "A long time ago in a galaxy far, far away...\n    I am a big deal in the resistance. count\n    Who, mesa ? 2\n    You'll find I'm full of surprises. count\nMay the force be with you.\n";
+        std::fs::write(&path, text).unwrap();
+
+        let uri = path_to_uri(&path);
+        let document = Value::object([("uri", uri.as_str().into())]);
+        let open = Value::object([
+            ("uri", uri.as_str().into()),
+            (
+                "text",
+                text.replace("surprises. count", "surprises. x").into(),
+            ),
+        ]);
+        let change = Value::object([("text", text.into())]);
+        let at = |line: usize, character: usize| {
+            Value::object([
+                ("textDocument", document.clone()),
+                (
+                    "position",
+                    Value::object([("line", line.into()), ("character", character.into())]),
+                ),
+            ])
+        };
+
+        let requests = [
+            ("initialize", Some(1), Value::object([])),
+            (
+                "textDocument/didOpen",
+                None,
+                Value::object([("textDocument", open)]),
+            ),
+            (
+                "textDocument/didChange",
+                None,
+                Value::object([
+                    ("textDocument", document.clone()),
+                    ("contentChanges", vec![change].into()),
+                ]),
+            ),
+            ("textDocument/hover", Some(2), at(3, 40)),
+            ("textDocument/definition", Some(3), at(3, 40)),
+            (
+                "textDocument/documentSymbol",
+                Some(4),
+                Value::object([("textDocument", document.clone())]),
+            ),
+            ("shutdown", Some(5), Value::Null),
+            ("exit", None, Value::Null),
+        ];
+        let (code, replies) = exchange(requests);
+        assert_eq!(code, 0);
+        assert_eq!(replies.len(), 7);
+
+        assert_eq!(diagnostics(&replies[1]).len(), 1);
+        assert_eq!(diagnostics(&replies[2]).len(), 0);
+
+        assert_eq!(
+            replies[3]
+                .get("result")
+                .get("contents")
+                .get("value")
+                .as_str(),
+            Some("```hsl\ncount: value(Credit)\n```")
+        );
+        assert_eq!(
+            replies[4].get("result").get("range").get("start"),
+            &Value::object([("line", 1.into()), ("character", 39.into())])
+        );
+        assert_eq!(
+            replies[5].get("result").as_array().unwrap()[0]
+                .get("name")
+                .as_str(),
+            Some("galaxy")
+        );
+        assert_eq!(replies[6].get("result"), &Value::Null);
+    }
+
+    #[test]
+    fn shadowing() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/04_control_flow.hsl");
+        let uri = path_to_uri(&path);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let blocks = "A long time ago in a galaxy far, far away...\n    Do, or do not. There is no try. From a certain point of view.\n        I am a big deal in the resistance. inner\n        Who, mesa ? 1\n    You have failed me for the last time.\n    I am a big deal in the resistance. outer\n    Who, mesa ? 2\nMay the force be with you.\n";
+        let open = |uri: &str, text: &str| {
+            Value::object([(
+                "textDocument",
+                Value::object([("uri", uri.into()), ("text", text.into())]),
+            )])
+        };
+        let at = |uri: &str, line: usize, character: usize| {
+            Value::object([
+                ("textDocument", Value::object([("uri", uri.into())])),
+                (
+                    "position",
+                    Value::object([("line", line.into()), ("character", character.into())]),
+                ),
+            ])
+        };
+
+        let (code, replies) = exchange([
+            ("initialize", Some(1), Value::object([])),
+            ("textDocument/didOpen", None, open(&uri, &text)),
+            (
+                "textDocument/didOpen",
+                None,
+                open("untitled:Untitled-1", blocks),
+            ),
+            // The outer fuel after the loop and the one of the loop body
+            ("textDocument/definition", Some(2), at(&uri, 24, 27)),
+            ("textDocument/definition", Some(3), at(&uri, 16, 13)),
+            (
+                "textDocument/completion",
+                Some(4),
+                at("untitled:Untitled-1", 6, 17),
+            ),
+            ("shutdown", Some(5), Value::Null),
+            ("exit", None, Value::Null),
+        ]);
+        assert_eq!(code, 0);
+
+        let start = |reply: &Value| reply.get("result").get("range").get("start").clone();
+        assert_eq!(
+            start(&replies[3]),
+            Value::object([("line", 5.into()), ("character", 39.into())])
+        );
+        assert_eq!(
+            start(&replies[4]),
+            Value::object([("line", 11.into()), ("character", 43.into())])
+        );
+
+        let labels: Vec<_> = replies[5]
+            .get("result")
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.get("label").as_str())
+            .collect();
+        assert!(labels.contains(&"outer"));
+        assert!(!labels.contains(&"inner"));
+    }
+
+    #[test]
+    fn invalid_documents() {
+        // An untitled document has no file, it is checked from its content
+        let document = Value::object([("uri", "untitled:Untitled-1".into())]);
+        let edit = |text: &str| {
+            Value::object([
+                ("textDocument", document.clone()),
+                (
+                    "contentChanges",
+                    vec![Value::object([("text", text.into())])].into(),
+                ),
+            ])
+        };
+        let open = Value::object([(
+            "textDocument",
+            Value::object([
+                ("uri", "untitled:Untitled-1".into()),
+                ("text", "Jump to\n".into()),
+            ]),
+        )]);
+        let at_start = Value::object([
+            ("textDocument", document.clone()),
+            (
+                "position",
+                Value::object([("line", 0.into()), ("character", 0.into())]),
+            ),
+        ]);
+
+        let (code, replies) = exchange([
+            ("initialize", Some(1), Value::object([])),
+            ("textDocument/didOpen", None, open),
+            (
+                "textDocument/didChange",
+                None,
+                edit("\nA long time ago in a galaxy far, far away...\n    Execute order\n"),
+            ),
+            (
+                "textDocument/didChange",
+                None,
+                edit("A long time ago in a f far, far away... Starfield 1\nMay the force be with you.\n"),
+            ),
+            (
+                "textDocument/didChange",
+                None,
+                edit("\n\n    Who, mesa ? $ <(o.o) open\n"),
+            ),
+            ("textDocument/hover", Some(2), at_start.clone()),
+            ("textDocument/completion", Some(3), at_start),
+            ("shutdown", Some(4), Value::Null),
+            ("exit", None, Value::Null),
+        ]);
+        assert_eq!(code, 0);
+        assert_eq!(replies.len(), 8);
+
+        let lines = |reply: &Value| -> Vec<usize> {
+            diagnostics(reply)
+                .iter()
+                .map(|d| d.get("range").get("start").get("line").as_usize().unwrap())
+                .collect()
+        };
+        assert_eq!(lines(&replies[1]), [0]);
+        assert!(diagnostics(&replies[1])[0]
+            .get("message")
+            .as_str()
+            .unwrap()
+            .starts_with("unexpected `Dereference`"));
+        assert_eq!(lines(&replies[2]), [3]);
+        assert_eq!(lines(&replies[3]), [0]);
+        assert!(lines(&replies[4]).contains(&2));
+
+        assert_eq!(replies[5].get("result"), &Value::Null);
+        assert!(!replies[6].get("result").as_array().unwrap().is_empty());
+        assert_eq!(replies[7].get("result"), &Value::Null);
+    }
+}
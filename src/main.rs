@@ -15,15 +15,36 @@ mod command;
 mod arena;
 mod codegen;
 mod compiler;
+mod cst;
+mod doc;
 mod flags;
+mod formatter;
 mod fs;
 mod ir;
+mod lsp;
 mod math;
 mod parser;
+mod project;
+mod resolver;
 mod semantic;
+mod stdlib;
 mod target;
 
 use codegen::Codegen;
+use flags::Command;
+
+// Exit codes of the compiler, scripts and editors tell the failing step from them
+const EXIT_FLAGS: u8 = 1;
+// Missing or unreadable source files, project or target
+const EXIT_SETUP: u8 = 2;
+// Lexer, parser and import errors
+const EXIT_PARSE: u8 = 3;
+// Name resolution and semantic errors
+const EXIT_SEMANTIC: u8 = 4;
+const EXIT_CODEGEN: u8 = 5;
+const EXIT_RUN: u8 = 6;
+// `hsl fmt --check` found files that are not formatted
+const EXIT_UNFORMATTED: u8 = 7;
 
 fn main() -> std::process::ExitCode {
     // Create the arena allocator to store all compiler variables
@@ -36,17 +57,20 @@ fn main() -> std::process::ExitCode {
     };
 
     let args: Vec<_> = std::env::args_os().collect();
+    // Loaded by the flags when no source file is given
+    let project = std::cell::OnceCell::new();
     let flags = flags::Flags::parse(
         args.first(),
         args.iter().skip(1).map(|a| a.as_os_str()),
         default_target.map(|d| d.name()),
+        &project,
     );
 
     let flags = match flags {
         flags::FlagsResult::Ok(flags) => flags,
         flags::FlagsResult::InvalidFlags(e) => {
             eprintln!("{e}");
-            return std::process::ExitCode::from(1);
+            return std::process::ExitCode::from(EXIT_FLAGS);
         }
         flags::FlagsResult::Help(help) => {
             eprintln!("{help}");
@@ -54,33 +78,65 @@ fn main() -> std::process::ExitCode {
         }
     };
 
-    let Some(mut c) = compiler::Compiler::new(&arena, flags) else {
+    if flags.quiet {
+        // SAFETY: this is the only place where LOGGER is mutated, nothing is logged concurrently
+        unsafe { log::LOGGER.set_threshold(log::LogLevel::Warning) };
+    }
+
+    let code = match flags.command {
+        Command::Build | Command::Run | Command::Check => compile(&arena, flags),
+        Command::Test => test(&arena, flags),
+        Command::Clean => clean(&arena, flags),
+        Command::New => new(flags),
+        Command::Fmt => format(flags),
+        Command::Lsp => lsp::serve(std::io::stdin().lock(), std::io::stdout().lock()),
+        Command::Doc => doc(&arena, flags),
+    };
+
+    std::process::ExitCode::from(code)
+}
+
+/// Compile the source files, `hsl check` stops after the semantic controls and `hsl run` runs
+/// the program once it is built. Returns the exit code
+fn compile(arena: &arena::Arena, flags: flags::Flags) -> u8 {
+    if let Some(project) = flags.project {
+        if flags.command != Command::Check && project.create_build_dir().is_none() {
+            return EXIT_SETUP;
+        }
+    }
+
+    let Some(mut c) = compiler::Compiler::new(arena, flags) else {
         error!("unable to create a compiler instance, it may be because you have wrong file paths");
-        return std::process::ExitCode::from(2);
+        return EXIT_SETUP;
     };
 
     info!("compiling files {}", c.flags.source_files.join(", "));
     let mut slt_builder = parser::slt::Builder::new();
     let mut slt = slt_builder.region();
-    let mut err_cpt = 0;
-
-    // We deliberatly skip this error and just log it to continue compilation and collect has much
-    // errors has possible
-    let _ = c.try_for_each_source_files(|c, file| {
-        let Ok(content) = std::fs::read_to_string(file) else {
-            error!("unable to read file `{file}` continuing compilation to collect more errors");
-            return Err(());
-        };
-        let mut parser = parser::Parser::new(&content, &arena);
-        parser.parse(c.program_mut(), &mut slt_builder, &mut slt);
-        err_cpt += parser.err_cpt;
 
-        Ok(())
-    });
+    // Errors are counted to continue compilation and collect as much errors as possible
+    match c.parse_modules() {
+        Ok(()) => (),
+        Err(compiler::LoadError::Io) => return EXIT_SETUP,
+        Err(compiler::LoadError::Parse(err_cpt)) => {
+            error!("unable to compile your program because of {err_cpt} errors");
+            return EXIT_PARSE;
+        }
+    }
+
+    // Resolve names and build the symbol lookup table
+    {
+        let err_cpt = resolver::resolve(
+            &mut c.program,
+            &mut slt_builder,
+            &mut slt,
+            c.flags.lint_shadowing,
+        );
 
-    if err_cpt != 0 {
-        error!("unable to compile your program because of {err_cpt} errors");
-        return std::process::ExitCode::from(3);
+        if err_cpt > 0 {
+            error!("unable to compile your program because of {err_cpt} name resolution errors");
+            return EXIT_SEMANTIC;
+        }
     }
 
     let nav_slt: parser::slt::NavigableSlt<'_, '_> = (&slt).into();
@@ -91,27 +147,221 @@ fn main() -> std::process::ExitCode {
 
         if err_cpt > 0 {
             error!("unable to compile your program because of {err_cpt} semantic errors");
-            return std::process::ExitCode::from(4);
+            return EXIT_SEMANTIC;
         }
     }
 
+    if c.flags.command == Command::Check {
+        info!("no errors found in {}", c.flags.source_files.join(", "));
+        return 0;
+    }
+
     let mut cmd = command::Cmd::new(c.flags.quiet);
     let mut codegen = codegen::build_codegen(&c);
 
     if codegen
-        .generate_program(&c.program, &nav_slt, &mut nav_slt.childs(), &mut cmd)
+        .generate_program(&c.program, &nav_slt, &mut cmd)
         .is_err()
     {
         error!("an error occured in codegen, please check the logs or file an issue");
-        return std::process::ExitCode::from(5);
+        return EXIT_CODEGEN;
     }
 
-    if c.flags.run && codegen.run_program(&mut cmd).is_err() {
+    if c.flags.command == Command::Run && codegen.run_program(&mut cmd).is_err() {
         error!(
             "an error occured while running the executable, please check the logs or file an issue"
         );
-        return std::process::ExitCode::from(6);
+        return EXIT_RUN;
     }
 
-    std::process::ExitCode::SUCCESS
+    0
+}
+
+/// Build and run each source file as its own program, a test passes when its program exits
+/// successfully. The programs of the tests of a project are built in its build directory
+fn test(arena: &arena::Arena, flags: flags::Flags) -> u8 {
+    let output_dir = flags.project.map(|p| p.tests_output_dir());
+    if let Some(dir) = &output_dir {
+        if std::fs::create_dir_all(dir).is_err() {
+            error!("unable to create `{}`", dir.display());
+            return EXIT_SETUP;
+        }
+    }
+
+    let mut failed = Vec::new();
+    for &file in flags.source_files.iter() {
+        let mut flags = flags.clone();
+        flags.command = Command::Run;
+        flags.source_files = vec![file];
+        if let Some(dir) = &output_dir {
+            let stem = fs::get_file_stem(file).unwrap_or("test");
+            flags.output_path = Some(arena.strdup(&format!("{}/{stem}", dir.display())));
+        }
+
+        if compile(arena, flags) != 0 {
+            failed.push(file);
+        }
+    }
+
+    let total = flags.source_files.len();
+    if failed.is_empty() {
+        info!("{total} tests passed");
+        0
+    } else {
+        error!(
+            "{} of {total} tests failed: {}",
+            failed.len(),
+            failed.join(", ")
+        );
+        EXIT_RUN
+    }
+}
+
+/// Remove the program built from the source files and the intermediate files
+fn clean(arena: &arena::Arena, flags: flags::Flags) -> u8 {
+    let Some(file) = flags.source_files.first() else {
+        error!("no source files specified, please at least specify one source file");
+        return EXIT_SETUP;
+    };
+
+    // The target only names the program, without one the programs of every target are removed
+    let targets = match flags.target_name {
+        None => target::TARGET_ORDER.to_vec(),
+        Some(name) => match target::Target::by_name(name) {
+            Some(target) => vec![target],
+            None => {
+                error!("invalid target used please specify a valid target");
+                return EXIT_SETUP;
+            }
+        },
+    };
+
+    let mut paths = Vec::new();
+    for target in targets {
+        let program_path = compiler::build_program_path(arena, flags.output_path, file, target);
+        paths.push(std::path::PathBuf::from(program_path));
+        paths.extend(fs::get_garbage_base(program_path).map(Into::into));
+    }
+    paths.extend(flags.project.map(|p| p.tests_output_dir()));
+
+    let mut code = 0;
+    for path in paths.iter().filter(|p| p.exists()) {
+        let removed = if path.is_dir() {
+            std::fs::remove_dir_all(path)
+        } else {
+            std::fs::remove_file(path)
+        };
+
+        if removed.is_ok() {
+            info!("removed `{}`", path.display());
+        } else {
+            error!("unable to remove `{}`", path.display());
+            code = EXIT_SETUP;
+        }
+    }
+
+    code
+}
+
+/// Format the source files in place, with `--check` the unformatted files are reported and
+/// left untouched. The spellings come from the manifest of the project
+fn format(flags: flags::Flags) -> u8 {
+    if flags.source_files.is_empty() {
+        error!("no source files specified, please at least specify one source file");
+        return EXIT_SETUP;
+    }
+
+    let default_style = formatter::Style::default();
+    let style = flags.project.map_or(&default_style, |p| &p.manifest.style);
+
+    let mut code = 0;
+    for &file in flags.source_files.iter() {
+        let Ok(source) = std::fs::read_to_string(file) else {
+            error!("unable to read `{file}`");
+            code = EXIT_SETUP;
+            continue;
+        };
+
+        let Some(formatted) = formatter::format(&source, style) else {
+            error!("unable to format `{file}`");
+            code = EXIT_PARSE;
+            continue;
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if flags.check_format {
+            warning!("`{file}` is not formatted");
+            if code == 0 {
+                code = EXIT_UNFORMATTED;
+            }
+        } else if std::fs::write(file, formatted).is_ok() {
+            info!("formatted `{file}`");
+        } else {
+            error!("unable to write `{file}`");
+            code = EXIT_SETUP;
+        }
+    }
+
+    code
+}
+
+/// Generate the documentation of the program next to its output, or at the path given by
+/// `--output`
+fn doc(arena: &arena::Arena, flags: flags::Flags) -> u8 {
+    if let Some(project) = flags.project {
+        if project.create_build_dir().is_none() {
+            return EXIT_SETUP;
+        }
+    }
+
+    let Some(mut c) = compiler::Compiler::new(arena, flags) else {
+        error!("unable to create a compiler instance, it may be because you have wrong file paths");
+        return EXIT_SETUP;
+    };
+
+    match c.parse_modules() {
+        Ok(()) => (),
+        Err(compiler::LoadError::Io) => return EXIT_SETUP,
+        Err(compiler::LoadError::Parse(err_cpt)) => {
+            error!("unable to document your program because of {err_cpt} errors");
+            return EXIT_PARSE;
+        }
+    }
+
+    let format = c.flags.doc_format;
+    let path = match c.flags.output_path {
+        Some(path) if !fs::get_file_extension(path).is_empty() => path.to_string(),
+        Some(path) => format!("{path}{}", format.file_ext()),
+        None => format!(
+            "{}{}",
+            fs::strip_extension(c.flags.source_files[0]),
+            format.file_ext()
+        ),
+    };
+
+    let title = c
+        .flags
+        .project
+        .map(|p| p.manifest.name.as_str())
+        .or_else(|| fs::get_file_stem(&path))
+        .unwrap_or("documentation");
+    if std::fs::write(&path, doc::generate(&c.program, title, format)).is_err() {
+        error!("unable to write `{path}`");
+        return EXIT_SETUP;
+    }
+
+    info!("documentation written to `{path}`");
+    0
+}
+
+fn new(flags: flags::Flags) -> u8 {
+    // SAFETY: this is safe because the flags of `hsl new` hold the path of the project
+    let path = flags.project_path.unwrap();
+    match project::create(std::path::Path::new(path)) {
+        Some(()) => 0,
+        None => EXIT_SETUP,
+    }
 }
@@ -1,5 +1,6 @@
-use crate::ir::Arg;
+use crate::ir::{Arg, Cmp, Logic, Part, Type};
 use crate::lexer::token::Token;
+use crate::lexer::{is_identifier, is_identifier_start};
 use crate::parser::Parser;
 
 impl<'input, 'prog, I> Parser<'input, 'prog, I>
@@ -8,25 +9,307 @@ where
 {
     pub fn arg(&mut self) -> Option<Arg<'prog>> {
         let Some(kind) = self.peek() else {
-            error!("Expected an expression and found nothing");
+            error!(
+                "line {}: expected an expression and found nothing",
+                self.line() + 1
+            );
             self.err_cpt += 1;
             return None;
         };
 
         match kind {
-            T![String] | T![Not] | T![IntLit] | T![True] | T![False] => {
+            T![String] | T![Not] | T![IntLit] | T![CharLit] | T![True] | T![False] => {
                 Some(Arg::Lit(self.literal()?))
             }
             T![ID] => {
                 // Consumes the token and retrieve the id in the parser state
                 self.consume(T![ID])?;
-                Some(Arg::Id(self.arena.strdup(self.id)))
+                let id = self.arena.strdup(self.id);
+                let span = self.span;
+
+                // `field of the record`
+                if self.check_next(T![Field]) {
+                    self.consume(T![Field])?;
+                    self.consume(T![ID])?;
+                    return Some(Arg::Field {
+                        id: self.arena.strdup(self.id),
+                        span: self.span,
+                        field: id,
+                        sym: None,
+                    });
+                }
+
+                if self.check_next(T![Index]) {
+                    self.consume(T![Index])?;
+                    return Some(Arg::Index {
+                        id,
+                        span,
+                        index: Box::new(self.arg()?),
+                        sym: None,
+                    });
+                }
+
+                Some(Arg::Id {
+                    id,
+                    span,
+                    sym: None,
+                })
+            }
+            T![AddrOf] => {
+                self.consume(T![AddrOf])?;
+                let span = self.span;
+                Some(Arg::AddrOf {
+                    span,
+                    target: Box::new(self.arg()?),
+                })
+            }
+            T![Deref] => {
+                self.consume(T![Deref])?;
+                self.consume(T![ID])?;
+                Some(Arg::Deref {
+                    id: self.arena.strdup(self.id),
+                    span: self.span,
+                    sym: None,
+                })
+            }
+            T![Null] => {
+                self.consume(T![Null])?;
+                Some(Arg::Null { span: self.span })
+            }
+            T![Alloc] => {
+                self.consume(T![Alloc])?;
+                let span = self.span;
+
+                let ty = match self.parse_type()? {
+                    Type::Val(inner) => inner,
+                    ty => {
+                        error!(
+                            "line {}: expected an element type after `{}`, found `{ty}`",
+                            span.line + 1,
+                            T![Alloc]
+                        );
+                        self.err_cpt += 1;
+                        return None;
+                    }
+                };
+
+                Some(Arg::Alloc {
+                    span,
+                    ty,
+                    len: Box::new(self.arg()?),
+                })
+            }
+            T![Read] => {
+                self.consume(T![Read])?;
+                let span = self.span;
+
+                match self.parse_type()? {
+                    Type::Val(ty) => Some(Arg::Read { span, ty }),
+                    ty => {
+                        error!(
+                            "line {}: expected a type after `{}`, found `{ty}`",
+                            span.line + 1,
+                            T![Read]
+                        );
+                        self.err_cpt += 1;
+                        None
+                    }
+                }
+            }
+            T![Eq] | T![Lt] | T![Gt] => {
+                self.consume(kind)?;
+                let span = self.span;
+                let op = match kind {
+                    T![Eq] => Cmp::Eq,
+                    T![Lt] => Cmp::Lt,
+                    _ => Cmp::Gt,
+                };
+
+                Some(Arg::Cmp {
+                    span,
+                    op,
+                    lhs: Box::new(self.arg()?),
+                    rhs: Box::new(self.arg()?),
+                })
+            }
+            T![And] | T![Or] => {
+                self.consume(kind)?;
+                let span = self.span;
+                let op = match kind {
+                    T![And] => Logic::And,
+                    _ => Logic::Or,
+                };
+
+                Some(Arg::Logic {
+                    span,
+                    op,
+                    lhs: Box::new(self.arg()?),
+                    rhs: Box::new(self.arg()?),
+                })
+            }
+            T![LNot] => {
+                self.consume(T![LNot])?;
+                let span = self.span;
+                Some(Arg::Not {
+                    span,
+                    arg: Box::new(self.arg()?),
+                })
+            }
+            T![Concat] => {
+                self.consume(T![Concat])?;
+                let span = self.span;
+                Some(Arg::Concat {
+                    span,
+                    lhs: Box::new(self.arg()?),
+                    rhs: Box::new(self.arg()?),
+                })
+            }
+            T![Substr] => {
+                self.consume(T![Substr])?;
+                let span = self.span;
+                Some(Arg::Substr {
+                    span,
+                    text: Box::new(self.arg()?),
+                    start: Box::new(self.arg()?),
+                    len: Box::new(self.arg()?),
+                })
+            }
+            T![Interp] => {
+                self.consume(T![Interp])?;
+                let span = self.span;
+
+                if !self.check_next(T![String]) {
+                    error!(
+                        "line {}: expected a string literal after `{}`",
+                        span.line + 1,
+                        T![Interp]
+                    );
+                    self.err_cpt += 1;
+                    return None;
+                }
+
+                // SAFETY: this is safe because we checked the token before
+                let tok = self.next().unwrap();
+                let text = self.text(tok);
+                let parts = self.interpolation(&text[1..(text.len() - 1)], tok.span)?;
+                Some(Arg::Interp { span, parts })
+            }
+            T![Text] => {
+                self.consume(T![Text])?;
+                let span = self.span;
+                Some(Arg::Text {
+                    span,
+                    init: Box::new(self.arg()?),
+                })
+            }
+            T![Len] => {
+                self.consume(T![Len])?;
+                self.consume(T![ID])?;
+                Some(Arg::Len {
+                    id: self.arena.strdup(self.id),
+                    span: self.span,
+                    sym: None,
+                })
+            }
+            T![OArray] => {
+                self.consume(T![OArray])?;
+                let span = self.span;
+
+                let mut items = Vec::new();
+                while !self.check_next(T![CArray]) {
+                    if self.check_next(T![EOF]) {
+                        error!(
+                            "line {}: unexpected end of file, expected to find `{}`",
+                            self.line() + 1,
+                            T![CArray]
+                        );
+                        self.err_cpt += 1;
+                        return None;
+                    }
+                    items.push(self.literal()?);
+                }
+                self.consume(T![CArray])?;
+
+                if items.is_empty() {
+                    error!(
+                        "line {}: an array literal needs at least one element",
+                        span.line + 1
+                    );
+                    self.err_cpt += 1;
+                    return None;
+                }
+
+                Some(Arg::Array { span, items })
+            }
+            T![ONew] => {
+                self.consume(T![ONew])?;
+                self.consume(T![ID])?;
+
+                let id = self.arena.strdup(self.id);
+                let span = self.span;
+
+                let mut fields = Vec::new();
+                while !self.check_next(T![CNew]) {
+                    self.consume(T![ID])?;
+                    let field = self.arena.strdup(self.id);
+                    fields.push((field, self.arg()?));
+                }
+                self.consume(T![CNew])?;
+
+                Some(Arg::Record { id, span, fields })
             }
             kind => {
-                error!("unknown start of expression: `{kind}`");
+                let line = self.line() + 1;
+                self.unexpected(
+                    kind,
+                    format_args!("line {line}: unknown start of expression: `{kind}`"),
+                )
+            }
+        }
+    }
+
+    /// Split the content of an interpolated string literal, the variables are written
+    /// between braces
+    fn interpolation(
+        &mut self,
+        mut text: &'input str,
+        span: crate::lexer::token::Span,
+    ) -> Option<Vec<Part<'prog>>> {
+        let mut parts = Vec::new();
+
+        while let Some(open) = text.find('{') {
+            let close = text[open..].find('}').map(|close| open + close);
+            let id = close.map(|close| &text[(open + 1)..close]);
+
+            let Some(id) = id.filter(|id| {
+                let mut chars = id.chars();
+                chars.next().is_some_and(is_identifier_start) && chars.all(is_identifier)
+            }) else {
+                error!(
+                    "line {}: expected a variable name between braces in interpolated string",
+                    span.line + 1
+                );
                 self.err_cpt += 1;
-                None
+                return None;
+            };
+
+            if open > 0 {
+                parts.push(Part::Str(self.arena.strdup(&text[..open])));
             }
+            parts.push(Part::Arg(Arg::Id {
+                id: self.arena.strdup(id),
+                span,
+                sym: None,
+            }));
+
+            // SAFETY: this is safe because the identifier is followed by the closing brace
+            text = &text[(close.unwrap() + 1)..];
         }
+
+        if !text.is_empty() {
+            parts.push(Part::Str(self.arena.strdup(text)));
+        }
+
+        Some(parts)
     }
 }
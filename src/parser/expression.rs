@@ -1,20 +1,17 @@
-use crate::ir::{Arg, Expr, InnerType, Lit, Type};
-use crate::lexer::token::Token;
+use crate::ir::{Arg, Expr, Lit, Op};
+use crate::lexer::token::{Token, TokenKind};
 use crate::parser::Parser;
 
-use super::slt::{Builder, SymbolLookupTable};
-
 impl<'input, 'prog, I> Parser<'input, 'prog, I>
 where
     I: Iterator<Item = Token>,
 {
-    pub fn expression(
-        &mut self,
-        _slt_builder: &mut Builder,
-        slt: &mut SymbolLookupTable<'prog>,
-    ) -> Option<Expr<'prog>> {
+    pub fn expression(&mut self) -> Option<Expr<'prog>> {
         let Some(kind) = self.peek() else {
-            error!("Expected a statement and found nothing");
+            error!(
+                "line {}: expected a statement and found nothing",
+                self.line() + 1
+            );
             self.err_cpt += 1;
             return None;
         };
@@ -23,14 +20,18 @@ where
             T![Let] => {
                 self.consume(T![Let])?;
                 let Some(ident) = self.next() else {
-                    error!("expected identifier after `let` but found nothing");
+                    error!(
+                        "line {}: expected identifier after `let` but found nothing",
+                        self.span.line + 1
+                    );
                     self.err_cpt += 1;
                     return None;
                 };
 
                 if ident.kind != T![ID] {
                     error!(
-                        "expected identifier after `let` but found {} instead",
+                        "line {}: expected identifier after `let` but found {} instead",
+                        ident.span.line + 1,
                         ident.kind
                     );
                     self.err_cpt += 1;
@@ -41,44 +42,28 @@ where
                 self.consume(T![Assign])?;
                 let value = self.arg()?;
 
-                let res = match value {
-                    Arg::Lit(Lit::Str(s)) => {
-                        slt.add_variable((id, Type::Val(InnerType::Str), s), ident.span)
-                    }
-                    Arg::Lit(Lit::Int(i)) => {
-                        slt.add_variable((id, Type::Val(InnerType::Int), i), ident.span)
-                    }
-                    Arg::Lit(Lit::Bool(b)) => {
-                        slt.add_variable((id, Type::Val(InnerType::Bool), b), ident.span)
-                    }
-                    _ => {
-                        error!("invalid expression found");
-                        self.err_cpt += 1;
-                        return None;
-                    }
-                };
-
-                if let Some((_, loc)) = res {
-                    self.err_cpt += 1;
-                    error!(
-                        "variable {id} already declared, previous declaration happened on line {}",
-                        loc.line
-                    );
-                }
-
-                Some(Expr::Let { id, value })
+                Some(Expr::Let {
+                    id,
+                    span: ident.span,
+                    value,
+                    sym: None,
+                })
             }
             T![OFnCall] => {
                 self.consume(T![OFnCall])?;
                 let Some(ident) = self.next() else {
-                    error!("expected identifier after `fn_call` but found nothing");
+                    error!(
+                        "line {}: expected identifier after `fn_call` but found nothing",
+                        self.span.line + 1
+                    );
                     self.err_cpt += 1;
                     return None;
                 };
 
                 if ident.kind != T![ID] {
                     error!(
-                        "expected identifier after `fn_call` but found {} instead",
+                        "line {}: expected identifier after `fn_call` but found {} instead",
+                        ident.span.line + 1,
                         ident.kind
                     );
                     self.err_cpt += 1;
@@ -92,108 +77,189 @@ where
                 }
 
                 self.consume(T![CFnCall])?;
-                Some(Expr::FnCall { id, args })
+                Some(Expr::FnCall {
+                    id,
+                    span: ident.span,
+                    args,
+                    func: None,
+                })
+            }
+            T![OAssign] => {
+                self.consume(T![OAssign])?;
+
+                let deref = self.check_next(T![Deref]);
+                if deref {
+                    self.consume(T![Deref])?;
+                }
+                self.consume(T![ID])?;
+
+                let mut id = self.arena.strdup(self.id);
+                let mut span = self.span;
+
+                // `field of the record`
+                let field = if self.check_next(T![Field]) {
+                    self.consume(T![Field])?;
+                    self.consume(T![ID])?;
+
+                    let field = id;
+                    id = self.arena.strdup(self.id);
+                    span = self.span;
+                    Some(field)
+                } else {
+                    None
+                };
+
+                let index = if self.check_next(T![Index]) {
+                    self.consume(T![Index])?;
+                    Some(self.arg()?)
+                } else {
+                    None
+                };
+
+                let mut ops = Vec::new();
+                while !self.check_next(T![CAssign]) {
+                    ops.push(self.operation()?);
+                }
+
+                self.consume(T![CAssign])?;
+                Some(Expr::Assign {
+                    id,
+                    span,
+                    index,
+                    field,
+                    deref,
+                    ops,
+                    sym: None,
+                })
+            }
+            T![If] => {
+                self.consume(T![If])?;
+                let span = self.span;
+
+                let cond = self.arg()?;
+                let body = self.block(&[T![Else], T![IfEnd]])?;
+
+                let else_body = if self.check_next(T![Else]) {
+                    self.consume(T![Else])?;
+                    Some(self.block(&[T![IfEnd]])?)
+                } else {
+                    None
+                };
+
+                self.consume(T![IfEnd])?;
+                Some(Expr::If {
+                    span,
+                    cond,
+                    body,
+                    else_body,
+                })
+            }
+            T![OWhile] => {
+                self.consume(T![OWhile])?;
+                let span = self.span;
+
+                let cond = self.arg()?;
+                let body = self.block(&[T![CWhile]])?;
+
+                self.consume(T![CWhile])?;
+                Some(Expr::While { span, cond, body })
+            }
+            T![Print] => {
+                self.consume(T![Print])?;
+                let span = self.span;
+                Some(Expr::Print {
+                    span,
+                    value: self.arg()?,
+                })
+            }
+            T![Assert] => {
+                self.consume(T![Assert])?;
+                let span = self.span;
+                Some(Expr::Assert {
+                    span,
+                    cond: self.arg()?,
+                    msg: self.message()?,
+                })
+            }
+            T![Panic] => {
+                self.consume(T![Panic])?;
+                let span = self.span;
+                Some(Expr::Panic {
+                    span,
+                    msg: self.message()?,
+                })
+            }
+            T![Free] => {
+                self.consume(T![Free])?;
+                self.consume(T![ID])?;
+                Some(Expr::Free {
+                    id: self.arena.strdup(self.id),
+                    span: self.span,
+                    sym: None,
+                })
             }
-            //T![OAssign] => {
-            //    self.consume(T![OAssign])?;
-            //    let Some(ident) = self.next() else {
-            //        error!("expected identifier after `assign` but found nothing");
-            //        self.err_cpt += 1;
-            //        return None;
-            //    };
-
-            //    if ident.kind != T![ID] {
-            //        error!(
-            //            "expected identifier after `assign` but found {} instead",
-            //            ident.kind
-            //        );
-            //        self.err_cpt += 1;
-            //        return None;
-            //    }
-
-            //    let id = self.arena.strdup(self.text(ident));
-
-            //    let mut ops = Vec::new();
-            //    while !self.check_next(T![CAssign]) {
-            //        ops.push(self.unary_op()?);
-            //    }
-
-            //    self.consume(T![CAssign])?;
-            //    Some(Stmt::Assign { id, ops })
-            //}
-            //T![if] => {
-            //    self.consume(T![if]);
-
-            //    let condition = Box::new(self.expression());
-            //    let mut body = Vec::new();
-
-            //    while !self.at(T![else]) && !self.at(T![if_end]) {
-            //        body.push(self.statement());
-            //    }
-
-            //    let else_stmt = if self.at(T![else]) {
-            //        self.consume(T![else]);
-            //        let mut else_body = Vec::new();
-
-            //        while !self.at(T![if_end]) {
-            //            else_body.push(self.statement());
-            //        }
-
-            //        Some(else_body)
-            //    } else {
-            //        None
-            //    };
-
-            //    self.consume(T![if_end]);
-            //    ast::Stmt::IfStmt {
-            //        condition,
-            //        body,
-            //        else_stmt,
-            //    }
-            //}
             kind => {
-                error!("unknown start of statement: `{kind}`");
+                let line = self.line() + 1;
+                self.unexpected(
+                    kind,
+                    format_args!("line {line}: unknown start of statement: `{kind}`"),
+                )
+            }
+        }
+    }
+
+    /// Parse statements until one of the `ends` token is found, the ending token is not consumed
+    fn block(&mut self, ends: &[TokenKind]) -> Option<Vec<Expr<'prog>>> {
+        let mut body = Vec::new();
+
+        while !ends.iter().any(|end| self.check_next(*end)) {
+            if self.check_next(T![EOF]) {
+                error!(
+                    "line {}: unexpected end of file, expected to find `{}`",
+                    self.line() + 1,
+                    ends[0]
+                );
                 self.err_cpt += 1;
-                None
+                return None;
             }
+
+            body.push(self.expression()?);
         }
+
+        Some(body)
     }
 
-    //fn unary_op(&mut self) -> Option<Unop<'prog>> {
-    //    let Some(kind) = self.peek() else {
-    //        error!("expected an unary operator and found nothing");
-    //        self.err_cpt += 1;
-    //        return None;
-    //    };
-
-    //    let unop = match kind {
-    //        T![Plus] => Unop {
-    //            op: crate::ir::Op::Add,
-    //            value: self.expression()?,
-    //        },
-    //        T![Minus] => Unop {
-    //            op: crate::ir::Op::Sub,
-    //            value: self.expression()?,
-    //        },
-    //        T![Div] => Unop {
-    //            op: crate::ir::Op::Div,
-    //            value: self.expression()?,
-    //        },
-    //        T![Mul] => Unop {
-    //            op: crate::ir::Op::Mul,
-    //            value: self.expression()?,
-    //        },
-    //        T![Mod] => Unop {
-    //            op: crate::ir::Op::Mod,
-    //            value: self.expression()?,
-    //        },
-    //        kind => {
-    //            error!("unknown start of unary operator: `{kind}`");
-    //            self.err_cpt += 1;
-    //            return None;
-    //        }
-    //    };
-
-    //    Some(unop)
-    //}
+    /// Parse the optional string literal following an assertion or a panic
+    fn message(&mut self) -> Option<Option<&'prog str>> {
+        if !self.check_next(T![String]) {
+            return Some(None);
+        }
+
+        match self.literal()? {
+            Lit::Str(msg) => Some(Some(msg)),
+            _ => unreachable!(),
+        }
+    }
+
+    fn operation(&mut self) -> Option<(Op, Arg<'prog>)> {
+        let Some(kind) = self.peek() else {
+            error!(
+                "line {}: expected an operator and found nothing",
+                self.line() + 1
+            );
+            self.err_cpt += 1;
+            return None;
+        };
+
+        let Ok(op) = Op::try_from(kind) else {
+            let line = self.line() + 1;
+            return self.unexpected(
+                kind,
+                format_args!("line {line}: unknown start of operation: `{kind}`"),
+            );
+        };
+
+        self.consume(kind)?;
+        Some((op, self.arg()?))
+    }
 }
@@ -9,7 +9,10 @@ where
 {
     pub fn literal(&mut self) -> Option<Lit<'prog>> {
         let Some(kind) = self.peek() else {
-            error!("expected a literal but got nothing");
+            error!(
+                "line {}: expected a literal but got nothing",
+                self.line() + 1
+            );
             self.err_cpt += 1;
             return None;
         };
@@ -26,31 +29,87 @@ where
                 self.consume(T![Not])?;
 
                 if !self.check_next(T![IntLit]) {
-                    error!("expected `IntLit` after `Not`");
+                    error!("line {}: expected `IntLit` after `Not`", self.line() + 1);
                     self.err_cpt += 1;
                     return None;
                 }
 
                 // SAFETY: this is safe because we checked the token before
                 let tok = self.next().unwrap();
-                let str = self.text(tok);
-                // SAFETY: this is safe of the lexer
-                Some(Lit::Int(-str.parse::<i64>().unwrap()))
+
+                // The absolute value of `i64::MIN` doesn't fit in a positive `i64`
+                if tok.value > i64::MIN.unsigned_abs() {
+                    error!(
+                        "line {}: integer literal `-{}` is out of range for `Credit`",
+                        tok.span.line + 1,
+                        self.text(tok)
+                    );
+                    self.err_cpt += 1;
+                    return None;
+                }
+
+                Some(Lit::Int((tok.value as i64).wrapping_neg()))
             }
             T![IntLit] => {
                 // SAFETY: this is safe because we peeked the token before
                 let tok = self.next().unwrap();
-                let str = self.text(tok);
-                // SAFETY: this is safe of the lexer
-                Some(Lit::Int(str.parse().unwrap()))
+
+                let Ok(value) = i64::try_from(tok.value) else {
+                    error!(
+                        "line {}: integer literal `{}` is out of range for `Credit`",
+                        tok.span.line + 1,
+                        self.text(tok)
+                    );
+                    self.err_cpt += 1;
+                    return None;
+                };
+
+                Some(Lit::Int(value))
+            }
+            T![CharLit] => {
+                // SAFETY: this is safe because we peeked the token before
+                let tok = self.next().unwrap();
+                // SAFETY: this is safe because the lexer only produces valid code points
+                Some(Lit::Char(char::from_u32(tok.value as u32).unwrap()))
+            }
+            T![True] => {
+                self.consume(T![True])?;
+                Some(Lit::Bool(true))
+            }
+            T![False] => {
+                self.consume(T![False])?;
+                Some(Lit::Bool(false))
             }
-            T![True] => Some(Lit::Bool(true)),
-            T![False] => Some(Lit::Bool(false)),
             kind => {
-                error!("unknown start of expression: `{kind}`");
-                self.err_cpt += 1;
-                None
+                let line = self.line() + 1;
+                self.unexpected(
+                    kind,
+                    format_args!("line {line}: unknown start of expression: `{kind}`"),
+                )
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_literal(input: &str) -> Option<i64> {
+        let arena = crate::arena::Arena::new();
+        let mut parser = Parser::new(input, &arena);
+        match parser.literal()? {
+            Lit::Int(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn int_literals() {
+        assert_eq!(parse_literal("0x1F"), Some(31));
+        assert_eq!(parse_literal("-0b1_0"), Some(-2));
+        assert_eq!(parse_literal("0o777"), Some(511));
+        assert_eq!(parse_literal("-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(parse_literal("9223372036854775808"), None);
+    }
+}
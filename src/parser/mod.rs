@@ -1,6 +1,4 @@
-use slt::{Builder, SymbolLookupTable};
-
-use crate::ir::{Extrn, Fn, InnerType, Program, Type};
+use crate::ir::{Extrn, Fn, Global, Import, InnerType, Program, Record, Type};
 use crate::lexer::token::{Token, TokenKind};
 use crate::lexer::Lexer;
 
@@ -20,21 +18,39 @@ where
     id: &'input str,
     integer: usize,
     span: crate::lexer::token::Span,
+    // Path of the parsed source file, it is recorded in the functions for runtime errors
+    pub file: &'prog str,
+    // Module of the parsed file, the symbols of its functions are prefixed with its name
+    pub module: usize,
+    pub module_name: &'prog str,
+    // Modules imported by the parsed file, they are loaded by the compiler
+    pub imports: Vec<Import<'prog>>,
     pub has_main: bool,
     pub err_cpt: usize,
+    // Documentation of the tokens preceded by doc comments, by offset of the token
+    docs: std::collections::HashMap<usize, (usize, &'prog str)>,
+    // The doc comments read since the last token, by line
+    doc_lines: Vec<(usize, &'input str)>,
 }
 
 impl<'input, 'prog> Parser<'input, 'prog, Lexer<'input>> {
     pub fn new(input: &'input str, arena: &'prog crate::arena::Arena<'prog>) -> Self {
+        // The comments are skipped by the parser, the doc comments are kept on the way
         Parser {
             arena,
             input,
-            tokens: Lexer::new(input).peekable(),
+            tokens: Lexer::with_comments(input).peekable(),
             id: "",
             integer: 0,
             span: crate::lexer::token::Span::default(),
+            file: "",
+            module: 0,
+            module_name: "",
+            imports: Vec::new(),
             has_main: false,
             err_cpt: 0,
+            docs: std::collections::HashMap::new(),
+            doc_lines: Vec::new(),
         }
     }
 }
@@ -49,9 +65,73 @@ where
     }
 
     pub(crate) fn peek(&mut self) -> Option<TokenKind> {
+        self.skip_comments();
         self.tokens.peek().map(|t| t.kind)
     }
 
+    /// Line of the next token, the parse errors are reported on it. Once the input is consumed
+    /// it is the line of the last token
+    pub(crate) fn line(&mut self) -> usize {
+        self.skip_comments();
+        self.tokens.peek().map_or(self.span.line, |t| t.span.line)
+    }
+
+    /// Skip the comments in front of the next token. The doc comments written on their own
+    /// lines right above it are its documentation, a blank line or another comment ends them
+    fn skip_comments(&mut self) {
+        while let Some(comment) = self.tokens.next_if(|t| t.kind == T![Comment]) {
+            let text = comment.text(self.input);
+            let own_line = self.input[..comment.span.start]
+                .rsplit('\n')
+                .next()
+                .is_some_and(|before| before.trim().is_empty());
+            let follows = self
+                .doc_lines
+                .last()
+                .is_none_or(|(line, _)| line + 1 == comment.span.line);
+
+            if !follows || !own_line || !text.starts_with(crate::lexer::DOC_COMMENT) {
+                self.doc_lines.clear();
+            }
+            if own_line && text.starts_with(crate::lexer::DOC_COMMENT) {
+                self.doc_lines.push((comment.span.line, text));
+            }
+        }
+
+        let Some(token) = self.tokens.peek() else {
+            return;
+        };
+        let (start, line) = (token.span.start, token.span.line);
+        if self
+            .doc_lines
+            .last()
+            .is_some_and(|(last, _)| last + 1 == line)
+        {
+            let lines: Vec<_> = self
+                .doc_lines
+                .iter()
+                .map(|(_, text)| crate::lexer::doc_line(text))
+                .collect();
+            let doc = self.arena.strdup(&lines.join("\n"));
+            self.docs.insert(start, (line, doc));
+        }
+        self.doc_lines.clear();
+    }
+
+    /// Count an error on a token of kind `kind` and report it with `message`. The invalid
+    /// tokens are not reported again, the lexer already told what is wrong with them
+    pub(crate) fn unexpected<T>(
+        &mut self,
+        kind: TokenKind,
+        message: std::fmt::Arguments<'_>,
+    ) -> Option<T> {
+        if kind != T![ParseError] {
+            error!("{message}");
+        }
+        self.err_cpt += 1;
+        None
+    }
+
     /// Check if the next token is of a given kind
     pub(crate) fn check_next(&mut self, kind: TokenKind) -> bool {
         let Some(t_kind) = self.peek() else {
@@ -62,6 +142,7 @@ where
     }
 
     pub(crate) fn next(&mut self) -> Option<Token> {
+        self.skip_comments();
         if let Some(tok) = self.tokens.next() {
             self.span = tok.span;
             Some(tok)
@@ -78,53 +159,240 @@ where
     /// or if there is no more tokens to consume.
     pub(crate) fn consume(&mut self, expected: TokenKind) -> Option<()> {
         let Some(token) = self.next() else {
-            error!("expected to consume `{expected}`, but there was no next token.");
+            error!(
+                "line {}: expected to consume `{expected}`, but there was no next token.",
+                self.span.line + 1
+            );
             self.err_cpt += 1;
             return None;
         };
 
         if token.kind != expected {
-            error!(
-                "expected to consume `{expected}`, but found `{}` instead",
-                token.kind
+            return self.unexpected(
+                token.kind,
+                format_args!(
+                    "line {}: expected to consume `{expected}`, but found `{}` instead",
+                    token.span.line + 1,
+                    token.kind
+                ),
             );
-            self.err_cpt += 1;
-            return None;
         }
 
         match token.kind {
             T![ID] => self.id = self.text(token),
-            // SAFETY: this is guarented to work thanks to the lexer
-            T![IntLit] => self.integer = self.text(token).parse().unwrap(),
+            T![IntLit] => self.integer = token.value as usize,
             _ => (),
         }
 
         Some(())
     }
 
-    pub(crate) fn parse(
-        &mut self,
-        program: &mut Program<'prog>,
-        slt_builder: &mut Builder,
-        slt: &mut SymbolLookupTable<'prog>,
-    ) -> Option<()> {
+    /// The documentation of the next token
+    fn take_doc(&mut self) -> Option<&'prog str> {
+        self.skip_comments();
+        let start = self.tokens.peek()?.span.start;
+        self.docs.remove(&start).map(|(_, doc)| doc)
+    }
+
+    pub(crate) fn parse(&mut self, program: &mut Program<'prog>) -> Option<()> {
         while !self.check_next(T![EOF]) {
             // SAFETY: this is safe since the while loop is still looping
             match self.peek().unwrap() {
-                T![OFnDecl1] => program.func.push(self.parse_function(slt_builder, slt)?),
-                T![OExtrnFn] => program.extrn.push(self.parse_extrn_function(slt)?),
-                _ => todo!("handle unexpected token"),
+                T![OFnDecl1] => {
+                    let doc = self.take_doc();
+                    let mut func = self.parse_function()?;
+                    func.doc = doc;
+                    program.func.push(func);
+                }
+                T![Pub] => {
+                    let doc = self.take_doc();
+                    self.consume(T![Pub])?;
+                    let mut func = self.parse_function()?;
+                    func.public = true;
+                    func.doc = doc;
+                    program.func.push(func);
+                }
+                T![Import] => {
+                    let import = self.parse_import()?;
+                    self.imports.push(import);
+                }
+                T![OExtrnFn] => {
+                    let doc = self.take_doc();
+                    let mut extrn = self.parse_extrn_function()?;
+                    extrn.doc = doc;
+                    program.extrn.push(extrn);
+                }
+                T![Let] => program.globals.push(self.parse_global()?),
+                T![ORecord] => program.records.push(self.parse_record()?),
+                kind => {
+                    let line = self.line() + 1;
+                    return self.unexpected(
+                        kind,
+                        format_args!(
+                            "line {line}: unexpected `{kind}`, expected a function, an extern, a record, a global or an import"
+                        ),
+                    );
+                }
             }
         }
         self.consume(T![EOF])?;
+
+        let mut unattached: Vec<_> = self.docs.values().map(|(line, _)| *line).collect();
+        unattached.sort();
+        for line in unattached {
+            warning!(
+                "line {}: doc comment not attached to a function or an extern",
+                line + 1
+            );
+        }
+
         Some(())
     }
 
-    fn parse_extrn_function(&mut self, slt: &mut SymbolLookupTable<'prog>) -> Option<Extrn<'prog>> {
+    fn parse_type(&mut self) -> Option<Type<'prog>> {
+        let Some(kind) = self.peek() else {
+            error!(
+                "line {}: expected type token in function params",
+                self.span.line + 1
+            );
+            self.err_cpt += 1;
+            return None;
+        };
+
+        let ty = match kind {
+            T![TyInt] => Type::Val(InnerType::Int),
+            T![TyString] => Type::Val(InnerType::Str),
+            T![TyBool] => Type::Val(InnerType::Bool),
+            T![TyChar] => Type::Val(InnerType::Char),
+            // Records are passed by reference
+            T![ID] => {
+                self.consume(T![ID])?;
+                return Some(Type::RecordRef(self.arena.strdup(self.id)));
+            }
+            T![TyPtr] => {
+                self.consume(T![TyPtr])?;
+                return match self.parse_type()? {
+                    Type::Val(inner) => Some(Type::Ptr(inner)),
+                    ty => {
+                        error!(
+                            "line {}: expected a pointed type after `Tracker`, found `{ty}`",
+                            self.span.line + 1
+                        );
+                        self.err_cpt += 1;
+                        None
+                    }
+                };
+            }
+            T![TySlice] => {
+                self.consume(T![TySlice])?;
+                return match self.parse_type()? {
+                    Type::Val(inner) => Some(Type::Slice(inner)),
+                    ty => {
+                        error!(
+                            "line {}: expected an element type after `Squadron`, found `{ty}`",
+                            self.span.line + 1
+                        );
+                        self.err_cpt += 1;
+                        None
+                    }
+                };
+            }
+            _ => {
+                error!("line {}: unexpected token for type", self.line() + 1);
+                self.err_cpt += 1;
+                return None;
+            }
+        };
+        self.consume(kind)?;
+
+        Some(ty)
+    }
+
+    /// Symbol of an item of the module
+    fn symbol(&self, id: &'prog str) -> &'prog str {
+        match self.module_name {
+            "" => id,
+            module => self.arena.strdup(&crate::ir::mangle(module, id)),
+        }
+    }
+
+    fn parse_global(&mut self) -> Option<Global<'prog>> {
+        self.consume(T![Let])?;
+        self.consume(T![ID])?;
+
+        let id = self.arena.strdup(self.id);
+        let span = self.span;
+
+        self.consume(T![Assign])?;
+        let value = self.arg()?;
+
+        Some(Global {
+            id,
+            span,
+            value,
+            mutable: false,
+            module: self.module,
+            symbol: self.symbol(id),
+        })
+    }
+
+    fn parse_import(&mut self) -> Option<Import<'prog>> {
+        self.consume(T![Import])?;
+        let span = self.span;
+
+        if !self.check_next(T![String]) {
+            error!(
+                "line {}: expected the path of a module after `{}`",
+                span.line + 1,
+                T![Import]
+            );
+            self.err_cpt += 1;
+            return None;
+        }
+
+        // SAFETY: this is safe because we checked the token before
+        let tok = self.next().unwrap();
+        let path = self.text(tok);
+
+        Some(Import {
+            path: self.arena.strdup(&path[1..(path.len() - 1)]),
+            span,
+            module: None,
+        })
+    }
+
+    fn parse_record(&mut self) -> Option<Record<'prog>> {
+        self.consume(T![ORecord])?;
+        self.consume(T![ID])?;
+
+        let id = self.arena.strdup(self.id);
+        let span = self.span;
+
+        let mut fields = Vec::new();
+        while !self.check_next(T![CRecord]) {
+            let ty = self.parse_type()?;
+            self.consume(T![ID])?;
+
+            fields.push((self.arena.strdup(self.id), ty, self.span));
+        }
+
+        self.consume(T![CRecord])?;
+
+        Some(Record {
+            id,
+            span,
+            fields,
+            module: self.module,
+            symbol: self.symbol(id),
+        })
+    }
+
+    fn parse_extrn_function(&mut self) -> Option<Extrn<'prog>> {
         self.consume(T![OExtrnFn])?;
 
         self.consume(T![ID])?;
         let id = self.arena.strdup(self.id);
+        let span = self.span;
 
         let variadic = if self.check_next(T![Variadic]) {
             self.consume(T![Variadic])?;
@@ -137,23 +405,7 @@ where
 
         let mut args = Vec::new();
         while !self.check_next(T![CExtrnFn]) {
-            let Some(kind) = self.peek() else {
-                error!("expected type token in function params");
-                return None;
-            };
-
-            let ty = match kind {
-                T![TyInt] => Type::Val(InnerType::Int),
-                T![TyString] => Type::Val(InnerType::Str),
-                T![TyBool] => Type::Val(InnerType::Bool),
-                _ => {
-                    error!("unexpected token for type");
-                    self.err_cpt += 1;
-                    return None;
-                }
-            };
-            self.consume(kind)?;
-            args.push(ty);
+            args.push(self.parse_type()?);
         }
 
         self.consume(T![CExtrnFn])?;
@@ -161,34 +413,34 @@ where
         if let Some(variadic) = variadic {
             if args.len() != variadic {
                 error!(
-                    "invalid amount of fixed external function arguments given for {id} please verify it"
+                    "line {}: invalid amount of fixed external function arguments given for {id} please verify it",
+                    span.line + 1
                 );
                 self.err_cpt += 1;
             }
         }
 
-        let extrn = Extrn { id, variadic, args };
-        slt.add_function(&extrn, self.span);
-
-        Some(extrn)
+        Some(Extrn {
+            id,
+            span,
+            variadic,
+            args,
+            module: self.module,
+            doc: None,
+        })
     }
 
-    fn parse_function(
-        &mut self,
-        slt_builder: &mut Builder,
-        slt: &mut SymbolLookupTable<'prog>,
-    ) -> Option<Fn<'prog>> {
-        slt_builder.new_region(slt);
-        let child_mut = slt.last_children_mut().unwrap();
-
+    fn parse_function(&mut self) -> Option<Fn<'prog>> {
         self.consume(T![OFnDecl1])?;
         self.consume(T![ID])?;
+        let span = self.span;
 
         if self.id == "galaxy" {
             self.has_main = true;
         }
 
         let id = self.arena.strdup(self.id);
+        let symbol = self.symbol(id);
 
         self.consume(T![OFnDecl2])?;
 
@@ -198,65 +450,47 @@ where
             self.consume(T![OFnParams])?;
 
             while !self.check_next(T![CFnParams]) {
-                let Some(kind) = self.peek() else {
-                    error!("expected type token in function params");
-                    self.err_cpt += 1;
-                    return None;
-                };
-
-                let ty = match kind {
-                    T![TyInt] => Type::Val(InnerType::Int),
-                    T![TyString] => Type::Val(InnerType::Str),
-                    T![TyBool] => Type::Val(InnerType::Bool),
-                    _ => {
-                        error!("unexpected token for type");
-                        self.err_cpt += 1;
-                        return None;
-                    }
-                };
-                self.consume(kind)?;
-
+                let ty = self.parse_type()?;
                 self.consume(T![ID])?;
 
                 let id = self.arena.strdup(self.id);
 
                 args.push((id, ty));
-                child_mut.add_variable((id, ty), self.span);
             }
 
             self.consume(T![CFnParams])?;
         }
 
-        // TODO: handle variadic functions
-        let variadic = if self.check_next(T![Variadic]) {
-            self.consume(T![Variadic])?;
-            if !self.check_next(T![IntLit]) {
-                todo!("handle non integer variadic");
-            }
-            let tok = self.next()?;
-            let _variadic: usize = self.text(tok).parse().ok()?;
-
-            todo!("handle the variadic functions")
-        } else {
-            None
-        };
+        // Only the externs can be variadic, there is no way to read the variadic arguments
+        if self.check_next(T![Variadic]) {
+            error!(
+                "line {}: `{id}` can't be variadic, only the externs can take variadic arguments",
+                self.line() + 1
+            );
+            self.err_cpt += 1;
+            return None;
+        }
+        let variadic = None;
 
         let mut body = Vec::new();
         while !self.check_next(T![CFnDecl]) {
-            body.push(self.expression(slt_builder, child_mut)?);
+            body.push(self.expression()?);
         }
 
         self.consume(T![CFnDecl])?;
 
-        let func = Fn {
+        Some(Fn {
             id,
+            span,
             body,
             variadic,
             args,
-        };
-
-        slt.add_function(&func, self.span);
-
-        Some(func)
+            file: self.file,
+            module: self.module,
+            public: false,
+            symbol,
+            doc: None,
+            region: None,
+        })
     }
 }
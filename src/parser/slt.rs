@@ -6,8 +6,12 @@ use std::collections::HashMap;
 pub struct SymbolLookupTable<'prog> {
     pub variables: HashMap<&'prog str, (Variable<'prog>, crate::lexer::token::Span)>,
     pub funcs: HashMap<&'prog str, (Fn<'prog>, crate::lexer::token::Span)>,
+    pub types: HashMap<&'prog str, (Record<'prog>, crate::lexer::token::Span)>,
 
     pub offset: i32,
+    // The biggest offset reached by this region and its children, the stack slots of disjoint
+    // children are reused so this is the number of slots needed by the region
+    pub max_offset: i32,
     pub region: u32,
     pub scope: u32,
 
@@ -26,8 +30,21 @@ impl<'prog> SymbolLookupTable<'prog> {
         var: T,
         span: crate::lexer::token::Span,
     ) -> Option<(Variable<'prog>, crate::lexer::token::Span)> {
-        self.offset += 1;
-        let mut var = var.into();
+        let var = var.into();
+        let slots = var.ty.slots();
+        self.add_variable_with_slots(var, slots, span)
+    }
+
+    /// Add a new variable taking `slots` stack slots to the slt, see `add_variable`
+    pub fn add_variable_with_slots(
+        &mut self,
+        mut var: Variable<'prog>,
+        slots: usize,
+        span: crate::lexer::token::Span,
+    ) -> Option<(Variable<'prog>, crate::lexer::token::Span)> {
+        // A variable is addressed by its lowest slot, arrays and records grow upward from there
+        self.offset += slots as i32;
+        self.max_offset = self.max_offset.max(self.offset);
         var.offset = self.offset;
         var.scope = self.scope;
         self.variables.insert(var.id, (var, span))
@@ -42,38 +59,113 @@ impl<'prog> SymbolLookupTable<'prog> {
         self.funcs.insert(func.id, (func, span))
     }
 
-    pub fn get_variable(&self, name: &str) -> Option<&Variable> {
+    /// Add a record under its symbol, records of different modules may share a name
+    pub fn add_record(
+        &mut self,
+        symbol: &'prog str,
+        record: Record<'prog>,
+        span: crate::lexer::token::Span,
+    ) -> Option<(Record<'prog>, crate::lexer::token::Span)> {
+        self.types.insert(symbol, (record, span))
+    }
+
+    pub fn get_record(&self, name: &str) -> Option<&Record<'prog>> {
+        self.types.get(name).map(|(record, _)| record)
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<&Variable<'prog>> {
         self.variables.get(name).map(|(var, _)| var)
     }
 
-    pub fn get_function(&self, name: &str) -> Option<&Fn> {
+    pub fn get_function(&self, name: &str) -> Option<&Fn<'prog>> {
         self.funcs.get(name).map(|(func, _)| func)
     }
+
+    /// Find the region with the given number in this slt or in one of its children
+    pub fn find_region(&self, region: u32) -> Option<&SymbolLookupTable<'prog>> {
+        if self.region == region {
+            Some(self)
+        } else {
+            self.children.iter().find_map(|c| c.find_region(region))
+        }
+    }
+
+    pub fn get_symbol_variable(&self, sym: SymbolId<'_>) -> Option<&Variable<'prog>> {
+        self.find_region(sym.region)
+            .and_then(|slt| slt.get_variable(sym.name))
+    }
+
+    pub fn get_symbol_function(&self, sym: SymbolId<'_>) -> Option<&Fn<'prog>> {
+        self.find_region(sym.region)
+            .and_then(|slt| slt.get_function(sym.name))
+    }
+}
+
+/// A resolved symbol, it points to the region of the slt in which the symbol has been declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolId<'prog> {
+    pub region: u32,
+    pub name: &'prog str,
 }
 
 #[derive(Debug)]
 pub struct Variable<'prog> {
     pub id: &'prog str,
-    pub ty: crate::ir::Type,
+    pub ty: crate::ir::Type<'prog>,
     pub value: Value<'prog>,
     pub offset: i32,
     pub scope: u32,
 }
 
+impl Variable<'_> {
+    /// Global variables are declared in the root region
+    pub fn is_global(&self) -> bool {
+        self.scope == 0
+    }
+}
+
 #[derive(Debug)]
 pub struct Fn<'prog> {
     pub id: &'prog str,
-    pub ty: crate::ir::Type,
-    pub args: Vec<crate::ir::Type>,
+    pub ty: crate::ir::Type<'prog>,
+    pub args: Vec<crate::ir::Type<'prog>>,
     pub variadic: Option<usize>,
 }
 
+/// The layout of a record type, fields are stored at their offset in bytes from the address of
+/// the record
 #[derive(Debug)]
+pub struct Record<'prog> {
+    pub id: &'prog str,
+    pub fields: Vec<Field<'prog>>,
+    pub size: usize,
+}
+
+#[derive(Debug)]
+pub struct Field<'prog> {
+    pub id: &'prog str,
+    pub ty: crate::ir::InnerType,
+    pub offset: usize,
+}
+
+impl<'prog> Record<'prog> {
+    pub fn get_field(&self, name: &str) -> Option<&Field<'prog>> {
+        self.fields.iter().find(|f| f.id == name)
+    }
+
+    /// Number of 8 bytes stack slots used by a variable of this record
+    pub fn slots(&self) -> usize {
+        self.size / 8
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 pub enum Value<'prog> {
     None,
     Str(&'prog str),
     Int(i64),
     Bool(bool),
+    Char(char),
 }
 
 pub struct Builder<'prog> {
@@ -98,16 +190,30 @@ impl<'prog> Builder<'prog> {
         new
     }
 
-    pub fn new_region(&mut self, parent: &mut SymbolLookupTable) {
+    /// Create a new region for a function, the caller is responsible for pushing it to the
+    /// parent children
+    pub fn new_region(&mut self, parent: &SymbolLookupTable) -> SymbolLookupTable<'prog> {
         let new = SymbolLookupTable {
             region: self.region_count,
             scope: parent.scope + 1,
             ..Default::default()
         };
+        self.region_count += 1;
+        new
+    }
 
-        parent.children.push(new);
-
+    /// Create a new region for a block nested in a function, the block variables are stored
+    /// in the function stack frame right after the variables of the parent
+    pub fn block_region(&mut self, parent: &SymbolLookupTable) -> SymbolLookupTable<'prog> {
+        let new = SymbolLookupTable {
+            region: self.region_count,
+            scope: parent.scope + 1,
+            offset: parent.offset,
+            max_offset: parent.offset,
+            ..Default::default()
+        };
         self.region_count += 1;
+        new
     }
 }
 
@@ -124,6 +230,26 @@ impl<'a, 'prog> NavigableSlt<'a, 'prog> {
         }
     }
 
+    /// Get the child region with the given region number
+    pub fn child(&'a self, region: u32) -> Option<NavigableSlt<'a, 'prog>> {
+        self.childs().find(|c| c.region == region)
+    }
+
+    pub fn root(&self) -> &NavigableSlt<'a, 'prog> {
+        match self.parent {
+            Some(parent) => parent.root(),
+            None => self,
+        }
+    }
+
+    pub fn resolve_variable(&self, sym: SymbolId<'_>) -> Option<&'prog Variable<'prog>> {
+        self.root().slt.get_symbol_variable(sym)
+    }
+
+    pub fn resolve_func(&self, sym: SymbolId<'_>) -> Option<&'prog Fn<'prog>> {
+        self.root().slt.get_symbol_function(sym)
+    }
+
     pub fn find_variable(&self, name: &str) -> Option<&Variable<'prog>> {
         match self.slt.get_variable(name) {
             Some(var) => Some(var),
@@ -131,6 +257,10 @@ impl<'a, 'prog> NavigableSlt<'a, 'prog> {
         }
     }
 
+    pub fn find_record(&self, name: &str) -> Option<&'prog Record<'prog>> {
+        self.root().slt.get_record(name)
+    }
+
     pub fn find_func(&self, name: &str) -> Option<&Fn<'prog>> {
         match self.slt.get_function(name) {
             Some(var) => Some(var),
@@ -175,8 +305,8 @@ impl<'a, 'prog> Iterator for ChildIterator<'a, 'prog> {
 macro_rules! impl_variable_from {
     (@inner) => {};
     (@inner $inner_ty:tt < $from_ty:ty > ; $($tt:tt)*) => {
-        impl<'prog> From<(&'prog str, crate::ir::Type, $from_ty)> for Variable<'prog> {
-            fn from(value: (&'prog str, crate::ir::Type, $from_ty)) -> Self {
+        impl<'prog> From<(&'prog str, crate::ir::Type<'prog>, $from_ty)> for Variable<'prog> {
+            fn from(value: (&'prog str, crate::ir::Type<'prog>, $from_ty)) -> Self {
                 Variable {
                     id: value.0,
                     ty: value.1,
@@ -198,10 +328,11 @@ impl_variable_from! {
     Str<&'prog str>;
     Bool<bool>;
     Int<i64>;
+    Char<char>;
 }
 
-impl<'prog> From<(&'prog str, crate::ir::Type)> for Variable<'prog> {
-    fn from(value: (&'prog str, crate::ir::Type)) -> Self {
+impl<'prog> From<(&'prog str, crate::ir::Type<'prog>)> for Variable<'prog> {
+    fn from(value: (&'prog str, crate::ir::Type<'prog>)) -> Self {
         Variable {
             id: value.0,
             ty: value.1,
@@ -214,8 +345,9 @@ impl<'prog> From<(&'prog str, crate::ir::Type)> for Variable<'prog> {
 
 impl<'prog> From<&crate::ir::Fn<'prog>> for Fn<'prog> {
     fn from(value: &crate::ir::Fn<'prog>) -> Self {
+        // Functions of different modules may share a name, they are declared by symbol
         Self {
-            id: value.id,
+            id: value.symbol,
             ty: crate::ir::Type::Void,
             args: value.args.iter().map(|a| a.1).collect(),
             variadic: value.variadic,
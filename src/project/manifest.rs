@@ -0,0 +1,263 @@
+//! Parser of the `Holocron.toml` manifest
+//!
+//! The manifest is a subset of TOML: comments, a `[project]` table, an optional `[fmt]` table
+//! and keys holding a string, an integer or an array of strings.
+
+pub struct Manifest {
+    pub name: String,
+    // Source file holding the galaxy function, relative to the project root
+    pub entry: String,
+    // Directories whose `.hsl` files are compiled along with the entry, relative to the root
+    pub sources: Vec<String>,
+    pub target: Option<String>,
+    // 0 builds with the runtime checks, 1 is a release build without them
+    pub opt_level: u8,
+    // Libraries given to the linker, e.g. `m` links with `-lm`
+    pub libs: Vec<String>,
+    // Spellings picked by `hsl fmt`, set by the `[fmt]` table
+    pub style: crate::formatter::Style,
+}
+
+enum Value {
+    String(String),
+    Integer(i64),
+    Array(Vec<String>),
+}
+
+impl Manifest {
+    /// Parse the content of a manifest, errors are logged and `None` is returned if there is
+    /// at least one of them
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut err_cpt = 0;
+        let mut table = None;
+
+        let mut name = None;
+        let mut entry = None;
+        let mut manifest = Self {
+            name: String::new(),
+            entry: String::new(),
+            sources: Vec::new(),
+            target: None,
+            opt_level: 0,
+            libs: Vec::new(),
+            style: crate::formatter::Style::default(),
+        };
+
+        for (index, line) in content.lines().enumerate() {
+            let line_nb = index + 1;
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                table = header.strip_suffix(']').map(str::trim);
+                if !matches!(table, Some("project" | "fmt")) {
+                    error!(
+                        "{} line {line_nb}: unknown table `{line}`, expected `[project]` or `[fmt]`",
+                        super::MANIFEST
+                    );
+                    err_cpt += 1;
+                }
+                continue;
+            }
+
+            if table.is_none() {
+                error!(
+                    "{} line {line_nb}: keys must be declared in the `[project]` table",
+                    super::MANIFEST
+                );
+                err_cpt += 1;
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                error!("{} line {line_nb}: expected `key = value`", super::MANIFEST);
+                err_cpt += 1;
+                continue;
+            };
+            let key = key.trim();
+            let Some(value) = parse_value(value.trim()) else {
+                error!(
+                    "{} line {line_nb}: invalid value for `{key}`, expected a string, an integer or an array of strings",
+                    super::MANIFEST
+                );
+                err_cpt += 1;
+                continue;
+            };
+
+            if table == Some("fmt") {
+                let set = match value {
+                    Value::String(s) => manifest.style.set(key, &s),
+                    _ => Err("a string".to_string()),
+                };
+                if let Err(expected) = set {
+                    error!(
+                        "{} line {line_nb}: `{key}` must be {expected}",
+                        super::MANIFEST
+                    );
+                    err_cpt += 1;
+                }
+                continue;
+            }
+
+            let expected = match (key, value) {
+                ("name", Value::String(s)) => {
+                    name = Some(s);
+                    continue;
+                }
+                ("entry", Value::String(s)) => {
+                    entry = Some(s);
+                    continue;
+                }
+                ("sources", Value::Array(a)) => {
+                    manifest.sources = a;
+                    continue;
+                }
+                ("target", Value::String(s)) => {
+                    manifest.target = Some(s);
+                    continue;
+                }
+                ("opt-level", Value::Integer(level @ 0..=1)) => {
+                    manifest.opt_level = level as u8;
+                    continue;
+                }
+                ("libs", Value::Array(a)) => {
+                    manifest.libs = a;
+                    continue;
+                }
+                ("name" | "entry" | "target", _) => "a string",
+                ("sources" | "libs", _) => "an array of strings",
+                ("opt-level", _) => "0 or 1",
+                _ => {
+                    error!("{} line {line_nb}: unknown key `{key}`", super::MANIFEST);
+                    err_cpt += 1;
+                    continue;
+                }
+            };
+
+            error!(
+                "{} line {line_nb}: `{key}` must be {expected}",
+                super::MANIFEST
+            );
+            err_cpt += 1;
+        }
+
+        for (key, missing) in [("name", name.is_none()), ("entry", entry.is_none())] {
+            if missing {
+                error!("{}: missing key `{key}`", super::MANIFEST);
+                err_cpt += 1;
+            }
+        }
+
+        if err_cpt > 0 {
+            return None;
+        }
+
+        manifest.name = name?;
+        manifest.entry = entry?;
+        Some(manifest)
+    }
+}
+
+/// Remove the comment ending the line, `#` inside strings does not start a comment
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => (),
+        }
+    }
+    line
+}
+
+fn parse_value(value: &str) -> Option<Value> {
+    if let Some(items) = value.strip_prefix('[') {
+        let items = items.strip_suffix(']')?.trim();
+        if items.is_empty() {
+            return Some(Value::Array(Vec::new()));
+        }
+
+        // A trailing comma is allowed
+        let items = items.strip_suffix(',').unwrap_or(items);
+        return items
+            .split(',')
+            .map(|item| parse_string(item.trim()))
+            .collect::<Option<_>>()
+            .map(Value::Array);
+    }
+
+    if value.starts_with('"') {
+        return parse_string(value).map(Value::String);
+    }
+
+    value.parse().ok().map(Value::Integer)
+}
+
+fn parse_string(value: &str) -> Option<String> {
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+    (!value.contains('"')).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_manifest() {
+        let manifest = Manifest::parse(
+            r#"
+            # The Millennium Falcon
+            [project]
+            name = "falcon"
+            entry = "src/main.hsl"      # holds the galaxy function
+            sources = ["src", "lib/#hyperdrive",]
+            target = "aarch64-darwin"
+            opt-level = 1
+            libs = ["m"]
+
+            [fmt]
+            init = "I am your father."
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name, "falcon");
+        assert_eq!(manifest.entry, "src/main.hsl");
+        assert_eq!(manifest.sources, ["src", "lib/#hyperdrive"]);
+        assert_eq!(manifest.target.as_deref(), Some("aarch64-darwin"));
+        assert_eq!(manifest.opt_level, 1);
+        assert_eq!(manifest.libs, ["m"]);
+        assert_eq!(manifest.style.init, "I am your father.");
+    }
+
+    #[test]
+    fn defaults() {
+        let manifest = Manifest::parse("[project]\nname = \"x\"\nentry = \"x.hsl\"\n").unwrap();
+
+        assert!(manifest.sources.is_empty());
+        assert_eq!(manifest.target, None);
+        assert_eq!(manifest.opt_level, 0);
+        assert!(manifest.libs.is_empty());
+    }
+
+    #[test]
+    fn invalid_manifests() {
+        for content in [
+            "name = \"x\"\nentry = \"x.hsl\"",
+            "[project]\nentry = \"x.hsl\"",
+            "[project]\nname = \"x\"\nentry = \"x.hsl\"\n[build]",
+            "[project]\nname = \"x\"\nentry = \"x.hsl\"\nspeed = 12",
+            "[project]\nname = \"x\"\nentry = \"x.hsl\"\nopt-level = 3",
+            "[project]\nname = 12\nentry = \"x.hsl\"",
+            "[project]\nname = \"x\"\nentry = \"x.hsl\"\nlibs = \"m\"",
+            "[project]\nname = \"x\"\nentry = \"x.hsl\"\nsources = [src]",
+            "[project]\nname = \"x\"\nentry = \"x.hsl\"\n[fmt]\nlocal = \"Who, mesa ?\"",
+            "[project]\nname = \"x\"\nentry = \"x.hsl\"\n[fmt]\nindent = 2",
+        ] {
+            assert!(Manifest::parse(content).is_none(), "{content}");
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Projects of HSL
+//!
+//! A project is a directory holding a `Holocron.toml` manifest. `hsl new` creates one and
+//! `hsl build` compiles the project of the current directory or of one of its parents: the
+//! entry file and every `.hsl` file of the source directories are compiled into
+//! `build/<name>`. Each `.hsl` file of the `tests` directory is a test program run by
+//! `hsl test`.
+
+use std::path::{Path, PathBuf};
+
+pub mod manifest;
+
+pub use manifest::Manifest;
+
+pub const MANIFEST: &str = "Holocron.toml";
+// Directory of the project receiving the program and the intermediate files
+const BUILD_DIR: &str = "build";
+const TESTS_DIR: &str = "tests";
+
+pub struct Project {
+    pub root: PathBuf,
+    pub manifest: Manifest,
+    // Source files to compile, the entry file comes first
+    pub files: Vec<String>,
+    // Test programs, each one is built and run on its own
+    pub tests: Vec<String>,
+    // Path of the program to produce, without the extension of the target
+    pub output: String,
+}
+
+impl Project {
+    /// Load the project whose manifest is in `dir` or in one of its parents
+    pub fn find(dir: &Path) -> Option<Self> {
+        let Some(root) = dir.ancestors().find(|d| d.join(MANIFEST).is_file()) else {
+            error!(
+                "unable to find `{MANIFEST}` in `{}` or in its parent directories",
+                dir.display()
+            );
+            return None;
+        };
+
+        Self::load(root)
+    }
+
+    /// Load the project whose manifest is in `root` and discover its source files
+    pub fn load(root: &Path) -> Option<Self> {
+        let path = root.join(MANIFEST);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            error!("unable to read `{}`", path.display());
+            return None;
+        };
+        let manifest = Manifest::parse(&content)?;
+
+        let entry = root.join(&manifest.entry);
+        if !entry.is_file() {
+            error!(
+                "unable to find the entry file `{}` of the project",
+                entry.display()
+            );
+            return None;
+        }
+
+        let mut files = vec![path_to_string(entry)?];
+        for dir in &manifest.sources {
+            for file in discover_sorted(&root.join(dir))? {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+
+        let tests_dir = root.join(TESTS_DIR);
+        let tests = if tests_dir.is_dir() {
+            discover_sorted(&tests_dir)?
+        } else {
+            Vec::new()
+        };
+
+        let output = path_to_string(root.join(BUILD_DIR).join(&manifest.name))?;
+
+        Some(Self {
+            root: root.to_path_buf(),
+            manifest,
+            files,
+            tests,
+            output,
+        })
+    }
+
+    /// Directory receiving the programs built from the tests
+    pub fn tests_output_dir(&self) -> PathBuf {
+        self.root.join(BUILD_DIR).join(TESTS_DIR)
+    }
+
+    /// Create the directory receiving the program
+    pub fn create_build_dir(&self) -> Option<()> {
+        let dir = self.root.join(BUILD_DIR);
+        if std::fs::create_dir_all(&dir).is_err() {
+            error!("unable to create `{}`", dir.display());
+            return None;
+        }
+
+        Some(())
+    }
+}
+
+/// Create a new project in the directory `path`, the project is named after the directory. It
+/// holds a manifest and a hello world entry file
+pub fn create(path: &Path) -> Option<()> {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        error!("invalid project path `{}`", path.display());
+        return None;
+    };
+
+    // The name is used for the program file
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        error!("invalid project name `{name}`, use letters, digits, `_` and `-`");
+        return None;
+    }
+
+    if path.exists() {
+        error!(
+            "unable to create the project, `{}` already exists",
+            path.display()
+        );
+        return None;
+    }
+
+    let files = [
+        (
+            MANIFEST,
+            format!(
+                "[project]\nname = \"{name}\"\nentry = \"src/main.hsl\"\nsources = [\"src\"]\nopt-level = 0\nlibs = []\n"
+            ),
+        ),
+        (
+            "src/main.hsl",
+            String::from(concat!(
+                "You're coming with me. \"std/io\"\n",
+                "\n",
+                "A long time ago in a galaxy far, far away...\n",
+                "    Execute order write_line\n",
+                "        \"Hello there!\"\n",
+                "    Order executed\n",
+                "May the force be with you.\n",
+            )),
+        ),
+        (".gitignore", format!("/{BUILD_DIR}\n")),
+    ];
+
+    let created = std::fs::create_dir_all(path.join("src")).is_ok()
+        && files
+            .iter()
+            .all(|(file, content)| std::fs::write(path.join(file), content).is_ok());
+    if !created {
+        error!("unable to create the project `{}`", path.display());
+        return None;
+    }
+
+    info!("created project `{name}` in `{}`", path.display());
+    Some(())
+}
+
+/// The `.hsl` files of `dir` and of its subdirectories, sorted so that the order of the modules
+/// does not depend on the file system
+fn discover_sorted(dir: &Path) -> Option<Vec<String>> {
+    let mut found = Vec::new();
+    if discover(dir, &mut found).is_err() {
+        error!("unable to read the source directory `{}`", dir.display());
+        return None;
+    }
+
+    found.sort();
+    found.into_iter().map(path_to_string).collect()
+}
+
+/// Push the `.hsl` files of `dir` and of its subdirectories to `files`
+fn discover(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            discover(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "hsl") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn path_to_string(path: PathBuf) -> Option<String> {
+    let path = path.into_os_string().into_string();
+    if let Err(path) = &path {
+        error!("the path `{}` is not valid UTF-8", path.to_string_lossy());
+    }
+    path.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_project() {
+        let dir = std::env::temp_dir().join("hsl_project");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let root = dir.join("falcon");
+        assert!(create(&root).is_some());
+        // The project exists already
+        assert!(create(&root).is_none());
+        assert!(create(&dir.join("x-wing!")).is_none());
+
+        std::fs::create_dir(root.join("src/engines")).unwrap();
+        std::fs::write(root.join("src/engines/hyperdrive.hsl"), "").unwrap();
+        std::fs::write(root.join("src/notes.txt"), "").unwrap();
+
+        // The manifest is found from a subdirectory
+        let project = Project::find(&root.join("src/engines")).unwrap();
+        assert_eq!(project.manifest.name, "falcon");
+        assert_eq!(
+            project.files,
+            [
+                root.join("src/main.hsl").to_str().unwrap(),
+                root.join("src/engines/hyperdrive.hsl").to_str().unwrap()
+            ]
+        );
+        assert_eq!(project.output, root.join("build/falcon").to_str().unwrap());
+
+        assert!(Project::find(&dir).is_none());
+    }
+}
@@ -0,0 +1,1448 @@
+//! Name resolution of the HSL language
+//!
+//! This pass runs after the parser and builds the symbol lookup table from the IR. Functions and
+//! externs are hoisted into the root region so that they can be called regardless of their
+//! declaration order. Globals are evaluated at compile time and declared in the root region as
+//! well, their initialiser can refer to other globals as long as there is no cycle. Then each
+//! function gets its own region holding its parameters and
+//! variables. Blocks (if/else and loop bodies) get a region nested in the one of their parent,
+//! their variables are invisible once the block ends and may shadow the outer ones. Every
+//! identifier and function call of the IR is given the resolved `SymbolId`.
+//!
+//! Record types are declared in the root region as well, the resolver computes their layout
+//! with each field aligned on its size.
+//!
+//! Functions are declared by symbol since modules may declare functions with the same name. A
+//! call resolves to a function of the calling module, else to a public function of a module it
+//! imports, else to an extern. Modules may declare the same extern as long as the declarations
+//! are identical. Globals and records are declared by symbol too, a module can use its own and
+//! the ones of the modules it imports, its own first.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ir::{Arg, Expr, Fn, Global, InnerType, Lit, Part, Program, Record, Type};
+use crate::lexer::token::Span;
+use crate::parser::slt::{self, Builder, SymbolId, SymbolLookupTable, Value, Variable};
+
+pub fn resolve<'prog>(
+    program: &mut Program<'prog>,
+    builder: &mut Builder<'prog>,
+    slt: &mut SymbolLookupTable<'prog>,
+    lint_shadowing: bool,
+) -> usize {
+    let modules = Modules {
+        functions: program
+            .func
+            .iter()
+            .map(|f| Callable {
+                module: f.module,
+                id: f.id,
+                public: f.public,
+                symbol: f.symbol,
+            })
+            .collect(),
+        imports: program
+            .modules
+            .iter()
+            .map(|m| m.imports.iter().filter_map(|i| i.module).collect())
+            .collect(),
+        names: program.modules.iter().map(|m| m.name).collect(),
+        paths: program.modules.iter().map(|m| m.path).collect(),
+    };
+
+    let mut err_cpt = resolve_records(&program.records, &modules, slt);
+
+    for extrn in program.extrn.iter_mut() {
+        crate::log::set_context(modules.path(extrn.module), None);
+        for ty in extrn.args.iter_mut() {
+            err_cpt += resolve_type(ty, extrn.module, extrn.span, &modules, slt);
+        }
+
+        // Every module declares the externs it calls, the declarations have to agree
+        if let Some((prev, loc)) = slt.add_function(&*extrn, extrn.span) {
+            if prev.args != extrn.args || prev.variadic != extrn.variadic {
+                error!(
+                    "line {}: extern {} declared with a different signature than on line {}",
+                    extrn.span.line + 1,
+                    extrn.id,
+                    loc.line + 1
+                );
+                err_cpt += 1;
+            }
+        }
+    }
+
+    for func in program.func.iter_mut() {
+        crate::log::set_context(Some(func.file), Some(func.span.line));
+        for (_, ty) in func.args.iter_mut() {
+            err_cpt += resolve_type(ty, func.module, func.span, &modules, slt);
+        }
+
+        if let Some((_, loc)) = slt.add_function(&*func, func.span) {
+            error!(
+                "function {} already declared, previous declaration happened on line {}",
+                func.id,
+                loc.line + 1
+            );
+            err_cpt += 1;
+        }
+    }
+
+    err_cpt += resolve_globals(&program.globals, &modules, slt);
+
+    let mut assigned = HashSet::new();
+    for func in program.func.iter_mut() {
+        crate::log::set_context(Some(func.file), Some(func.span.line));
+        let fn_slt = builder.new_region(slt);
+        func.region = Some(fn_slt.region);
+
+        let mut resolver = Resolver {
+            builder,
+            root: slt,
+            scopes: vec![fn_slt],
+            assigned_globals: &mut assigned,
+            modules: &modules,
+            module: func.module,
+            lint_shadowing,
+            err_cpt: 0,
+        };
+        resolver.resolve_fn(func);
+        err_cpt += resolver.err_cpt;
+
+        // SAFETY: this is safe because the function region is never popped by the resolver
+        let fn_slt = resolver.scopes.pop().unwrap();
+        slt.children.push(fn_slt);
+    }
+
+    crate::log::set_context(None, None);
+    for global in program.globals.iter_mut() {
+        global.mutable = assigned.contains(global.symbol);
+    }
+
+    err_cpt
+}
+
+/// Declare the records in the root region by symbol and compute their layout
+fn resolve_records<'prog>(
+    records: &[Record<'prog>],
+    modules: &Modules<'prog>,
+    slt: &mut SymbolLookupTable<'prog>,
+) -> usize {
+    let mut err_cpt = 0;
+
+    for record in records {
+        crate::log::set_context(modules.path(record.module), None);
+        let mut fields: Vec<slt::Field<'prog>> = Vec::new();
+        let mut size = 0;
+
+        for (id, ty, span) in &record.fields {
+            let Type::Val(inner) = ty else {
+                error!(
+                    "line {}: field {id} of record {} must be a Credit, a Holotext, a Signal or an Aurebesh and got `{ty}`",
+                    span.line + 1, record.id
+                );
+                err_cpt += 1;
+                continue;
+            };
+
+            if fields.iter().any(|f| f.id == *id) {
+                error!(
+                    "line {}: field {id} of record {} declared twice",
+                    span.line + 1,
+                    record.id
+                );
+                err_cpt += 1;
+                continue;
+            }
+
+            let offset = crate::math::align_bytes(size, inner.size());
+            size = offset + inner.size();
+            fields.push(slt::Field {
+                id,
+                ty: *inner,
+                offset,
+            });
+        }
+
+        if record.fields.is_empty() {
+            error!(
+                "line {}: record {} must have at least one field",
+                record.span.line + 1,
+                record.id
+            );
+            err_cpt += 1;
+        }
+
+        let record_slt = slt::Record {
+            id: record.id,
+            fields,
+            // Records are stored in 8 bytes stack slots
+            size: crate::math::align_bytes(size, 8),
+        };
+
+        if let Some((_, loc)) = slt.add_record(record.symbol, record_slt, record.span) {
+            error!(
+                "record {} already declared, previous declaration happened on line {}",
+                record.id,
+                loc.line + 1
+            );
+            err_cpt += 1;
+        }
+    }
+
+    err_cpt
+}
+
+/// Replace the name of the record used by a type with its symbol, the record has to be
+/// declared by the module or by a module it imports
+fn resolve_type<'prog>(
+    ty: &mut Type<'prog>,
+    module: usize,
+    span: Span,
+    modules: &Modules<'prog>,
+    slt: &SymbolLookupTable<'prog>,
+) -> usize {
+    let (Type::Record(id) | Type::RecordRef(id)) = ty else {
+        return 0;
+    };
+
+    match modules.find_record(module, id, slt) {
+        Some(symbol) => {
+            *id = symbol;
+            0
+        }
+        None => {
+            error!(
+                "line {}: cannot find record {id} in this scope",
+                span.line + 1
+            );
+            1
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum GlobalState<'prog> {
+    Visiting,
+    Done(Type<'prog>, Value<'prog>),
+    // The error has been reported, the globals using it are not reported again
+    Failed,
+}
+
+/// Declare the globals in the root region by symbol, a global can only be used by its module
+/// and by the modules importing it
+fn resolve_globals<'prog>(
+    globals: &[Global<'prog>],
+    modules: &Modules<'prog>,
+    slt: &mut SymbolLookupTable<'prog>,
+) -> usize {
+    let mut err_cpt = 0;
+    let mut states = HashMap::new();
+
+    for global in globals {
+        crate::log::set_context(modules.path(global.module), None);
+        if slt.get_variable(global.symbol).is_some() {
+            error!(
+                "line {}: global {} already declared",
+                global.span.line + 1,
+                global.id
+            );
+            err_cpt += 1;
+            continue;
+        }
+
+        let mut path = Vec::new();
+        let value = evaluate_global(
+            global,
+            globals,
+            modules,
+            &mut states,
+            &mut path,
+            &mut err_cpt,
+        );
+        let Some((ty, value)) = value else {
+            continue;
+        };
+
+        let mut var: Variable<'prog> = (global.symbol, ty).into();
+        var.value = value;
+        slt.add_variable(var, global.span);
+    }
+
+    crate::log::set_context(None, None);
+    err_cpt
+}
+
+/// Evaluate the value of a global at compile time, `path` holds the globals being evaluated to
+/// report cycles. The reported errors are added to `err_cpt`
+fn evaluate_global<'g, 'prog>(
+    global: &'g Global<'prog>,
+    globals: &'g [Global<'prog>],
+    modules: &Modules<'prog>,
+    states: &mut HashMap<&'prog str, GlobalState<'prog>>,
+    path: &mut Vec<&'g Global<'prog>>,
+    err_cpt: &mut usize,
+) -> Option<(Type<'prog>, Value<'prog>)> {
+    match states.get(global.symbol) {
+        Some(GlobalState::Done(ty, value)) => return Some((*ty, *value)),
+        Some(GlobalState::Failed) => return None,
+        Some(GlobalState::Visiting) => {
+            // The cycle is reported once, starting from its member declared first
+            let start = path.iter().position(|g| std::ptr::eq(*g, global));
+            let mut cycle = path[start.unwrap_or(0)..].to_vec();
+            let declared = |g: &&Global| globals.iter().position(|other| std::ptr::eq(other, *g));
+            let first = (0..cycle.len()).min_by_key(|i| declared(&cycle[*i]));
+            cycle.rotate_left(first.unwrap_or(0));
+
+            let names: Vec<_> = cycle.iter().chain(&cycle[..1]).map(|g| g.id).collect();
+            error!(
+                "line {}: cycle detected while evaluating global {}: {}",
+                cycle[0].span.line + 1,
+                cycle[0].id,
+                names.join(" -> ")
+            );
+            *err_cpt += 1;
+            return None;
+        }
+        None => (),
+    }
+
+    states.insert(global.symbol, GlobalState::Visiting);
+    path.push(global);
+
+    // The errors of the global used by this one are counted with it
+    let mut dependency = false;
+
+    let res = match &global.value {
+        Arg::Lit(Lit::Str(s)) => Some((Type::Val(InnerType::Str), Value::Str(s))),
+        Arg::Lit(Lit::Int(i)) => Some((Type::Val(InnerType::Int), Value::Int(*i))),
+        Arg::Lit(Lit::Bool(b)) => Some((Type::Val(InnerType::Bool), Value::Bool(*b))),
+        Arg::Lit(Lit::Char(c)) => Some((Type::Val(InnerType::Char), Value::Char(*c))),
+        Arg::Id { id, span, .. } => match modules.find(global.module, |m| {
+            globals.iter().find(|g| g.module == m && g.id == *id)
+        }) {
+            Some(other) => {
+                dependency = true;
+                evaluate_global(other, globals, modules, states, path, err_cpt)
+            }
+            None => {
+                error!(
+                    "line {}: cannot find global {id} in this scope",
+                    span.line + 1
+                );
+                None
+            }
+        },
+        Arg::Array { span, .. } => {
+            error!(
+                "line {}: global {} cannot be an array",
+                span.line + 1,
+                global.id
+            );
+            None
+        }
+        Arg::Record { span, .. } => {
+            error!(
+                "line {}: global {} cannot be a record",
+                span.line + 1,
+                global.id
+            );
+            None
+        }
+        Arg::Alloc { span, .. }
+        | Arg::Text { span, .. }
+        | Arg::Concat { span, .. }
+        | Arg::Substr { span, .. }
+        | Arg::Interp { span, .. } => {
+            error!(
+                "line {}: global {} cannot be allocated on the heap",
+                span.line + 1,
+                global.id
+            );
+            None
+        }
+        Arg::Read { span, .. } => {
+            error!(
+                "line {}: global {} cannot be read from the standard input",
+                span.line + 1,
+                global.id
+            );
+            None
+        }
+        Arg::Index { span, .. }
+        | Arg::Len { span, .. }
+        | Arg::Field { span, .. }
+        | Arg::AddrOf { span, .. }
+        | Arg::Deref { span, .. }
+        | Arg::Cmp { span, .. }
+        | Arg::Logic { span, .. }
+        | Arg::Not { span, .. }
+        | Arg::Null { span } => {
+            error!(
+                "line {}: global {} cannot be evaluated at compile time",
+                span.line + 1,
+                global.id
+            );
+            None
+        }
+    };
+
+    path.pop();
+    match res {
+        Some((ty, value)) => {
+            states.insert(global.symbol, GlobalState::Done(ty, value));
+        }
+        None => {
+            states.insert(global.symbol, GlobalState::Failed);
+            if !dependency {
+                *err_cpt += 1;
+            }
+        }
+    }
+
+    res
+}
+
+/// The functions of every module and the modules they can call
+struct Modules<'prog> {
+    functions: Vec<Callable<'prog>>,
+    // Indexes of the modules imported by each module
+    imports: Vec<Vec<usize>>,
+    names: Vec<&'prog str>,
+    paths: Vec<&'prog str>,
+}
+
+impl<'prog> Modules<'prog> {
+    /// The first item found by `f` in the module, else in the modules it imports in their
+    /// import order
+    fn find<T>(&self, module: usize, f: impl FnMut(usize) -> Option<T>) -> Option<T> {
+        let imports = self.imports.get(module).into_iter().flatten().copied();
+        std::iter::once(module).chain(imports).find_map(f)
+    }
+
+    fn symbol(&self, module: usize, id: &str) -> String {
+        crate::ir::mangle(self.names.get(module).copied().unwrap_or_default(), id)
+    }
+
+    fn path(&self, module: usize) -> Option<&'prog str> {
+        self.paths.get(module).copied()
+    }
+
+    /// Symbol of the record `id` seen from the module
+    fn find_record(
+        &self,
+        module: usize,
+        id: &str,
+        slt: &SymbolLookupTable<'prog>,
+    ) -> Option<&'prog str> {
+        self.find(module, |m| {
+            slt.types
+                .get_key_value(self.symbol(m, id).as_str())
+                .map(|(symbol, _)| *symbol)
+        })
+    }
+}
+
+struct Callable<'prog> {
+    module: usize,
+    id: &'prog str,
+    public: bool,
+    symbol: &'prog str,
+}
+
+struct Resolver<'r, 'prog> {
+    builder: &'r mut Builder<'prog>,
+    root: &'r SymbolLookupTable<'prog>,
+
+    // The regions of the function being resolved, the innermost block is the last one
+    scopes: Vec<SymbolLookupTable<'prog>>,
+    assigned_globals: &'r mut HashSet<&'prog str>,
+    modules: &'r Modules<'prog>,
+    // Module of the function being resolved
+    module: usize,
+    lint_shadowing: bool,
+    err_cpt: usize,
+}
+
+impl<'r, 'prog> Resolver<'r, 'prog> {
+    fn current(&mut self) -> &mut SymbolLookupTable<'prog> {
+        // SAFETY: this is safe because there is always at least the function region
+        self.scopes.last_mut().unwrap()
+    }
+
+    /// Find a variable of the function or a global of the module or of a module it imports
+    fn find_variable(&self, name: &str) -> Option<(SymbolId<'prog>, &Variable<'prog>)> {
+        fn get<'a, 'prog>(
+            slt: &'a SymbolLookupTable<'prog>,
+            name: &str,
+        ) -> Option<(SymbolId<'prog>, &'a Variable<'prog>)> {
+            slt.variables.get_key_value(name).map(|(name, (var, _))| {
+                let sym = SymbolId {
+                    region: slt.region,
+                    name,
+                };
+                (sym, var)
+            })
+        }
+
+        // The globals are declared by symbol
+        let local = self.scopes.iter().rev().find_map(|slt| get(slt, name));
+        local.or_else(|| {
+            self.modules.find(self.module, |m| {
+                get(self.root, &self.modules.symbol(m, name))
+            })
+        })
+    }
+
+    /// The variable of a resolved symbol
+    fn variable(&self, sym: SymbolId<'_>) -> Option<&Variable<'prog>> {
+        self.scopes
+            .iter()
+            .chain([self.root])
+            .find(|slt| slt.region == sym.region)
+            .and_then(|slt| slt.get_variable(sym.name))
+    }
+
+    /// Find the symbol of a function called from the current module, the errors are reported
+    fn find_function(&mut self, id: &str, span: Span) -> Option<&'prog str> {
+        let functions = &self.modules.functions;
+        if let Some(func) = functions
+            .iter()
+            .find(|f| f.module == self.module && f.id == id)
+        {
+            return Some(func.symbol);
+        }
+
+        let imported: Vec<_> = self
+            .modules
+            .imports
+            .get(self.module)
+            .into_iter()
+            .flatten()
+            .filter_map(|m| functions.iter().find(|f| f.module == *m && f.id == id))
+            .collect();
+        let name = |func: &Callable<'_>| self.modules.names.get(func.module).copied();
+
+        let err = match imported.as_slice() {
+            [func] if func.public => return Some(func.symbol),
+            [func] => format!(
+                "function {id} of module {} is private",
+                name(func).unwrap_or_default()
+            ),
+            [first, second, ..] => format!(
+                "call to function {id} is ambiguous, it is declared by modules {} and {}",
+                name(first).unwrap_or_default(),
+                name(second).unwrap_or_default()
+            ),
+            // The symbols of the functions are not names that can be called
+            [] if !functions.iter().any(|f| f.symbol == id) => {
+                match self.root.funcs.get_key_value(id) {
+                    Some((symbol, _)) => return Some(symbol),
+                    None => format!("cannot find function {id} in this scope"),
+                }
+            }
+            [] => format!("cannot find function {id} in this scope"),
+        };
+
+        error!("line {}: {err}", span.line + 1);
+        self.err_cpt += 1;
+        None
+    }
+
+    /// Type of a resolved expression, the type errors are left to the semantic pass which
+    /// reports them. Returns `None` if a symbol of the expression could not be resolved
+    fn arg_ty(&self, arg: &Arg<'prog>) -> Option<Type<'prog>> {
+        // SAFETY: the symbols are only set when the variable exists
+        let var_ty = |sym: &Option<SymbolId<'prog>>| sym.map(|s| self.variable(s).unwrap().ty);
+
+        match arg {
+            Arg::Lit(lit) => Some(Type::Val(lit.ty())),
+            Arg::Id { sym, .. } => var_ty(sym),
+            Arg::Index { sym, .. } => {
+                var_ty(sym).map(|ty| ty.elem().map(Type::Val).unwrap_or(Type::Void))
+            }
+            Arg::Field { sym, field, .. } => var_ty(sym).map(|ty| {
+                ty.record()
+                    .and_then(|r| self.root.get_record(r))
+                    .and_then(|r| r.get_field(field))
+                    .map(|f| Type::Val(f.ty))
+                    .unwrap_or(Type::Void)
+            }),
+            Arg::Len { .. } => Some(Type::Val(InnerType::Int)),
+            Arg::Array { items, .. } => Some(Type::Array(items[0].ty(), items.len())),
+            Arg::Record { id, .. } => Some(Type::Record(id)),
+            Arg::AddrOf { target, .. } => self.arg_ty(target).map(|ty| match ty {
+                Type::Val(inner) => Type::Ptr(inner),
+                _ => Type::Void,
+            }),
+            Arg::Deref { sym, .. } => var_ty(sym).map(|ty| match ty {
+                Type::Ptr(inner) => Type::Val(inner),
+                _ => Type::Void,
+            }),
+            Arg::Null { .. } => Some(Type::Null),
+            Arg::Alloc { ty, .. } => Some(Type::Heap(*ty)),
+            Arg::Text { .. } => Some(Type::Text),
+            // Lines are read into dynamic strings
+            Arg::Read {
+                ty: InnerType::Str, ..
+            } => Some(Type::Text),
+            Arg::Read { ty, .. } => Some(Type::Val(*ty)),
+            Arg::Cmp { .. } | Arg::Logic { .. } | Arg::Not { .. } => {
+                Some(Type::Val(InnerType::Bool))
+            }
+            Arg::Concat { .. } | Arg::Substr { .. } | Arg::Interp { .. } => Some(Type::Text),
+        }
+    }
+
+    /// Number of stack slots of a type, records are sized from their layout
+    fn slots(&self, ty: &Type<'_>) -> usize {
+        match ty {
+            // Unknown records have already been reported
+            Type::Record(id) => self.root.get_record(id).map_or(1, |r| r.slots()),
+            _ => ty.slots(),
+        }
+    }
+
+    /// Declare a variable in the innermost scope and set its symbol
+    fn declare(
+        &mut self,
+        var: Variable<'prog>,
+        slots: usize,
+        span: Span,
+        sym: &mut Option<SymbolId<'prog>>,
+    ) {
+        let id = var.id;
+        let region = self.current().region;
+        if self.lint_shadowing {
+            if let Some((shadowed, _)) = self.find_variable(id) {
+                if shadowed.region != region {
+                    warning!(
+                        "line {}: variable {id} shadows a previous declaration",
+                        span.line + 1
+                    );
+                }
+            }
+        }
+
+        if let Some((_, loc)) = self.current().add_variable_with_slots(var, slots, span) {
+            error!(
+                "variable {id} already declared, previous declaration happened on line {}",
+                loc.line + 1
+            );
+            self.err_cpt += 1;
+        }
+
+        *sym = Some(SymbolId { region, name: id });
+    }
+
+    fn resolve_fn(&mut self, func: &mut Fn<'prog>) {
+        for (id, ty) in func.args.iter() {
+            if self.current().add_variable((*id, *ty), func.span).is_some() {
+                error!("parameter {id} of function {} declared twice", func.id);
+                self.err_cpt += 1;
+            }
+        }
+
+        for expr in func.body.iter_mut() {
+            self.resolve_expr(expr);
+        }
+    }
+
+    fn resolve_block(&mut self, body: &mut [Expr<'prog>]) {
+        // SAFETY: this is safe because there is always at least the function region
+        let block = self.builder.block_region(self.scopes.last().unwrap());
+        self.scopes.push(block);
+
+        for expr in body.iter_mut() {
+            self.resolve_expr(expr);
+        }
+
+        // SAFETY: this is safe because we just pushed the block region
+        let block = self.scopes.pop().unwrap();
+        let parent = self.current();
+        parent.max_offset = parent.max_offset.max(block.max_offset);
+        parent.children.push(block);
+    }
+
+    fn resolve_expr(&mut self, expr: &mut Expr<'prog>) {
+        match expr {
+            Expr::Let {
+                id,
+                span,
+                value,
+                sym,
+            } => {
+                self.resolve_arg(value);
+
+                let var: Variable<'prog> = match value {
+                    Arg::Lit(Lit::Str(s)) => (*id, Type::Val(InnerType::Str), *s).into(),
+                    Arg::Lit(Lit::Int(i)) => (*id, Type::Val(InnerType::Int), *i).into(),
+                    Arg::Lit(Lit::Bool(b)) => (*id, Type::Val(InnerType::Bool), *b).into(),
+                    Arg::Lit(Lit::Char(c)) => (*id, Type::Val(InnerType::Char), *c).into(),
+                    Arg::Record { id: record, .. } if self.root.get_record(record).is_none() => {
+                        error!(
+                            "line {}: cannot find record {record} in this scope",
+                            span.line + 1
+                        );
+                        self.err_cpt += 1;
+                        return;
+                    }
+                    value => match self.arg_ty(value) {
+                        Some(ty) => (*id, ty).into(),
+                        // The error has already been reported while resolving the value
+                        None => return,
+                    },
+                };
+
+                let slots = self.slots(&var.ty);
+                self.declare(var, slots, *span, sym);
+            }
+            Expr::FnCall {
+                id,
+                span,
+                args,
+                func,
+            } => {
+                *func = self.find_function(id, *span).map(|name| SymbolId {
+                    region: self.root.region,
+                    name,
+                });
+
+                for arg in args.iter_mut() {
+                    self.resolve_arg(arg);
+                }
+            }
+            Expr::Assign {
+                id,
+                span,
+                index,
+                ops,
+                sym,
+                ..
+            } => {
+                if let Some((var, _)) = self.find_variable(id) {
+                    if var.region == self.root.region {
+                        self.assigned_globals.insert(var.name);
+                    }
+                    *sym = Some(var);
+                } else {
+                    error!(
+                        "line {}: cannot find variable {id} in this scope",
+                        span.line + 1
+                    );
+                    self.err_cpt += 1;
+                }
+
+                if let Some(index) = index {
+                    self.resolve_arg(index);
+                }
+
+                for (_, arg) in ops.iter_mut() {
+                    self.resolve_arg(arg);
+                }
+            }
+            Expr::If {
+                cond,
+                body,
+                else_body,
+                ..
+            } => {
+                self.resolve_arg(cond);
+                self.resolve_block(body);
+
+                if let Some(else_body) = else_body {
+                    self.resolve_block(else_body);
+                }
+            }
+            Expr::While { cond, body, .. } => {
+                self.resolve_arg(cond);
+                self.resolve_block(body);
+            }
+            Expr::Print { value, .. } | Expr::Assert { cond: value, .. } => self.resolve_arg(value),
+            Expr::Panic { .. } => (),
+            Expr::Free { id, span, sym } => {
+                if let Some((var, _)) = self.find_variable(id) {
+                    *sym = Some(var);
+                } else {
+                    error!(
+                        "line {}: cannot find variable {id} in this scope",
+                        span.line + 1
+                    );
+                    self.err_cpt += 1;
+                }
+            }
+        }
+    }
+
+    fn resolve_arg(&mut self, arg: &mut Arg<'prog>) {
+        match arg {
+            Arg::Lit(_) | Arg::Array { .. } | Arg::Null { .. } | Arg::Read { .. } => (),
+            Arg::Id { id, span, sym }
+            | Arg::Len { id, span, sym }
+            | Arg::Field { id, span, sym, .. }
+            | Arg::Deref { id, span, sym } => {
+                if let Some((var, _)) = self.find_variable(id) {
+                    *sym = Some(var);
+                } else {
+                    error!(
+                        "line {}: cannot find variable {id} in this scope",
+                        span.line + 1
+                    );
+                    self.err_cpt += 1;
+                }
+            }
+            Arg::Index {
+                id,
+                span,
+                index,
+                sym,
+            } => {
+                if let Some((var, _)) = self.find_variable(id) {
+                    *sym = Some(var);
+                } else {
+                    error!(
+                        "line {}: cannot find variable {id} in this scope",
+                        span.line + 1
+                    );
+                    self.err_cpt += 1;
+                }
+
+                self.resolve_arg(index);
+            }
+            Arg::Record { id, fields, .. } => {
+                // The unknown records are reported with the declaration using them
+                if let Some(symbol) = self.modules.find_record(self.module, id, self.root) {
+                    *id = symbol;
+                }
+
+                for (_, arg) in fields.iter_mut() {
+                    self.resolve_arg(arg);
+                }
+            }
+            Arg::Alloc { len: arg, .. } | Arg::Text { init: arg, .. } | Arg::Not { arg, .. } => {
+                self.resolve_arg(arg)
+            }
+            Arg::Cmp { lhs, rhs, .. }
+            | Arg::Logic { lhs, rhs, .. }
+            | Arg::Concat { lhs, rhs, .. } => {
+                self.resolve_arg(lhs);
+                self.resolve_arg(rhs);
+            }
+            Arg::Substr {
+                text, start, len, ..
+            } => {
+                self.resolve_arg(text);
+                self.resolve_arg(start);
+                self.resolve_arg(len);
+            }
+            Arg::Interp { parts, .. } => {
+                for part in parts.iter_mut() {
+                    if let Part::Arg(arg) = part {
+                        self.resolve_arg(arg);
+                    }
+                }
+            }
+            Arg::AddrOf { target, .. } => {
+                self.resolve_arg(target);
+
+                // A global may be written through its address
+                if let Arg::Id { sym: Some(s), .. } = **target {
+                    if s.region == self.root.region {
+                        self.assigned_globals.insert(s.name);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn resolve_source(input: &str) -> usize {
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        let mut parser = Parser::new(input, &arena);
+        parser.parse(&mut program);
+        assert_eq!(parser.err_cpt, 0);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        resolve(&mut program, &mut builder, &mut slt, false)
+    }
+
+    #[test]
+    fn functions_are_hoisted() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                Execute order hello
+                Order executed
+            May the force be with you.
+
+            A long time ago in a hello far, far away...
+            May the force be with you.
+        "#;
+        assert_eq!(resolve_source(input), 0);
+    }
+
+    #[test]
+    fn unknown_symbols() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                Execute order hello
+                    finn
+                Order executed
+            May the force be with you.
+        "#;
+        assert_eq!(resolve_source(input), 2);
+    }
+
+    #[test]
+    fn duplicated_variable() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. finn
+                Who, mesa ? 10
+                I am a big deal in the resistance. finn
+                Who, mesa ? 12
+            May the force be with you.
+        "#;
+        assert_eq!(resolve_source(input), 1);
+    }
+
+    #[test]
+    fn block_scopes() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. finn
+                Who, mesa ? From a certain point of view.
+
+                Do, or do not. There is no try. finn
+                    I am a big deal in the resistance. finn
+                    Who, mesa ? 10
+                    I am a big deal in the resistance. rey
+                    Who, mesa ? finn
+                You have failed me for the last time.
+
+                Execute order printf
+                    rey
+                Order executed
+            May the force be with you.
+        "#;
+        // `rey` is not visible after the block and `printf` is not declared
+        assert_eq!(resolve_source(input), 2);
+    }
+
+    #[test]
+    fn disjoint_blocks_share_stack_slots() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. finn
+                Who, mesa ? From a certain point of view.
+
+                Do, or do not. There is no try. finn
+                    I am a big deal in the resistance. rey
+                    Who, mesa ? 10
+                These aren't the droids you're looking for.
+                    I am a big deal in the resistance. poe
+                    Who, mesa ? 10
+                    I am a big deal in the resistance. bb8
+                    Who, mesa ? 10
+                You have failed me for the last time.
+            May the force be with you.
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 0);
+
+        let fn_slt = &slt.children[0];
+        assert_eq!(fn_slt.max_offset, 3);
+        assert_eq!(fn_slt.children[0].get_variable("rey").unwrap().offset, 2);
+        assert_eq!(fn_slt.children[1].get_variable("poe").unwrap().offset, 2);
+    }
+
+    #[test]
+    fn arrays_reserve_their_length_slot() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. fleet
+                Who, mesa ? Lock S-foils in attack position. 1 2 3 Stay on target.
+                I am a big deal in the resistance. first
+                Who, mesa ? fleet at sector 0
+                I am a big deal in the resistance. len
+                Who, mesa ? Size matters not. fleet
+            May the force be with you.
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 0);
+
+        let fn_slt = &slt.children[0];
+        let fleet = fn_slt.get_variable("fleet").unwrap();
+        assert_eq!(fleet.ty, Type::Array(InnerType::Int, 3));
+        assert_eq!(fleet.offset, 4);
+        assert_eq!(
+            fn_slt.get_variable("first").unwrap().ty,
+            Type::Val(InnerType::Int)
+        );
+        assert_eq!(fn_slt.get_variable("len").unwrap().offset, 6);
+        assert_eq!(fn_slt.max_offset, 6);
+    }
+
+    #[test]
+    fn record_layout() {
+        let input = r#"
+            Help me, Obi-Wan Kenobi. Ship
+                Signal hyperdrive
+                Credit speed
+                Aurebesh class
+                Signal shields
+            You're my only hope.
+
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. xwing
+                Who, mesa ? 1
+                I am a big deal in the resistance. falcon
+                Who, mesa ? Prepare my ship. Ship
+                    hyperdrive From a certain point of view.
+                    speed 1050
+                    class 'Y'
+                    shields That's impossible!
+                Punch it!
+            May the force be with you.
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 0);
+
+        let ship = slt.get_record("Ship").unwrap();
+        let offsets: Vec<_> = ship.fields.iter().map(|f| f.offset).collect();
+        assert_eq!(offsets, [0, 8, 16, 20]);
+        assert_eq!(ship.size, 24);
+
+        let fn_slt = &slt.children[0];
+        assert_eq!(fn_slt.get_variable("falcon").unwrap().offset, 4);
+        assert_eq!(fn_slt.max_offset, 4);
+    }
+
+    #[test]
+    fn unknown_records() {
+        let input = r#"
+            Help me, Obi-Wan Kenobi. Ship
+                Credit speed
+                Credit speed
+            You're my only hope.
+
+            A long time ago in a galaxy far, far away...
+                Cargo
+                    Pilot luke
+                UnloadCargo
+
+                I am a big deal in the resistance. falcon
+                Who, mesa ? Prepare my ship. Freighter
+                    speed 10
+                Punch it!
+            May the force be with you.
+        "#;
+        assert_eq!(resolve_source(input), 3);
+    }
+
+    #[test]
+    fn globals_are_hoisted() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                What a piece of junk! launched
+                    I am your father. crew
+                The garbage will do.
+            May the force be with you.
+
+            That's one hell of a pilot. crew
+            Who, mesa ? squadron
+            That's one hell of a pilot. squadron
+            Who, mesa ? 12
+            That's one hell of a pilot. launched
+            Who, mesa ? 0
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 0);
+
+        assert!(matches!(
+            slt.get_variable("crew").unwrap().value,
+            Value::Int(12)
+        ));
+        assert!(!program.globals[0].mutable);
+        assert!(program.globals[2].mutable);
+    }
+
+    #[test]
+    fn globals_cycle() {
+        let input = r#"
+            That's one hell of a pilot. fleet
+            Who, mesa ? squadron
+            That's one hell of a pilot. crew
+            Who, mesa ? squadron
+            That's one hell of a pilot. squadron
+            Who, mesa ? crew
+        "#;
+        let (err_cpt, records) = crate::log::capture(|| resolve_source(input));
+        assert_eq!(err_cpt, 1);
+
+        let messages: Vec<_> = records.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["line 4: cycle detected while evaluating global crew: crew -> squadron -> crew"]
+        );
+    }
+
+    #[test]
+    fn pointed_globals_are_mutable() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. target
+                Who, mesa ? Coordinates of base
+                I am a big deal in the resistance. fuel
+                Who, mesa ? Jump to target
+            May the force be with you.
+
+            That's one hell of a pilot. base
+            Who, mesa ? 12
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 0);
+
+        let fn_slt = &slt.children[0];
+        assert_eq!(
+            fn_slt.get_variable("target").unwrap().ty,
+            Type::Ptr(InnerType::Int)
+        );
+        assert_eq!(
+            fn_slt.get_variable("fuel").unwrap().ty,
+            Type::Val(InnerType::Int)
+        );
+        assert!(program.globals[0].mutable);
+    }
+
+    #[test]
+    fn heap_values() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. fleet
+                Who, mesa ? Send in the clones. Credit 3
+                I am a big deal in the resistance. log
+                Who, mesa ? Begin recording. "Red five"
+                Let go, Luke. fleet
+                Let go, Luke. wedge
+            May the force be with you.
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        // `wedge` is not declared
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 1);
+
+        // Heap values only hold a pointer in the stack frame
+        let fn_slt = &slt.children[0];
+        let fleet = fn_slt.get_variable("fleet").unwrap();
+        assert_eq!(fleet.ty, Type::Heap(InnerType::Int));
+        assert_eq!(fleet.offset, 1);
+        assert_eq!(fn_slt.get_variable("log").unwrap().ty, Type::Text);
+        assert_eq!(fn_slt.max_offset, 2);
+    }
+
+    #[test]
+    fn interpolated_strings() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. pilot
+                Who, mesa ? "Luke"
+                I am a big deal in the resistance. report
+                Who, mesa ? Incoming transmission: "{pilot} and {droid} are {pilot}'s crew"
+            May the force be with you.
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        // `droid` is not declared
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 1);
+
+        let Expr::Let {
+            value: Arg::Interp { parts, .. },
+            ..
+        } = &program.func[0].body[1]
+        else {
+            panic!("expected an interpolated string");
+        };
+        let pieces: Vec<_> = parts
+            .iter()
+            .map(|part| match part {
+                Part::Str(s) => *s,
+                Part::Arg(Arg::Id { id, .. }) => id,
+                Part::Arg(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(
+            pieces,
+            ["pilot", " and ", "droid", " are ", "pilot", "'s crew"]
+        );
+        let fn_slt = &slt.children[0];
+        assert_eq!(fn_slt.get_variable("report").unwrap().ty, Type::Text);
+    }
+
+    /// Parse the modules main, fleet and base, main imports fleet
+    fn parse_modules<'prog>(
+        sources: [&'prog str; 3],
+        arena: &'prog crate::arena::Arena<'prog>,
+    ) -> Program<'prog> {
+        let mut program = Program::new();
+
+        for (module, (input, name)) in sources.iter().zip(["main", "fleet", "base"]).enumerate() {
+            let mut parser = Parser::new(input, arena);
+            parser.module = module;
+            parser.module_name = name;
+            parser.parse(&mut program);
+            assert_eq!(parser.err_cpt, 0);
+
+            program.modules.push(crate::ir::Module {
+                path: name,
+                name,
+                imports: Vec::new(),
+                source: None,
+            });
+        }
+        program.modules[0].imports.push(crate::ir::Import {
+            path: "fleet",
+            span: Span::default(),
+            module: Some(1),
+        });
+
+        program
+    }
+
+    #[test]
+    fn module_visibility() {
+        let sources = [
+            r#"
+                A long time ago in a galaxy far, far away...
+                    Execute order launch
+                    Order executed
+                    Execute order countdown
+                    Order executed
+                May the force be with you.
+            "#,
+            r#"
+                Hello there!
+                A long time ago in a launch far, far away...
+                May the force be with you.
+                A long time ago in a countdown far, far away...
+                May the force be with you.
+            "#,
+            r#"
+                Hello there!
+                A long time ago in a launch far, far away...
+                May the force be with you.
+            "#,
+        ];
+        let arena = crate::arena::Arena::new();
+        let mut program = parse_modules(sources, &arena);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        // `countdown` is private to the fleet
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 1);
+
+        let Expr::FnCall { func, .. } = &program.func[0].body[0] else {
+            panic!("expected a function call");
+        };
+        assert_eq!(func.unwrap().name, "fleet.launch");
+        assert!(slt.get_function("base.launch").is_some());
+
+        // Both imported modules declare a public `launch`
+        program.modules[0].imports.push(crate::ir::Import {
+            path: "base",
+            span: Span::default(),
+            module: Some(2),
+        });
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 2);
+    }
+
+    #[test]
+    fn module_scoping() {
+        let sources = [
+            r#"
+                I am a big deal in the resistance. speed
+                Who, mesa ? 1
+
+                A long time ago in a galaxy far, far away...
+                    I am a big deal in the resistance. own
+                    Who, mesa ? speed
+                    I am a big deal in the resistance. imported
+                    Who, mesa ? fuel
+                    I am a big deal in the resistance. hidden
+                    Who, mesa ? hull
+                    I am a big deal in the resistance. falcon
+                    Who, mesa ? Prepare my ship. Ship
+                        speed 1050
+                    Punch it!
+                May the force be with you.
+            "#,
+            r#"
+                I am a big deal in the resistance. speed
+                Who, mesa ? 2
+                I am a big deal in the resistance. fuel
+                Who, mesa ? speed
+
+                Help me, Obi-Wan Kenobi. Ship
+                    Credit speed
+                You're my only hope.
+            "#,
+            r#"
+                I am a big deal in the resistance. speed
+                Who, mesa ? 3
+                I am a big deal in the resistance. hull
+                Who, mesa ? 4
+
+                Help me, Obi-Wan Kenobi. Ship
+                    Signal shields
+                You're my only hope.
+            "#,
+        ];
+        let arena = crate::arena::Arena::new();
+        let mut program = parse_modules(sources, &arena);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        // `hull` is declared by base which main does not import
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 1);
+
+        // Every module declares its own `speed` and `Ship`
+        assert!(matches!(
+            slt.get_variable("main.speed").unwrap().value,
+            Value::Int(1)
+        ));
+        assert!(matches!(
+            slt.get_variable("fleet.fuel").unwrap().value,
+            Value::Int(2)
+        ));
+        assert!(slt.get_variable("base.speed").is_some());
+        assert_eq!(slt.get_record("base.Ship").unwrap().fields[0].id, "shields");
+
+        let Expr::Let {
+            value: Arg::Id { sym, .. },
+            ..
+        } = &program.func[0].body[0]
+        else {
+            panic!("expected a let statement");
+        };
+        assert_eq!(sym.unwrap().name, "main.speed");
+        let Expr::Let {
+            value: Arg::Id { sym, .. },
+            ..
+        } = &program.func[0].body[1]
+        else {
+            panic!("expected a let statement");
+        };
+        assert_eq!(sym.unwrap().name, "fleet.fuel");
+        assert_eq!(
+            slt.children[0].get_variable("falcon").unwrap().ty,
+            Type::Record("fleet.Ship")
+        );
+    }
+
+    #[test]
+    fn logical_operators() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. fuel
+                Who, mesa ? 3
+                I am a big deal in the resistance. low
+                Who, mesa ? You and what army? Smaller than a womp rat. fuel 5 I find your lack of faith disturbing. empty
+            May the force be with you.
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        // `empty` is not declared
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 1);
+
+        let Expr::Let {
+            value: Arg::Logic { lhs, rhs, .. },
+            ..
+        } = &program.func[0].body[1]
+        else {
+            panic!("expected a logical operation");
+        };
+        assert!(matches!(**lhs, Arg::Cmp { .. }));
+        assert!(matches!(**rhs, Arg::Not { .. }));
+        let fn_slt = &slt.children[0];
+        assert_eq!(
+            fn_slt.get_variable("low").unwrap().ty,
+            Type::Val(InnerType::Bool)
+        );
+    }
+
+    #[test]
+    fn read_input() {
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. name
+                Who, mesa ? What is thy bidding, my master? Holotext
+                I am a big deal in the resistance. debt
+                Who, mesa ? What is thy bidding, my master? Credit
+            May the force be with you.
+
+            That's one hell of a pilot. bounty
+            Who, mesa ? What is thy bidding, my master? Credit
+        "#;
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        Parser::new(input, &arena).parse(&mut program);
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        // Globals are evaluated at compile time
+        assert_eq!(resolve(&mut program, &mut builder, &mut slt, false), 1);
+
+        // Lines are read into dynamic strings
+        let fn_slt = &slt.children[0];
+        assert_eq!(fn_slt.get_variable("name").unwrap().ty, Type::Text);
+        assert_eq!(
+            fn_slt.get_variable("debt").unwrap().ty,
+            Type::Val(InnerType::Int)
+        );
+    }
+}
@@ -1,26 +1,52 @@
-use crate::ir::{Arg, Expr, Fn, InnerType, Lit, Program, Type};
-use crate::parser::slt::NavigableSlt;
+use crate::ir::{Arg, Cmp, Expr, Fn, InnerType, Lit, Op, Part, Program, Type};
+use crate::lexer::token::Span;
+use crate::parser::slt::{NavigableSlt, SymbolId};
 
 pub fn validate(program: &Program<'_>, slt: &NavigableSlt<'_, '_>) -> usize {
-    let childs = slt.childs();
+    let err_cpt = program.func.iter().fold(validate_main(program), |acc, f| {
+        crate::log::set_context(Some(f.file), Some(f.span.line));
+        // SAFETY: this is safe because the resolver gave a region to every function
+        let slt = slt.child(f.region.unwrap()).unwrap();
+        acc + validate_fn(f, &slt)
+    });
+    crate::log::set_context(None, None);
+    err_cpt
+}
 
-    let fn_err = program
-        .func
-        .iter()
-        .zip(childs)
-        .fold(0, |acc, (f, slt)| acc + validate_fn(f, &slt));
+/// Check that exactly one module declares the main function
+fn validate_main(program: &Program<'_>) -> usize {
+    let mut mains = program.func.iter().filter(|f| f.id == "galaxy");
+    let Some(main) = mains.next() else {
+        error!("no main function found, please declare the galaxy function");
+        return 1;
+    };
 
-    fn_err
+    // Duplicates in the same module are reported by the resolver
+    match mains.find(|f| f.module != main.module) {
+        Some(other) => {
+            error!(
+                "line {}: the galaxy function is declared in {} and {}",
+                other.span.line + 1,
+                main.file,
+                other.file
+            );
+            1
+        }
+        None => 0,
+    }
 }
 
 fn validate_fn(func: &Fn<'_>, slt: &NavigableSlt<'_, '_>) -> usize {
+    validate_body(&func.body, slt)
+}
+
+fn validate_body(body: &[Expr<'_>], slt: &NavigableSlt<'_, '_>) -> usize {
     let mut err_cpt = 0;
 
-    for expr in &func.body {
-        #[allow(clippy::single_match)]
+    for expr in body {
         match expr {
-            Expr::FnCall { id, args } => {
-                let Some(called_func) = slt.find_func(id) else {
+            Expr::FnCall { id, args, func, .. } => {
+                let Some(called_func) = func.and_then(|f| slt.resolve_func(f)) else {
                     error!("cannot find function {id} in this scope");
                     err_cpt += 1;
                     continue;
@@ -38,6 +64,16 @@ fn validate_fn(func: &Fn<'_>, slt: &NavigableSlt<'_, '_>) -> usize {
                     continue;
                 }
 
+                if called_func.variadic.is_none() && args.len() > min_args_number {
+                    error!("too many arguments passed to call function {id}");
+                    err_cpt += 1;
+                    continue;
+                }
+
+                for arg in args {
+                    err_cpt += validate_arg(arg, slt);
+                }
+
                 for i in 0..min_args_number {
                     let Some(ty) = get_arg_ty(&args[i], slt) else {
                         error!("unable to find the type of this expression");
@@ -45,7 +81,7 @@ fn validate_fn(func: &Fn<'_>, slt: &NavigableSlt<'_, '_>) -> usize {
                         continue;
                     };
 
-                    if ty != called_func.args[i] {
+                    if !called_func.args[i].accepts(&ty) {
                         error!(
                             "type mismatch for {id} argument number {i} expected `{}` and got `{}`",
                             called_func.args[i], ty
@@ -54,18 +90,862 @@ fn validate_fn(func: &Fn<'_>, slt: &NavigableSlt<'_, '_>) -> usize {
                     }
                 }
             }
-            _ => (),
+            Expr::Assign {
+                id,
+                span,
+                index,
+                field,
+                deref,
+                ops,
+                sym,
+            } => {
+                // SAFETY: this is safe because of the resolver
+                let mut var_ty = sym.and_then(|s| slt.resolve_variable(s)).unwrap().ty;
+
+                if *deref {
+                    let Some(ty) = deref_ty(id, *span, var_ty) else {
+                        err_cpt += 1;
+                        continue;
+                    };
+                    var_ty = ty;
+                }
+
+                if let Some(field) = field {
+                    let Some(ty) = field_ty(id, *span, var_ty, field, slt) else {
+                        err_cpt += 1;
+                        continue;
+                    };
+                    var_ty = ty;
+                } else if var_ty.record().is_some() {
+                    error!(
+                        "line {}: cannot assign to record {id}, assign its fields instead",
+                        span.line + 1
+                    );
+                    err_cpt += 1;
+                    continue;
+                }
+
+                if let Some(index) = index {
+                    err_cpt += validate_index(id, *span, var_ty, index, slt);
+                    var_ty = var_ty.elem().map(Type::Val).unwrap_or(var_ty);
+                } else if var_ty.elem().is_some() && !var_ty.is_heap() {
+                    error!(
+                        "line {}: cannot assign to array {id}, assign its elements instead",
+                        span.line + 1
+                    );
+                    err_cpt += 1;
+                    continue;
+                }
+
+                // Value of the variable when it is known at compile time
+                let mut known = None;
+                for (op, arg) in ops {
+                    err_cpt += validate_arg(arg, slt);
+                    let lhs = known.take();
+
+                    let Some(ty) = get_arg_ty(arg, slt) else {
+                        error!("unable to find the type of this expression");
+                        err_cpt += 1;
+                        continue;
+                    };
+
+                    let expected = match (op, var_ty) {
+                        (Op::Push, Type::Heap(inner)) => Some(Type::Val(inner)),
+                        (Op::Push, Type::Text) => Some(Type::Val(InnerType::Str)),
+                        (Op::Push, _) => None,
+                        (Op::Assign, _) => Some(var_ty),
+                        // Comparisons and logical operations result in a Signal
+                        (Op::Eq | Op::And | Op::Or, Type::Val(InnerType::Bool)) => Some(var_ty),
+                        (Op::Eq | Op::And | Op::Or, _) => None,
+                        (_, Type::Val(InnerType::Int)) => Some(var_ty),
+                        _ => None,
+                    };
+
+                    let Some(expected) = expected else {
+                        error!(
+                            "line {}: cannot apply operation {op} to {id} of type `{var_ty}`",
+                            span.line + 1
+                        );
+                        err_cpt += 1;
+                        continue;
+                    };
+
+                    if !expected.accepts(&ty) {
+                        error!(
+                            "line {}: type mismatch for operation {op} on {id}, expected `{expected}` and got `{ty}`",
+                            span.line + 1
+                        );
+                        err_cpt += 1;
+                    } else if matches!(op, Op::Assign)
+                        && validate_heap_copy(id, *span, arg, slt) > 0
+                    {
+                        err_cpt += 1;
+                    } else if matches!(op, Op::Push) && is_variable(arg, sym) {
+                        // The storage of the string may move while it is being copied
+                        error!("line {}: cannot push {id} onto itself", span.line + 1);
+                        err_cpt += 1;
+                    } else if var_ty == Type::Val(InnerType::Int) {
+                        let rhs = match arg {
+                            Arg::Lit(Lit::Int(i)) => Some(*i),
+                            _ => None,
+                        };
+                        match fold_arith(op, lhs, rhs, *span) {
+                            Ok(value) => known = value,
+                            Err(()) => err_cpt += 1,
+                        }
+                    }
+                }
+            }
+            Expr::Print { span, value } => {
+                err_cpt += validate_arg(value, slt);
+
+                match get_arg_ty(value, slt) {
+                    Some(Type::Val(_) | Type::Text) => (),
+                    Some(ty) => {
+                        error!(
+                            "line {}: cannot print a value of type `{ty}`",
+                            span.line + 1
+                        );
+                        err_cpt += 1;
+                    }
+                    None => {
+                        error!("unable to find the type of this expression");
+                        err_cpt += 1;
+                    }
+                }
+            }
+            Expr::Free { id, span, sym } => {
+                // SAFETY: this is safe because of the resolver
+                let var_ty = sym.and_then(|s| slt.resolve_variable(s)).unwrap().ty;
+                if !var_ty.is_heap() {
+                    error!(
+                        "line {}: cannot let go of {id} of type `{var_ty}`, it is not allocated on the heap",
+                        span.line + 1
+                    );
+                    err_cpt += 1;
+                }
+            }
+            Expr::If {
+                span,
+                cond,
+                body,
+                else_body,
+            } => {
+                err_cpt += validate_arg(cond, slt);
+                err_cpt += validate_cond(cond, *span, slt);
+                err_cpt += validate_body(body, slt);
+
+                if let Some(else_body) = else_body {
+                    err_cpt += validate_body(else_body, slt);
+                }
+            }
+            Expr::Assert { span, cond, .. } => {
+                err_cpt += validate_arg(cond, slt);
+                err_cpt += validate_cond(cond, *span, slt);
+            }
+            Expr::Panic { .. } => (),
+            Expr::While { span, cond, body } => {
+                err_cpt += validate_arg(cond, slt);
+                err_cpt += validate_cond(cond, *span, slt);
+                err_cpt += validate_body(body, slt);
+            }
+            Expr::Let {
+                id, span, value, ..
+            } => {
+                match value {
+                    Arg::Array { span, items } => {
+                        err_cpt += validate_array(*span, items);
+                        continue;
+                    }
+                    Arg::Record { id, span, fields } => {
+                        err_cpt += validate_record(id, *span, fields, slt);
+                        continue;
+                    }
+                    Arg::Alloc { span, len, .. } => {
+                        err_cpt += validate_arg(len, slt);
+                        err_cpt += validate_value(len, *span, Type::Val(InnerType::Int), slt);
+                        continue;
+                    }
+                    Arg::Text { span, init } => {
+                        err_cpt += validate_arg(init, slt);
+                        err_cpt += validate_value(init, *span, Type::Val(InnerType::Str), slt);
+                        continue;
+                    }
+                    Arg::Concat { span, lhs, rhs } => {
+                        err_cpt += validate_arg(lhs, slt) + validate_arg(rhs, slt);
+                        err_cpt += validate_value(lhs, *span, Type::Val(InnerType::Str), slt);
+                        err_cpt += validate_value(rhs, *span, Type::Val(InnerType::Str), slt);
+                        continue;
+                    }
+                    Arg::Substr {
+                        span,
+                        text,
+                        start,
+                        len,
+                    } => {
+                        err_cpt += validate_arg(text, slt);
+                        err_cpt += validate_arg(start, slt) + validate_arg(len, slt);
+                        err_cpt += validate_value(text, *span, Type::Val(InnerType::Str), slt);
+                        err_cpt += validate_value(start, *span, Type::Val(InnerType::Int), slt);
+                        err_cpt += validate_value(len, *span, Type::Val(InnerType::Int), slt);
+                        continue;
+                    }
+                    Arg::Interp { span, parts } => {
+                        for part in parts {
+                            let Part::Arg(arg) = part else {
+                                continue;
+                            };
+
+                            // Unknown variables have already been reported by the resolver
+                            match get_arg_ty(arg, slt) {
+                                Some(Type::Val(_) | Type::Text) | None => (),
+                                Some(ty) => {
+                                    error!(
+                                        "line {}: cannot interpolate a value of type `{ty}`",
+                                        span.line + 1
+                                    );
+                                    err_cpt += 1;
+                                }
+                            }
+                        }
+                        continue;
+                    }
+                    Arg::Read { span, ty } => {
+                        if !matches!(ty, InnerType::Int | InnerType::Str) {
+                            error!(
+                                "line {}: cannot read a value of type `{}` from the standard input, only `{}` and `{}` can be read",
+                                span.line + 1,
+                                Type::Val(*ty),
+                                Type::Val(InnerType::Int),
+                                Type::Val(InnerType::Str)
+                            );
+                            err_cpt += 1;
+                        }
+                        continue;
+                    }
+                    _ => (),
+                }
+
+                err_cpt += validate_arg(value, slt);
+                err_cpt += validate_heap_copy(id, *span, value, slt);
+
+                match get_arg_ty(value, slt) {
+                    Some(Type::Array(..)) => {
+                        error!(
+                            "line {}: cannot copy an array into {id}, arrays are passed by reference",
+                            span.line + 1
+                        );
+                        err_cpt += 1;
+                    }
+                    Some(Type::Record(..)) => {
+                        error!(
+                            "line {}: cannot copy a record into {id}, records are passed by reference",
+                            span.line + 1
+                        );
+                        err_cpt += 1;
+                    }
+                    Some(Type::Null) => {
+                        error!(
+                            "line {}: cannot infer the pointed type of {id} from a null pointer",
+                            span.line + 1
+                        );
+                        err_cpt += 1;
+                    }
+                    _ => (),
+                }
+            }
         }
     }
 
     err_cpt
 }
 
-fn get_arg_ty(expr: &Arg<'_>, slt: &NavigableSlt<'_, '_>) -> Option<Type> {
+/// Check the array, record and pointer accesses of an expression
+fn validate_arg(arg: &Arg<'_>, slt: &NavigableSlt<'_, '_>) -> usize {
+    match arg {
+        Arg::Lit(_) | Arg::Id { .. } | Arg::Null { .. } => 0,
+        Arg::AddrOf { span, target } => {
+            if !matches!(
+                **target,
+                Arg::Id { .. } | Arg::Index { .. } | Arg::Field { .. }
+            ) {
+                error!(
+                    "line {}: can only take the coordinates of a variable, an array element or a field",
+                    span.line + 1
+                );
+                return 1;
+            }
+
+            let err_cpt = validate_arg(target, slt);
+            match get_arg_ty(target, slt) {
+                Some(Type::Val(_)) => err_cpt,
+                // Errors of the target have already been reported
+                _ if err_cpt > 0 => err_cpt,
+                Some(ty) => {
+                    error!(
+                        "line {}: cannot take the coordinates of a value of type `{ty}`",
+                        span.line + 1
+                    );
+                    err_cpt + 1
+                }
+                None => {
+                    error!("unable to find the type of this expression");
+                    err_cpt + 1
+                }
+            }
+        }
+        Arg::Deref { id, span, sym } => {
+            // SAFETY: this is safe because of the resolver
+            let var_ty = sym.and_then(|s| slt.resolve_variable(s)).unwrap().ty;
+            match deref_ty(id, *span, var_ty) {
+                Some(_) => 0,
+                None => 1,
+            }
+        }
+        Arg::Index {
+            id,
+            span,
+            index,
+            sym,
+        } => {
+            // SAFETY: this is safe because of the resolver
+            let var_ty = sym.and_then(|s| slt.resolve_variable(s)).unwrap().ty;
+            validate_arg(index, slt) + validate_index(id, *span, var_ty, index, slt)
+        }
+        Arg::Len { id, span, sym } => {
+            // SAFETY: this is safe because of the resolver
+            let var_ty = sym.and_then(|s| slt.resolve_variable(s)).unwrap().ty;
+            if var_ty.elem().is_none() && !var_ty.is_str() {
+                error!(
+                    "line {}: cannot take the length of {id} of type `{var_ty}`",
+                    span.line + 1
+                );
+                1
+            } else {
+                0
+            }
+        }
+        Arg::Field {
+            id,
+            span,
+            field,
+            sym,
+        } => {
+            // SAFETY: this is safe because of the resolver
+            let var_ty = sym.and_then(|s| slt.resolve_variable(s)).unwrap().ty;
+            match field_ty(id, *span, var_ty, field, slt) {
+                Some(_) => 0,
+                None => 1,
+            }
+        }
+        Arg::Array { span, .. } => {
+            error!(
+                "line {}: array literals are only allowed as the value of a variable",
+                span.line + 1
+            );
+            1
+        }
+        Arg::Record { span, .. } => {
+            error!(
+                "line {}: record constructions are only allowed as the value of a variable",
+                span.line + 1
+            );
+            1
+        }
+        Arg::Cmp { span, op, lhs, rhs } => {
+            let err_cpt = validate_arg(lhs, slt) + validate_arg(rhs, slt);
+            match (get_arg_ty(lhs, slt), get_arg_ty(rhs, slt)) {
+                (Some(lhs), Some(rhs)) if is_comparable(op, &lhs, &rhs) => err_cpt,
+                // Errors of the operands have already been reported
+                _ if err_cpt > 0 => err_cpt,
+                (Some(lhs), Some(rhs)) => {
+                    error!(
+                        "line {}: cannot compare `{lhs}` with `{rhs}` using {op}",
+                        span.line + 1
+                    );
+                    err_cpt + 1
+                }
+                _ => {
+                    error!("unable to find the type of this expression");
+                    err_cpt + 1
+                }
+            }
+        }
+        Arg::Logic { span, op, lhs, rhs } => {
+            let mut err_cpt = 0;
+            for arg in [lhs, rhs] {
+                err_cpt += validate_signal(arg, *span, &op.to_string(), slt);
+            }
+            err_cpt
+        }
+        Arg::Not { span, arg } => validate_signal(arg, *span, "not", slt),
+        Arg::Alloc { span, .. }
+        | Arg::Text { span, .. }
+        | Arg::Concat { span, .. }
+        | Arg::Substr { span, .. }
+        | Arg::Interp { span, .. } => {
+            error!(
+                "line {}: heap allocations are only allowed as the value of a variable",
+                span.line + 1
+            );
+            1
+        }
+        Arg::Read { span, .. } => {
+            error!(
+                "line {}: reading the standard input is only allowed as the value of a variable",
+                span.line + 1
+            );
+            1
+        }
+    }
+}
+
+/// Whether two values of these types can be compared, strings are compared by their content
+fn is_comparable(op: &Cmp, lhs: &Type<'_>, rhs: &Type<'_>) -> bool {
+    match (lhs, rhs) {
+        (lhs, rhs) if lhs.is_str() && rhs.is_str() => true,
+        (Type::Val(InnerType::Int), Type::Val(InnerType::Int))
+        | (Type::Val(InnerType::Char), Type::Val(InnerType::Char)) => true,
+        (Type::Val(InnerType::Bool), Type::Val(InnerType::Bool)) => matches!(op, Cmp::Eq),
+        // Pointers are only compared to find out whether they are null
+        (Type::Ptr(_) | Type::Null, Type::Ptr(_) | Type::Null) => {
+            matches!(op, Cmp::Eq) && (lhs.accepts(rhs) || rhs.accepts(lhs))
+        }
+        _ => false,
+    }
+}
+
+/// Validate an operand of a logical operation, it must be a Signal
+fn validate_signal(arg: &Arg<'_>, span: Span, op: &str, slt: &NavigableSlt<'_, '_>) -> usize {
+    let err_cpt = validate_arg(arg, slt);
+    match get_arg_ty(arg, slt) {
+        Some(Type::Val(InnerType::Bool)) => err_cpt,
+        // Errors of the operand have already been reported
+        _ if err_cpt > 0 => err_cpt,
+        Some(ty) => {
+            error!(
+                "line {}: operands of {op} must be Signals, found `{ty}`",
+                span.line + 1
+            );
+            err_cpt + 1
+        }
+        None => {
+            error!("unable to find the type of this expression");
+            err_cpt + 1
+        }
+    }
+}
+
+/// Evaluate an operation on Credits known at compile time, the operations that always overflow
+/// or divide by zero are reported
+fn fold_arith(op: &Op, lhs: Option<i64>, rhs: Option<i64>, span: Span) -> Result<Option<i64>, ()> {
+    if matches!(op, Op::Div | Op::Mod) && rhs == Some(0) {
+        error!("line {}: attempt to divide by zero", span.line + 1);
+        return Err(());
+    }
+
+    let (Some(lhs), Some(rhs)) = (lhs, rhs) else {
+        return Ok(if matches!(op, Op::Assign) { rhs } else { None });
+    };
+
+    let value = match op {
+        Op::Assign => Some(rhs),
+        Op::Add => lhs.checked_add(rhs),
+        Op::Sub => lhs.checked_sub(rhs),
+        Op::Mul => lhs.checked_mul(rhs),
+        Op::Div => lhs.checked_div(rhs),
+        Op::Mod => lhs.checked_rem(rhs),
+        _ => return Ok(None),
+    };
+
+    match value {
+        Some(value) => Ok(Some(value)),
+        None => {
+            error!(
+                "line {}: operation {op} on {lhs} and {rhs} overflows",
+                span.line + 1
+            );
+            Err(())
+        }
+    }
+}
+
+/// Tell if an expression is the variable `sym`
+fn is_variable(arg: &Arg<'_>, sym: &Option<SymbolId<'_>>) -> bool {
+    matches!(arg, Arg::Id { sym: Some(s), .. } if Some(*s) == *sym)
+}
+
+/// Check that `value` is not a variable owning a heap value, copying it into `id` would share
+/// its allocation and letting go of one of them would leave the other dangling
+fn validate_heap_copy(id: &str, span: Span, value: &Arg<'_>, slt: &NavigableSlt<'_, '_>) -> usize {
+    let Arg::Id { id: owner, .. } = value else {
+        return 0;
+    };
+
+    match get_arg_ty(value, slt) {
+        Some(ty) if ty.is_heap() => {
+            error!(
+                "line {}: cannot copy {owner} into {id}, both would own the same `{ty}` and letting go of one would leave the other dangling",
+                span.line + 1
+            );
+            1
+        }
+        _ => 0,
+    }
+}
+
+/// Check that a value can be given where a value of type `expected` is expected
+fn validate_value(
+    value: &Arg<'_>,
+    span: Span,
+    expected: Type<'_>,
+    slt: &NavigableSlt<'_, '_>,
+) -> usize {
+    match get_arg_ty(value, slt) {
+        Some(ty) if expected.accepts(&ty) => 0,
+        Some(ty) => {
+            error!(
+                "line {}: type mismatch, expected `{expected}` and got `{ty}`",
+                span.line + 1
+            );
+            1
+        }
+        None => {
+            error!("unable to find the type of this expression");
+            1
+        }
+    }
+}
+
+/// Get the type of the value pointed by a pointer, reporting values that are not pointers
+fn deref_ty<'prog>(id: &str, span: Span, var_ty: Type<'prog>) -> Option<Type<'prog>> {
+    match var_ty {
+        Type::Ptr(inner) => Some(Type::Val(inner)),
+        _ => {
+            error!(
+                "line {}: cannot jump to {id} of type `{var_ty}`, it is not a pointer",
+                span.line + 1
+            );
+            None
+        }
+    }
+}
+
+/// Get the type of a field of a record, reporting unknown fields
+fn field_ty<'prog>(
+    id: &str,
+    span: Span,
+    var_ty: Type<'prog>,
+    field: &str,
+    slt: &NavigableSlt<'_, 'prog>,
+) -> Option<Type<'prog>> {
+    let Some(record) = var_ty.record().and_then(|r| slt.find_record(r)) else {
+        error!(
+            "line {}: cannot access field {field} of {id} of type `{var_ty}`",
+            span.line + 1
+        );
+        return None;
+    };
+
+    let Some(field) = record.get_field(field) else {
+        error!(
+            "line {}: record {} has no field {field}",
+            span.line + 1,
+            record.id
+        );
+        return None;
+    };
+
+    Some(Type::Val(field.ty))
+}
+
+/// Check that a record construction gives a value of the right type to every field
+fn validate_record(
+    id: &str,
+    span: Span,
+    fields: &[(&str, Arg<'_>)],
+    slt: &NavigableSlt<'_, '_>,
+) -> usize {
+    // The resolver already reported unknown records
+    let Some(record) = slt.find_record(id) else {
+        return 0;
+    };
+    // The record is named by its symbol
+    let id = record.id;
+
+    let mut err_cpt = 0;
+    for (i, (name, value)) in fields.iter().enumerate() {
+        err_cpt += validate_arg(value, slt);
+
+        let Some(field) = record.get_field(name) else {
+            error!("line {}: record {id} has no field {name}", span.line + 1);
+            err_cpt += 1;
+            continue;
+        };
+
+        if fields[..i].iter().any(|(other, _)| other == name) {
+            error!(
+                "line {}: field {name} of record {id} given twice",
+                span.line + 1
+            );
+            err_cpt += 1;
+            continue;
+        }
+
+        match get_arg_ty(value, slt) {
+            Some(ty) if ty == Type::Val(field.ty) => (),
+            Some(ty) => {
+                error!(
+                    "line {}: type mismatch for field {name} of record {id}, expected `{}` and got `{ty}`",
+                    span.line + 1,
+                    Type::Val(field.ty)
+                );
+                err_cpt += 1;
+            }
+            None => {
+                error!("unable to find the type of this expression");
+                err_cpt += 1;
+            }
+        }
+    }
+
+    for field in record.fields.iter() {
+        if !fields.iter().any(|(name, _)| *name == field.id) {
+            error!(
+                "line {}: missing field {} in construction of record {id}",
+                span.line + 1,
+                field.id
+            );
+            err_cpt += 1;
+        }
+    }
+
+    err_cpt
+}
+
+fn validate_array(span: Span, items: &[Lit<'_>]) -> usize {
+    let ty = items[0].ty();
+    match items.iter().find(|item| item.ty() != ty) {
+        Some(item) => {
+            error!(
+                "line {}: array elements must all be of type `{ty}`, found `{}`",
+                span.line + 1,
+                item.ty()
+            );
+            1
+        }
+        None => 0,
+    }
+}
+
+fn validate_index(
+    id: &str,
+    span: Span,
+    var_ty: Type<'_>,
+    index: &Arg<'_>,
+    slt: &NavigableSlt<'_, '_>,
+) -> usize {
+    if var_ty.elem().is_none() {
+        error!(
+            "line {}: cannot index {id} of type `{var_ty}`",
+            span.line + 1
+        );
+        return 1;
+    }
+
+    match get_arg_ty(index, slt) {
+        Some(Type::Val(InnerType::Int)) => 0,
+        Some(ty) => {
+            error!(
+                "line {}: index of {id} must be of type `{}` and got `{ty}`",
+                span.line + 1,
+                Type::Val(InnerType::Int)
+            );
+            1
+        }
+        None => {
+            error!("unable to find the type of this expression");
+            1
+        }
+    }
+}
+
+fn validate_cond(cond: &Arg<'_>, span: Span, slt: &NavigableSlt<'_, '_>) -> usize {
+    match get_arg_ty(cond, slt) {
+        Some(Type::Val(InnerType::Bool)) => 0,
+        Some(ty) => {
+            error!(
+                "line {}: condition must be of type `{}` and got `{ty}`",
+                span.line + 1,
+                Type::Val(InnerType::Bool)
+            );
+            1
+        }
+        None => {
+            error!("unable to find the type of this expression");
+            1
+        }
+    }
+}
+
+/// Type of an expression, `None` if one of its symbols could not be resolved
+pub fn get_arg_ty<'prog>(expr: &Arg<'prog>, slt: &NavigableSlt<'_, 'prog>) -> Option<Type<'prog>> {
     match expr {
         Arg::Lit(Lit::Int(_)) => Some(Type::Val(InnerType::Int)),
         Arg::Lit(Lit::Str(_)) => Some(Type::Val(InnerType::Str)),
         Arg::Lit(Lit::Bool(_)) => Some(Type::Val(InnerType::Bool)),
-        Arg::Id(id) => slt.find_variable(id).map(|var| var.ty),
+        Arg::Lit(Lit::Char(_)) => Some(Type::Val(InnerType::Char)),
+        Arg::Id { sym, .. } => sym.and_then(|s| slt.resolve_variable(s)).map(|var| var.ty),
+        Arg::Index { sym, .. } => sym
+            .and_then(|s| slt.resolve_variable(s))
+            .and_then(|var| var.ty.elem())
+            .map(Type::Val),
+        Arg::Len { .. } => Some(Type::Val(InnerType::Int)),
+        Arg::Array { items, .. } => Some(Type::Array(items[0].ty(), items.len())),
+        Arg::Field { sym, field, .. } => sym
+            .and_then(|s| slt.resolve_variable(s))
+            .and_then(|var| var.ty.record())
+            .and_then(|r| slt.find_record(r))
+            .and_then(|r| r.get_field(field))
+            .map(|f| Type::Val(f.ty)),
+        Arg::Record { id, .. } => Some(Type::Record(id)),
+        Arg::AddrOf { target, .. } => match get_arg_ty(target, slt)? {
+            Type::Val(inner) => Some(Type::Ptr(inner)),
+            _ => None,
+        },
+        Arg::Deref { sym, .. } => match sym.and_then(|s| slt.resolve_variable(s))?.ty {
+            Type::Ptr(inner) => Some(Type::Val(inner)),
+            _ => None,
+        },
+        Arg::Null { .. } => Some(Type::Null),
+        Arg::Alloc { ty, .. } => Some(Type::Heap(*ty)),
+        Arg::Text { .. } => Some(Type::Text),
+        Arg::Read {
+            ty: InnerType::Str, ..
+        } => Some(Type::Text),
+        Arg::Read { ty, .. } => Some(Type::Val(*ty)),
+        Arg::Cmp { .. } | Arg::Logic { .. } | Arg::Not { .. } => Some(Type::Val(InnerType::Bool)),
+        Arg::Concat { .. } | Arg::Substr { .. } | Arg::Interp { .. } => Some(Type::Text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn validate_source(input: &str) -> usize {
+        let arena = crate::arena::Arena::new();
+        let mut program = Program::new();
+        let mut parser = Parser::new(input, &arena);
+        parser.parse(&mut program);
+        assert_eq!(parser.err_cpt, 0);
+
+        let mut builder = crate::parser::slt::Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(
+            crate::resolver::resolve(&mut program, &mut builder, &mut slt, false),
+            0
+        );
+        validate(&program, &(&slt).into())
+    }
+
+    #[test]
+    fn call_arity() {
+        let input = r#"
+            Hypersignal printf Starfield 1
+                Holotext
+            Jamsignal
+
+            A long time ago in a galaxy far, far away...
+                Execute order printf
+                    "%ld %ld"
+                    1
+                    2
+                Order executed
+                Execute order boost
+                    1
+                    2
+                Order executed
+                Execute order boost
+                Order executed
+            May the force be with you.
+
+            A long time ago in a boost far, far away...
+                Cargo
+                    Credit distance
+                UnloadCargo
+            May the force be with you.
+        "#;
+        let (err_cpt, records) = crate::log::capture(|| validate_source(input));
+        assert_eq!(err_cpt, 2);
+
+        let messages: Vec<_> = records.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "too many arguments passed to call function boost",
+                "not enough arguments passed to call function boost"
+            ]
+        );
+    }
+
+    #[test]
+    fn heap_values_are_not_aliased() {
+        // Letting go of s would leave t dangling and letting go of t later would free s twice
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. s
+                Who, mesa ? Begin recording. "Luke"
+                I am a big deal in the resistance. t
+                Who, mesa ? s
+                Let go, Luke. s
+                You'll find I'm full of surprises. t
+            May the force be with you.
+        "#;
+        assert_eq!(validate_source(input), 1);
+
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. fleet
+                Who, mesa ? Send in the clones. Credit 2
+                I am a big deal in the resistance. copy
+                Who, mesa ? Send in the clones. Credit 2
+                What a piece of junk! copy
+                    I am your father. fleet
+                The garbage will do.
+            May the force be with you.
+        "#;
+        assert_eq!(validate_source(input), 1);
+
+        // A new recording copies the string
+        let input = r#"
+            A long time ago in a galaxy far, far away...
+                I am a big deal in the resistance. s
+                Who, mesa ? Begin recording. "Luke"
+                I am a big deal in the resistance. t
+                Who, mesa ? Begin recording. s
+                Let go, Luke. s
+                You'll find I'm full of surprises. t
+                Let go, Luke. t
+            May the force be with you.
+        "#;
+        assert_eq!(validate_source(input), 0);
+    }
+
+    #[test]
+    fn constant_arithmetic() {
+        let span = Span::default();
+        assert_eq!(fold_arith(&Op::Assign, None, Some(7), span), Ok(Some(7)));
+        assert_eq!(fold_arith(&Op::Mul, Some(6), Some(7), span), Ok(Some(42)));
+        assert_eq!(fold_arith(&Op::Mod, Some(-7), Some(2), span), Ok(Some(-1)));
+        assert_eq!(fold_arith(&Op::Add, None, Some(1), span), Ok(None));
+        assert_eq!(fold_arith(&Op::Add, Some(i64::MAX), Some(1), span), Err(()));
+        assert_eq!(
+            fold_arith(&Op::Div, Some(i64::MIN), Some(-1), span),
+            Err(())
+        );
+        // A zero divisor always fails even when the dividend is unknown
+        assert_eq!(fold_arith(&Op::Div, None, Some(0), span), Err(()));
     }
 }
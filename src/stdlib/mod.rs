@@ -0,0 +1,97 @@
+//! Standard library of HSL
+//!
+//! The modules are written in HSL and embedded in the compiler binary. A program imports them
+//! with a path starting with `std/`, e.g. `You're coming with me. "std/math"`, they are then
+//! parsed and compiled along with the program like any other module.
+
+pub const PREFIX: &str = "std/";
+
+/// Import path and source of each module of the standard library
+pub const MODULES: &[(&str, &str)] = &[
+    ("std/io", include_str!("io.hsl")),
+    ("std/math", include_str!("math.hsl")),
+    ("std/strings", include_str!("strings.hsl")),
+];
+
+/// Source of the module of the standard library imported with `path`
+pub fn find(path: &str) -> Option<&'static str> {
+    MODULES
+        .iter()
+        .find(|(module, _)| *module == path)
+        .map(|(_, source)| *source)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::slt::{Builder, NavigableSlt};
+
+    /// Compile the test program of the module `name` found in `src/stdlib/tests`. On a host able
+    /// to run the target the program is assembled and run as well, its assertions must hold
+    fn compile(name: &str) {
+        // Each test gets its own directory as the tests run in parallel
+        let dir = std::env::temp_dir().join("hsl_stdlib").join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let program: std::ffi::OsString = "hsl".into();
+        let args: Vec<std::ffi::OsString> = vec![
+            "-q".into(),
+            "-t".into(),
+            "aarch64-darwin".into(),
+            "-o".into(),
+            dir.join(name).into_os_string(),
+            format!("{}/src/stdlib/tests/{name}.hsl", env!("CARGO_MANIFEST_DIR")).into(),
+        ];
+        let project = std::cell::OnceCell::new();
+        let crate::flags::FlagsResult::Ok(flags) = crate::flags::Flags::parse(
+            Some(&program),
+            args.iter().map(|a| a.as_os_str()),
+            None,
+            &project,
+        ) else {
+            panic!("invalid flags");
+        };
+
+        let arena = crate::arena::Arena::new();
+        let mut c = crate::compiler::Compiler::new(&arena, flags).unwrap();
+        assert_eq!(c.parse_modules(), Ok(()));
+
+        let mut builder = Builder::new();
+        let mut slt = builder.region();
+        assert_eq!(
+            crate::resolver::resolve(&mut c.program, &mut builder, &mut slt, false),
+            0
+        );
+
+        let nav_slt: NavigableSlt<'_, '_> = (&slt).into();
+        assert_eq!(crate::semantic::validate(&c.program, &nav_slt), 0);
+
+        let mut assembly = Vec::new();
+        let mut codegen = crate::codegen::aarch64::Codegen::new(&c, &mut assembly);
+        assert!(codegen.generate_assembly(&c.program, &nav_slt).is_ok());
+        assert!(!assembly.is_empty());
+
+        #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+        {
+            std::fs::write(c.output_path, &assembly).unwrap();
+            let mut cmd = crate::command::Cmd::new(true);
+            assert!(crate::codegen::aarch64::assemble(&c, &mut cmd).is_ok());
+
+            let status = std::process::Command::new(c.program_path).status().unwrap();
+            assert!(status.success());
+        }
+    }
+
+    #[test]
+    fn io() {
+        compile("io");
+    }
+
+    #[test]
+    fn math() {
+        compile("math");
+    }
+
+    #[test]
+    fn strings() {
+        compile("strings");
+    }
+}
@@ -0,0 +1,35 @@
+//! Exit codes of the command line, scripts and editors tell the failing step from them
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// An empty directory for the test, the compiler looks for a project from it
+fn directory(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("hsl_exit_codes").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn check(dir: &PathBuf, file: &str) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_hsl"))
+        .args(["check", file])
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status
+        .code()
+}
+
+#[test]
+fn missing_source_is_a_setup_error() {
+    let dir = directory("missing");
+    assert_eq!(check(&dir, "nonexist.hsl"), Some(2));
+}
+
+#[test]
+fn invalid_source_is_a_parse_error() {
+    let dir = directory("invalid");
+    std::fs::write(dir.join("invalid.hsl"), "Jump to\n").unwrap();
+    assert_eq!(check(&dir, "invalid.hsl"), Some(3));
+}
@@ -10,11 +10,14 @@
 module.exports = grammar({
   name: "hsl",
 
-  externals: $ => [$.string_content],
+  // The block comments nest, they are matched by the external scanner. The error sentinel is
+  // never used by the rules, it tells the scanner that the parser is recovering from an error
+  externals: $ => [$.string_content, $.block_comment, $.error_sentinel],
 
   extras: $ => [
     /\s/,
-    $.line_comment
+    $.line_comment,
+    $.block_comment
   ],
 
   rules: {
@@ -33,6 +33,7 @@
 ; Comments
 
 (line_comment) @comment
+(block_comment) @comment
 
 ; Keywords
 
@@ -17,10 +17,10 @@
 #define LANGUAGE_VERSION 15
 #define STATE_COUNT 82
 #define LARGE_STATE_COUNT 2
-#define SYMBOL_COUNT 54
+#define SYMBOL_COUNT 56
 #define ALIAS_COUNT 0
-#define TOKEN_COUNT 32
-#define EXTERNAL_TOKEN_COUNT 1
+#define TOKEN_COUNT 34
+#define EXTERNAL_TOKEN_COUNT 3
 #define FIELD_COUNT 3
 #define MAX_ALIAS_SEQUENCE_LENGTH 7
 #define MAX_RESERVED_WORD_SET_SIZE 0
@@ -59,28 +59,30 @@ enum ts_symbol_identifiers {
   anon_sym_DASH = 29,
   sym_unsigned_integer = 30,
   sym_string_content = 31,
-  sym_source_file = 32,
-  sym__definition = 33,
-  sym_line_comment = 34,
-  sym_extern_function = 35,
-  sym_function_definition = 36,
-  sym_function_arguments = 37,
-  sym_function_argument = 38,
-  sym_statement = 39,
-  sym_declaration = 40,
-  sym_function_call = 41,
-  sym_variadic = 42,
-  sym_expression = 43,
-  sym_literal = 44,
-  sym_string = 45,
-  sym_char = 46,
-  sym_bool = 47,
-  sym_integer = 48,
-  aux_sym_source_file_repeat1 = 49,
-  aux_sym_function_definition_repeat1 = 50,
-  aux_sym_function_arguments_repeat1 = 51,
-  aux_sym_function_call_repeat1 = 52,
-  aux_sym_string_repeat1 = 53,
+  sym_block_comment = 32,
+  sym_error_sentinel = 33,
+  sym_source_file = 34,
+  sym__definition = 35,
+  sym_line_comment = 36,
+  sym_extern_function = 37,
+  sym_function_definition = 38,
+  sym_function_arguments = 39,
+  sym_function_argument = 40,
+  sym_statement = 41,
+  sym_declaration = 42,
+  sym_function_call = 43,
+  sym_variadic = 44,
+  sym_expression = 45,
+  sym_literal = 46,
+  sym_string = 47,
+  sym_char = 48,
+  sym_bool = 49,
+  sym_integer = 50,
+  aux_sym_source_file_repeat1 = 51,
+  aux_sym_function_definition_repeat1 = 52,
+  aux_sym_function_arguments_repeat1 = 53,
+  aux_sym_function_call_repeat1 = 54,
+  aux_sym_string_repeat1 = 55,
 };
 
 static const char * const ts_symbol_names[] = {
@@ -116,6 +118,8 @@ static const char * const ts_symbol_names[] = {
   [anon_sym_DASH] = "-",
   [sym_unsigned_integer] = "unsigned_integer",
   [sym_string_content] = "string_content",
+  [sym_block_comment] = "block_comment",
+  [sym_error_sentinel] = "error_sentinel",
   [sym_source_file] = "source_file",
   [sym__definition] = "_definition",
   [sym_line_comment] = "line_comment",
@@ -173,6 +177,8 @@ static const TSSymbol ts_symbol_map[] = {
   [anon_sym_DASH] = anon_sym_DASH,
   [sym_unsigned_integer] = sym_unsigned_integer,
   [sym_string_content] = sym_string_content,
+  [sym_block_comment] = sym_block_comment,
+  [sym_error_sentinel] = sym_error_sentinel,
   [sym_source_file] = sym_source_file,
   [sym__definition] = sym__definition,
   [sym_line_comment] = sym_line_comment,
@@ -326,6 +332,14 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
+  [sym_block_comment] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_error_sentinel] = {
+    .visible = true,
+    .named = true,
+  },
   [sym_source_file] = {
     .visible = true,
     .named = true,
@@ -2028,87 +2042,87 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
 }
 
 static const TSLexerMode ts_lex_modes[STATE_COUNT] = {
-  [0] = {.lex_state = 0, .external_lex_state = 1},
-  [1] = {.lex_state = 332},
-  [2] = {.lex_state = 49},
-  [3] = {.lex_state = 49},
-  [4] = {.lex_state = 49},
-  [5] = {.lex_state = 50},
-  [6] = {.lex_state = 332},
-  [7] = {.lex_state = 332},
-  [8] = {.lex_state = 332},
-  [9] = {.lex_state = 332},
-  [10] = {.lex_state = 332},
-  [11] = {.lex_state = 332},
-  [12] = {.lex_state = 332},
-  [13] = {.lex_state = 332},
-  [14] = {.lex_state = 49},
-  [15] = {.lex_state = 49},
-  [16] = {.lex_state = 49},
-  [17] = {.lex_state = 49},
-  [18] = {.lex_state = 49},
-  [19] = {.lex_state = 49},
-  [20] = {.lex_state = 49},
-  [21] = {.lex_state = 49},
-  [22] = {.lex_state = 49},
-  [23] = {.lex_state = 332},
-  [24] = {.lex_state = 332},
-  [25] = {.lex_state = 332},
-  [26] = {.lex_state = 332},
-  [27] = {.lex_state = 332},
-  [28] = {.lex_state = 332},
-  [29] = {.lex_state = 332},
-  [30] = {.lex_state = 332},
-  [31] = {.lex_state = 332},
-  [32] = {.lex_state = 332},
-  [33] = {.lex_state = 332},
-  [34] = {.lex_state = 332},
-  [35] = {.lex_state = 332},
-  [36] = {.lex_state = 332},
-  [37] = {.lex_state = 332},
-  [38] = {.lex_state = 332},
-  [39] = {.lex_state = 332},
-  [40] = {.lex_state = 332},
-  [41] = {.lex_state = 332},
-  [42] = {.lex_state = 332},
-  [43] = {.lex_state = 332},
-  [44] = {.lex_state = 332},
-  [45] = {.lex_state = 332, .external_lex_state = 1},
-  [46] = {.lex_state = 332, .external_lex_state = 1},
-  [47] = {.lex_state = 332, .external_lex_state = 1},
-  [48] = {.lex_state = 332},
-  [49] = {.lex_state = 332, .external_lex_state = 1},
-  [50] = {.lex_state = 332, .external_lex_state = 1},
-  [51] = {.lex_state = 332},
-  [52] = {.lex_state = 332},
-  [53] = {.lex_state = 332},
-  [54] = {.lex_state = 332, .external_lex_state = 1},
-  [55] = {.lex_state = 332},
-  [56] = {.lex_state = 332},
-  [57] = {.lex_state = 332},
-  [58] = {.lex_state = 332},
-  [59] = {.lex_state = 332},
-  [60] = {.lex_state = 332},
-  [61] = {.lex_state = 332},
-  [62] = {.lex_state = 332},
-  [63] = {.lex_state = 332},
-  [64] = {.lex_state = 0},
-  [65] = {.lex_state = 69},
-  [66] = {.lex_state = 332},
-  [67] = {.lex_state = 332},
-  [68] = {.lex_state = 332},
-  [69] = {.lex_state = 69},
-  [70] = {.lex_state = 0},
-  [71] = {.lex_state = 70},
-  [72] = {.lex_state = 69},
-  [73] = {.lex_state = 0},
-  [74] = {.lex_state = 69},
-  [75] = {.lex_state = 69},
+  [0] = {.lex_state = 0, .external_lex_state = 3},
+  [1] = {.lex_state = 332, .external_lex_state = 1},
+  [2] = {.lex_state = 49, .external_lex_state = 1},
+  [3] = {.lex_state = 49, .external_lex_state = 1},
+  [4] = {.lex_state = 49, .external_lex_state = 1},
+  [5] = {.lex_state = 50, .external_lex_state = 1},
+  [6] = {.lex_state = 332, .external_lex_state = 1},
+  [7] = {.lex_state = 332, .external_lex_state = 1},
+  [8] = {.lex_state = 332, .external_lex_state = 1},
+  [9] = {.lex_state = 332, .external_lex_state = 1},
+  [10] = {.lex_state = 332, .external_lex_state = 1},
+  [11] = {.lex_state = 332, .external_lex_state = 1},
+  [12] = {.lex_state = 332, .external_lex_state = 1},
+  [13] = {.lex_state = 332, .external_lex_state = 1},
+  [14] = {.lex_state = 49, .external_lex_state = 1},
+  [15] = {.lex_state = 49, .external_lex_state = 1},
+  [16] = {.lex_state = 49, .external_lex_state = 1},
+  [17] = {.lex_state = 49, .external_lex_state = 1},
+  [18] = {.lex_state = 49, .external_lex_state = 1},
+  [19] = {.lex_state = 49, .external_lex_state = 1},
+  [20] = {.lex_state = 49, .external_lex_state = 1},
+  [21] = {.lex_state = 49, .external_lex_state = 1},
+  [22] = {.lex_state = 49, .external_lex_state = 1},
+  [23] = {.lex_state = 332, .external_lex_state = 1},
+  [24] = {.lex_state = 332, .external_lex_state = 1},
+  [25] = {.lex_state = 332, .external_lex_state = 1},
+  [26] = {.lex_state = 332, .external_lex_state = 1},
+  [27] = {.lex_state = 332, .external_lex_state = 1},
+  [28] = {.lex_state = 332, .external_lex_state = 1},
+  [29] = {.lex_state = 332, .external_lex_state = 1},
+  [30] = {.lex_state = 332, .external_lex_state = 1},
+  [31] = {.lex_state = 332, .external_lex_state = 1},
+  [32] = {.lex_state = 332, .external_lex_state = 1},
+  [33] = {.lex_state = 332, .external_lex_state = 1},
+  [34] = {.lex_state = 332, .external_lex_state = 1},
+  [35] = {.lex_state = 332, .external_lex_state = 1},
+  [36] = {.lex_state = 332, .external_lex_state = 1},
+  [37] = {.lex_state = 332, .external_lex_state = 1},
+  [38] = {.lex_state = 332, .external_lex_state = 1},
+  [39] = {.lex_state = 332, .external_lex_state = 1},
+  [40] = {.lex_state = 332, .external_lex_state = 1},
+  [41] = {.lex_state = 332, .external_lex_state = 1},
+  [42] = {.lex_state = 332, .external_lex_state = 1},
+  [43] = {.lex_state = 332, .external_lex_state = 1},
+  [44] = {.lex_state = 332, .external_lex_state = 1},
+  [45] = {.lex_state = 332, .external_lex_state = 2},
+  [46] = {.lex_state = 332, .external_lex_state = 2},
+  [47] = {.lex_state = 332, .external_lex_state = 2},
+  [48] = {.lex_state = 332, .external_lex_state = 1},
+  [49] = {.lex_state = 332, .external_lex_state = 2},
+  [50] = {.lex_state = 332, .external_lex_state = 2},
+  [51] = {.lex_state = 332, .external_lex_state = 1},
+  [52] = {.lex_state = 332, .external_lex_state = 1},
+  [53] = {.lex_state = 332, .external_lex_state = 1},
+  [54] = {.lex_state = 332, .external_lex_state = 2},
+  [55] = {.lex_state = 332, .external_lex_state = 1},
+  [56] = {.lex_state = 332, .external_lex_state = 1},
+  [57] = {.lex_state = 332, .external_lex_state = 1},
+  [58] = {.lex_state = 332, .external_lex_state = 1},
+  [59] = {.lex_state = 332, .external_lex_state = 1},
+  [60] = {.lex_state = 332, .external_lex_state = 1},
+  [61] = {.lex_state = 332, .external_lex_state = 1},
+  [62] = {.lex_state = 332, .external_lex_state = 1},
+  [63] = {.lex_state = 332, .external_lex_state = 1},
+  [64] = {.lex_state = 0, .external_lex_state = 1},
+  [65] = {.lex_state = 69, .external_lex_state = 1},
+  [66] = {.lex_state = 332, .external_lex_state = 1},
+  [67] = {.lex_state = 332, .external_lex_state = 1},
+  [68] = {.lex_state = 332, .external_lex_state = 1},
+  [69] = {.lex_state = 69, .external_lex_state = 1},
+  [70] = {.lex_state = 0, .external_lex_state = 1},
+  [71] = {.lex_state = 70, .external_lex_state = 1},
+  [72] = {.lex_state = 69, .external_lex_state = 1},
+  [73] = {.lex_state = 0, .external_lex_state = 1},
+  [74] = {.lex_state = 69, .external_lex_state = 1},
+  [75] = {.lex_state = 69, .external_lex_state = 1},
   [76] = {.lex_state = 343},
-  [77] = {.lex_state = 0},
-  [78] = {.lex_state = 0},
-  [79] = {.lex_state = 0},
-  [80] = {.lex_state = 70},
+  [77] = {.lex_state = 0, .external_lex_state = 1},
+  [78] = {.lex_state = 0, .external_lex_state = 1},
+  [79] = {.lex_state = 0, .external_lex_state = 1},
+  [80] = {.lex_state = 70, .external_lex_state = 1},
   [81] = {(TSStateId)(-1),},
 };
 
@@ -2142,6 +2156,8 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [anon_sym_DASH] = ACTIONS(1),
     [sym_unsigned_integer] = ACTIONS(1),
     [sym_string_content] = ACTIONS(1),
+    [sym_block_comment] = ACTIONS(237),
+    [sym_error_sentinel] = ACTIONS(1),
   },
   [STATE(1)] = {
     [sym_source_file] = STATE(64),
@@ -2154,11 +2170,14 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT] = ACTIONS(3),
     [anon_sym_Hypersignal] = ACTIONS(7),
     [anon_sym_Alongtimeagoina] = ACTIONS(9),
+    [sym_block_comment] = ACTIONS(237),
   },
 };
 
 static const uint16_t ts_small_parse_table[] = {
-  [0] = 12,
+  [0] = 13,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(11), 1,
@@ -2188,7 +2207,9 @@ static const uint16_t ts_small_parse_table[] = {
       sym_char,
       sym_bool,
       sym_integer,
-  [42] = 13,
+  [45] = 14,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(31), 1,
@@ -2219,7 +2240,9 @@ static const uint16_t ts_small_parse_table[] = {
       sym_char,
       sym_bool,
       sym_integer,
-  [86] = 13,
+  [92] = 14,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(33), 1,
@@ -2250,7 +2273,9 @@ static const uint16_t ts_small_parse_table[] = {
       sym_char,
       sym_bool,
       sym_integer,
-  [130] = 11,
+  [139] = 12,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(47), 1,
@@ -2277,7 +2302,9 @@ static const uint16_t ts_small_parse_table[] = {
       sym_char,
       sym_bool,
       sym_integer,
-  [168] = 12,
+  [180] = 13,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(59), 1,
@@ -2305,7 +2332,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [208] = 10,
+  [223] = 11,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(61), 1,
@@ -2329,7 +2358,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [242] = 8,
+  [260] = 9,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(65), 1,
@@ -2349,7 +2380,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [270] = 8,
+  [291] = 9,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(65), 1,
@@ -2369,7 +2402,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [298] = 8,
+  [322] = 9,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(65), 1,
@@ -2389,7 +2424,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [326] = 8,
+  [353] = 9,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(65), 1,
@@ -2409,7 +2446,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [354] = 7,
+  [384] = 8,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(73), 1,
@@ -2428,7 +2467,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [380] = 8,
+  [413] = 9,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(65), 1,
@@ -2448,7 +2489,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Iamabigdealintheresistance_DOT,
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
-  [408] = 4,
+  [444] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(85), 1,
@@ -2463,7 +2506,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [427] = 4,
+  [466] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(89), 1,
@@ -2478,7 +2523,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [446] = 4,
+  [488] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(93), 1,
@@ -2493,7 +2540,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [465] = 4,
+  [510] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(97), 1,
@@ -2508,7 +2557,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [484] = 4,
+  [532] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(101), 1,
@@ -2523,7 +2574,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [503] = 4,
+  [554] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(105), 1,
@@ -2538,7 +2591,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [522] = 4,
+  [576] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(109), 1,
@@ -2553,7 +2608,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [541] = 4,
+  [598] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(113), 1,
@@ -2568,7 +2625,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [560] = 4,
+  [620] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(117), 1,
@@ -2583,7 +2642,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_That_SQUOTEsimpossible_BANG,
       anon_sym_DASH,
       sym_unsigned_integer,
-  [579] = 8,
+  [642] = 9,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(7), 1,
@@ -2601,7 +2662,9 @@ static const uint16_t ts_small_parse_table[] = {
     STATE(61), 2,
       sym_extern_function,
       sym_function_definition,
-  [605] = 3,
+  [671] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(24), 1,
@@ -2614,7 +2677,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [621] = 7,
+  [690] = 8,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(123), 1,
@@ -2631,7 +2696,9 @@ static const uint16_t ts_small_parse_table[] = {
     STATE(61), 2,
       sym_extern_function,
       sym_function_definition,
-  [645] = 6,
+  [717] = 7,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(131), 1,
@@ -2646,7 +2713,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Holotext,
       anon_sym_Credit,
       anon_sym_Signal,
-  [666] = 3,
+  [741] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(27), 1,
@@ -2658,7 +2727,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [681] = 3,
+  [759] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(28), 1,
@@ -2670,7 +2741,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [696] = 6,
+  [777] = 7,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(139), 1,
@@ -2685,7 +2758,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Holotext,
       anon_sym_Credit,
       anon_sym_Signal,
-  [717] = 5,
+  [801] = 6,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(141), 1,
@@ -2699,7 +2774,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Holotext,
       anon_sym_Credit,
       anon_sym_Signal,
-  [736] = 7,
+  [823] = 8,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(61), 1,
@@ -2714,7 +2791,9 @@ static const uint16_t ts_small_parse_table[] = {
       sym_variadic,
     STATE(66), 1,
       sym_function_arguments,
-  [758] = 3,
+  [848] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(32), 1,
@@ -2725,7 +2804,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [772] = 3,
+  [865] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(33), 1,
@@ -2736,7 +2817,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [786] = 3,
+  [882] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(34), 1,
@@ -2747,7 +2830,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [800] = 3,
+  [899] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(35), 1,
@@ -2758,7 +2843,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [814] = 3,
+  [916] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(36), 1,
@@ -2769,7 +2856,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [828] = 3,
+  [933] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(37), 1,
@@ -2780,7 +2869,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [842] = 3,
+  [950] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(38), 1,
@@ -2791,7 +2882,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [856] = 3,
+  [967] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(39), 1,
@@ -2802,7 +2895,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [870] = 3,
+  [984] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(40), 1,
@@ -2813,7 +2908,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [884] = 3,
+  [1001] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(41), 1,
@@ -2824,7 +2921,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [898] = 3,
+  [1018] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(42), 1,
@@ -2835,7 +2934,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [912] = 3,
+  [1035] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(43), 1,
@@ -2846,7 +2947,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [926] = 3,
+  [1052] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(44), 1,
@@ -2857,7 +2960,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Theforceisstrongwiththisone_DOT,
       anon_sym_That_SQUOTEsonehellofapilot_DOT,
       anon_sym_Executeorder,
-  [940] = 5,
+  [1069] = 6,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(158), 1,
@@ -2869,7 +2974,9 @@ static const uint16_t ts_small_parse_table[] = {
     ACTIONS(160), 2,
       sym_string_content,
       sym_escape_sequence,
-  [957] = 5,
+  [1089] = 6,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(162), 1,
@@ -2881,7 +2988,9 @@ static const uint16_t ts_small_parse_table[] = {
     ACTIONS(160), 2,
       sym_string_content,
       sym_escape_sequence,
-  [974] = 4,
+  [1109] = 5,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(164), 1,
@@ -2892,7 +3001,9 @@ static const uint16_t ts_small_parse_table[] = {
     STATE(47), 2,
       sym_line_comment,
       aux_sym_string_repeat1,
-  [989] = 3,
+  [1127] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(48), 1,
@@ -2902,7 +3013,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Holotext,
       anon_sym_Credit,
       anon_sym_Signal,
-  [1002] = 5,
+  [1143] = 6,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(171), 1,
@@ -2914,7 +3027,9 @@ static const uint16_t ts_small_parse_table[] = {
     ACTIONS(160), 2,
       sym_string_content,
       sym_escape_sequence,
-  [1019] = 5,
+  [1163] = 6,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(173), 1,
@@ -2926,7 +3041,9 @@ static const uint16_t ts_small_parse_table[] = {
     ACTIONS(160), 2,
       sym_string_content,
       sym_escape_sequence,
-  [1036] = 3,
+  [1183] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(51), 1,
@@ -2936,7 +3053,9 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_Holotext,
       anon_sym_Credit,
       anon_sym_Signal,
-  [1049] = 3,
+  [1199] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(52), 1,
@@ -2945,7 +3064,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1061] = 3,
+  [1214] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(53), 1,
@@ -2954,7 +3075,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1073] = 3,
+  [1229] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(54), 1,
@@ -2963,7 +3086,9 @@ static const uint16_t ts_small_parse_table[] = {
       sym_string_content,
       anon_sym_DQUOTE2,
       sym_escape_sequence,
-  [1085] = 3,
+  [1244] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(55), 1,
@@ -2972,7 +3097,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1097] = 3,
+  [1259] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(56), 1,
@@ -2981,7 +3108,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1109] = 3,
+  [1274] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(57), 1,
@@ -2990,7 +3119,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1121] = 3,
+  [1289] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(58), 1,
@@ -2999,7 +3130,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1133] = 3,
+  [1304] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(59), 1,
@@ -3008,7 +3141,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1145] = 5,
+  [1319] = 6,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(61), 1,
@@ -3019,7 +3154,9 @@ static const uint16_t ts_small_parse_table[] = {
       sym_line_comment,
     STATE(67), 1,
       sym_function_arguments,
-  [1161] = 3,
+  [1338] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(61), 1,
@@ -3028,7 +3165,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1173] = 3,
+  [1353] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(62), 1,
@@ -3037,7 +3176,9 @@ static const uint16_t ts_small_parse_table[] = {
       ts_builtin_sym_end,
       anon_sym_Hypersignal,
       anon_sym_Alongtimeagoina,
-  [1185] = 3,
+  [1368] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     STATE(63), 1,
@@ -3045,211 +3186,243 @@ static const uint16_t ts_small_parse_table[] = {
     ACTIONS(199), 2,
       anon_sym_Iamyourfather_DOT,
       anon_sym_Judgemebymysize_COMMAdoyou_QMARK,
-  [1196] = 3,
+  [1382] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(201), 1,
       ts_builtin_sym_end,
     STATE(64), 1,
       sym_line_comment,
-  [1206] = 3,
+  [1395] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(203), 1,
       sym_identifier,
     STATE(65), 1,
       sym_line_comment,
-  [1216] = 3,
+  [1408] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(193), 1,
       anon_sym_Jamsignal,
     STATE(66), 1,
       sym_line_comment,
-  [1226] = 3,
+  [1421] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(205), 1,
       anon_sym_Jamsignal,
     STATE(67), 1,
       sym_line_comment,
-  [1236] = 3,
+  [1434] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(207), 1,
       anon_sym_far_COMMAfaraway_DOT_DOT_DOT,
     STATE(68), 1,
       sym_line_comment,
-  [1246] = 3,
+  [1447] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(209), 1,
       sym_identifier,
     STATE(69), 1,
       sym_line_comment,
-  [1256] = 3,
+  [1460] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(211), 1,
       sym_unsigned_integer,
     STATE(70), 1,
       sym_line_comment,
-  [1266] = 3,
+  [1473] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(213), 1,
       aux_sym_char_token1,
     STATE(71), 1,
       sym_line_comment,
-  [1276] = 3,
+  [1486] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(215), 1,
       sym_identifier,
     STATE(72), 1,
       sym_line_comment,
-  [1286] = 3,
+  [1499] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(217), 1,
       anon_sym_SQUOTE,
     STATE(73), 1,
       sym_line_comment,
-  [1296] = 3,
+  [1512] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(219), 1,
       sym_identifier,
     STATE(74), 1,
       sym_line_comment,
-  [1306] = 3,
+  [1525] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(221), 1,
       sym_identifier,
     STATE(75), 1,
       sym_line_comment,
-  [1316] = 3,
+  [1538] = 3,
     ACTIONS(223), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(225), 1,
       aux_sym_line_comment_token1,
     STATE(76), 1,
       sym_line_comment,
-  [1326] = 3,
+  [1548] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(227), 1,
       sym_unsigned_integer,
     STATE(77), 1,
       sym_line_comment,
-  [1336] = 3,
+  [1561] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(229), 1,
       sym_unsigned_integer,
     STATE(78), 1,
       sym_line_comment,
-  [1346] = 3,
+  [1574] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(231), 1,
       anon_sym_SQUOTE,
     STATE(79), 1,
       sym_line_comment,
-  [1356] = 3,
+  [1587] = 4,
+    ACTIONS(237), 1,
+      sym_block_comment,
     ACTIONS(3), 1,
       anon_sym_LT_LPAREN_DASH_DOT_DASH_RPAREN_GT,
     ACTIONS(233), 1,
       aux_sym_char_token1,
     STATE(80), 1,
       sym_line_comment,
-  [1366] = 1,
+  [1600] = 1,
     ACTIONS(235), 1,
       ts_builtin_sym_end,
 };
 
 static const uint32_t ts_small_parse_table_map[] = {
   [SMALL_STATE(2)] = 0,
-  [SMALL_STATE(3)] = 42,
-  [SMALL_STATE(4)] = 86,
-  [SMALL_STATE(5)] = 130,
-  [SMALL_STATE(6)] = 168,
-  [SMALL_STATE(7)] = 208,
-  [SMALL_STATE(8)] = 242,
-  [SMALL_STATE(9)] = 270,
-  [SMALL_STATE(10)] = 298,
-  [SMALL_STATE(11)] = 326,
-  [SMALL_STATE(12)] = 354,
-  [SMALL_STATE(13)] = 380,
-  [SMALL_STATE(14)] = 408,
-  [SMALL_STATE(15)] = 427,
-  [SMALL_STATE(16)] = 446,
-  [SMALL_STATE(17)] = 465,
-  [SMALL_STATE(18)] = 484,
-  [SMALL_STATE(19)] = 503,
-  [SMALL_STATE(20)] = 522,
-  [SMALL_STATE(21)] = 541,
-  [SMALL_STATE(22)] = 560,
-  [SMALL_STATE(23)] = 579,
-  [SMALL_STATE(24)] = 605,
-  [SMALL_STATE(25)] = 621,
-  [SMALL_STATE(26)] = 645,
-  [SMALL_STATE(27)] = 666,
-  [SMALL_STATE(28)] = 681,
-  [SMALL_STATE(29)] = 696,
-  [SMALL_STATE(30)] = 717,
-  [SMALL_STATE(31)] = 736,
-  [SMALL_STATE(32)] = 758,
-  [SMALL_STATE(33)] = 772,
-  [SMALL_STATE(34)] = 786,
-  [SMALL_STATE(35)] = 800,
-  [SMALL_STATE(36)] = 814,
-  [SMALL_STATE(37)] = 828,
-  [SMALL_STATE(38)] = 842,
-  [SMALL_STATE(39)] = 856,
-  [SMALL_STATE(40)] = 870,
-  [SMALL_STATE(41)] = 884,
-  [SMALL_STATE(42)] = 898,
-  [SMALL_STATE(43)] = 912,
-  [SMALL_STATE(44)] = 926,
-  [SMALL_STATE(45)] = 940,
-  [SMALL_STATE(46)] = 957,
-  [SMALL_STATE(47)] = 974,
-  [SMALL_STATE(48)] = 989,
-  [SMALL_STATE(49)] = 1002,
-  [SMALL_STATE(50)] = 1019,
-  [SMALL_STATE(51)] = 1036,
-  [SMALL_STATE(52)] = 1049,
-  [SMALL_STATE(53)] = 1061,
-  [SMALL_STATE(54)] = 1073,
-  [SMALL_STATE(55)] = 1085,
-  [SMALL_STATE(56)] = 1097,
-  [SMALL_STATE(57)] = 1109,
-  [SMALL_STATE(58)] = 1121,
-  [SMALL_STATE(59)] = 1133,
-  [SMALL_STATE(60)] = 1145,
-  [SMALL_STATE(61)] = 1161,
-  [SMALL_STATE(62)] = 1173,
-  [SMALL_STATE(63)] = 1185,
-  [SMALL_STATE(64)] = 1196,
-  [SMALL_STATE(65)] = 1206,
-  [SMALL_STATE(66)] = 1216,
-  [SMALL_STATE(67)] = 1226,
-  [SMALL_STATE(68)] = 1236,
-  [SMALL_STATE(69)] = 1246,
-  [SMALL_STATE(70)] = 1256,
-  [SMALL_STATE(71)] = 1266,
-  [SMALL_STATE(72)] = 1276,
-  [SMALL_STATE(73)] = 1286,
-  [SMALL_STATE(74)] = 1296,
-  [SMALL_STATE(75)] = 1306,
-  [SMALL_STATE(76)] = 1316,
-  [SMALL_STATE(77)] = 1326,
-  [SMALL_STATE(78)] = 1336,
-  [SMALL_STATE(79)] = 1346,
-  [SMALL_STATE(80)] = 1356,
-  [SMALL_STATE(81)] = 1366,
+  [SMALL_STATE(3)] = 45,
+  [SMALL_STATE(4)] = 92,
+  [SMALL_STATE(5)] = 139,
+  [SMALL_STATE(6)] = 180,
+  [SMALL_STATE(7)] = 223,
+  [SMALL_STATE(8)] = 260,
+  [SMALL_STATE(9)] = 291,
+  [SMALL_STATE(10)] = 322,
+  [SMALL_STATE(11)] = 353,
+  [SMALL_STATE(12)] = 384,
+  [SMALL_STATE(13)] = 413,
+  [SMALL_STATE(14)] = 444,
+  [SMALL_STATE(15)] = 466,
+  [SMALL_STATE(16)] = 488,
+  [SMALL_STATE(17)] = 510,
+  [SMALL_STATE(18)] = 532,
+  [SMALL_STATE(19)] = 554,
+  [SMALL_STATE(20)] = 576,
+  [SMALL_STATE(21)] = 598,
+  [SMALL_STATE(22)] = 620,
+  [SMALL_STATE(23)] = 642,
+  [SMALL_STATE(24)] = 671,
+  [SMALL_STATE(25)] = 690,
+  [SMALL_STATE(26)] = 717,
+  [SMALL_STATE(27)] = 741,
+  [SMALL_STATE(28)] = 759,
+  [SMALL_STATE(29)] = 777,
+  [SMALL_STATE(30)] = 801,
+  [SMALL_STATE(31)] = 823,
+  [SMALL_STATE(32)] = 848,
+  [SMALL_STATE(33)] = 865,
+  [SMALL_STATE(34)] = 882,
+  [SMALL_STATE(35)] = 899,
+  [SMALL_STATE(36)] = 916,
+  [SMALL_STATE(37)] = 933,
+  [SMALL_STATE(38)] = 950,
+  [SMALL_STATE(39)] = 967,
+  [SMALL_STATE(40)] = 984,
+  [SMALL_STATE(41)] = 1001,
+  [SMALL_STATE(42)] = 1018,
+  [SMALL_STATE(43)] = 1035,
+  [SMALL_STATE(44)] = 1052,
+  [SMALL_STATE(45)] = 1069,
+  [SMALL_STATE(46)] = 1089,
+  [SMALL_STATE(47)] = 1109,
+  [SMALL_STATE(48)] = 1127,
+  [SMALL_STATE(49)] = 1143,
+  [SMALL_STATE(50)] = 1163,
+  [SMALL_STATE(51)] = 1183,
+  [SMALL_STATE(52)] = 1199,
+  [SMALL_STATE(53)] = 1214,
+  [SMALL_STATE(54)] = 1229,
+  [SMALL_STATE(55)] = 1244,
+  [SMALL_STATE(56)] = 1259,
+  [SMALL_STATE(57)] = 1274,
+  [SMALL_STATE(58)] = 1289,
+  [SMALL_STATE(59)] = 1304,
+  [SMALL_STATE(60)] = 1319,
+  [SMALL_STATE(61)] = 1338,
+  [SMALL_STATE(62)] = 1353,
+  [SMALL_STATE(63)] = 1368,
+  [SMALL_STATE(64)] = 1382,
+  [SMALL_STATE(65)] = 1395,
+  [SMALL_STATE(66)] = 1408,
+  [SMALL_STATE(67)] = 1421,
+  [SMALL_STATE(68)] = 1434,
+  [SMALL_STATE(69)] = 1447,
+  [SMALL_STATE(70)] = 1460,
+  [SMALL_STATE(71)] = 1473,
+  [SMALL_STATE(72)] = 1486,
+  [SMALL_STATE(73)] = 1499,
+  [SMALL_STATE(74)] = 1512,
+  [SMALL_STATE(75)] = 1525,
+  [SMALL_STATE(76)] = 1538,
+  [SMALL_STATE(77)] = 1548,
+  [SMALL_STATE(78)] = 1561,
+  [SMALL_STATE(79)] = 1574,
+  [SMALL_STATE(80)] = 1587,
+  [SMALL_STATE(81)] = 1600,
 };
 
 static const TSParseActionEntry ts_parse_actions[] = {
@@ -3366,19 +3539,33 @@ static const TSParseActionEntry ts_parse_actions[] = {
   [231] = {.entry = {.count = 1, .reusable = true}}, SHIFT(44),
   [233] = {.entry = {.count = 1, .reusable = true}}, SHIFT(79),
   [235] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_line_comment, 2, 0, 0),
+  [237] = {.entry = {.count = 1, .reusable = true}}, SHIFT_EXTRA(),
 };
 
 enum ts_external_scanner_symbol_identifiers {
   ts_external_token_string_content = 0,
+  ts_external_token_block_comment = 1,
+  ts_external_token_error_sentinel = 2,
 };
 
 static const TSSymbol ts_external_scanner_symbol_map[EXTERNAL_TOKEN_COUNT] = {
   [ts_external_token_string_content] = sym_string_content,
+  [ts_external_token_block_comment] = sym_block_comment,
+  [ts_external_token_error_sentinel] = sym_error_sentinel,
 };
 
-static const bool ts_external_scanner_states[2][EXTERNAL_TOKEN_COUNT] = {
+static const bool ts_external_scanner_states[4][EXTERNAL_TOKEN_COUNT] = {
   [1] = {
+    [ts_external_token_block_comment] = true,
+  },
+  [2] = {
+    [ts_external_token_string_content] = true,
+    [ts_external_token_block_comment] = true,
+  },
+  [3] = {
     [ts_external_token_string_content] = true,
+    [ts_external_token_block_comment] = true,
+    [ts_external_token_error_sentinel] = true,
   },
 };
 
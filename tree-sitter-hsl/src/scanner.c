@@ -1,11 +1,17 @@
 #include "tree_sitter/alloc.h"
 #include "tree_sitter/parser.h"
 
+#include <wctype.h>
+
 enum TokenType {
     STRING_CONTENT,
+    BLOCK_COMMENT,
     ERROR_SENTINEL,
 };
 
+static const char BLOCK_COMMENT_OPEN[] = "<(o.o)";
+static const char BLOCK_COMMENT_CLOSE[] = "(o.o)>";
+
 typedef struct {} Scanner;
 
 void *tree_sitter_hsl_external_scanner_create() { return ts_calloc(1, sizeof(Scanner)); }
@@ -26,6 +32,54 @@ void tree_sitter_hsl_external_scanner_deserialize(void *payload, const char *buf
 
 static inline void advance(TSLexer *lexer) { lexer->advance(lexer, false); }
 
+static inline void skip(TSLexer *lexer) { lexer->advance(lexer, true); }
+
+// Advance past `delimiter` if the lookahead starts it. On a mismatch the lexer stays on the
+// mismatching character, no delimiter can start inside the characters already consumed
+static inline bool consume(TSLexer *lexer, const char *delimiter) {
+    for (const char *c = delimiter; *c; c++) {
+        if (lexer->lookahead != *c) {
+            return false;
+        }
+        advance(lexer);
+    }
+    return true;
+}
+
+// Block comments nest, the comment ends once every comment opened in it is closed
+static inline bool process_block_comment(TSLexer *lexer) {
+    while (iswspace(lexer->lookahead)) {
+        skip(lexer);
+    }
+
+    if (!consume(lexer, BLOCK_COMMENT_OPEN)) {
+        return false;
+    }
+
+    unsigned depth = 1;
+    while (depth > 0) {
+        if (lexer->eof(lexer)) {
+            return false;
+        }
+
+        if (lexer->lookahead == BLOCK_COMMENT_OPEN[0]) {
+            if (consume(lexer, BLOCK_COMMENT_OPEN)) {
+                depth++;
+            }
+        } else if (lexer->lookahead == BLOCK_COMMENT_CLOSE[0]) {
+            if (consume(lexer, BLOCK_COMMENT_CLOSE)) {
+                depth--;
+            }
+        } else {
+            advance(lexer);
+        }
+    }
+
+    lexer->result_symbol = BLOCK_COMMENT;
+    lexer->mark_end(lexer);
+    return true;
+}
+
 static inline bool process_string(TSLexer *lexer) {
     bool has_content = false;
     for (;;) {
@@ -61,11 +115,14 @@ bool tree_sitter_hsl_external_scanner_scan(void *payload, TSLexer *lexer, const
       then check whether that token is marked valid to determine whether
       Tree-sitter is in error correction mode.
     */
-    if (valid_symbols[ERROR_SENTINEL]) return false;
+    // The block comments are still skipped so the recovery does not stop on them
+    if (valid_symbols[ERROR_SENTINEL]) return process_block_comment(lexer);
 
     Scanner *scanner = (Scanner *)payload;
 
     if (valid_symbols[STRING_CONTENT]) return process_string(lexer);
 
+    if (valid_symbols[BLOCK_COMMENT]) return process_block_comment(lexer);
+
     return false;
 }